@@ -1,12 +1,63 @@
 import test from 'node:test'
 import assert from 'node:assert'
-import { nokhwaCheck, frameFormats, allKnownCameraControls } from '../index.js'
+import { mkdtempSync, writeFileSync } from 'node:fs'
+import { tmpdir } from 'node:os'
+import { join } from 'node:path'
+import {
+  nokhwaCheck,
+  nokhwaInitializeAsync,
+  frameFormats,
+  allKnownCameraControls,
+  rgbaAverageColor,
+  rgbaSharpness,
+  rgbaDeinterlace,
+  rgbaAdjust,
+  rgbaOverlay,
+  guessFrameFormat,
+  saveCameraConfig,
+  loadCameraConfig,
+  yuyv422ToRgb,
+  bufYuyv422ToNv12,
+  bufNv12ToYuyv422,
+  yuyv422PredictedSize,
+  yuyv422PredictedSizeForResolution,
+  rgbToGray,
+  rgbaToGray,
+  rgbaToRgb,
+  rgbaToJpeg,
+  watchDevices,
+  listCameras,
+  Camera,
+  CameraBuffer,
+  FramePool,
+} from '../index.js'
+
+const cameras = (() => {
+  try {
+    return listCameras()
+  } catch {
+    return []
+  }
+})()
+const noCamera = cameras.length === 0 && 'no camera attached'
 
 test('nokhwa check function should return a boolean', () => {
   const result = nokhwaCheck()
   assert.strictEqual(typeof result, 'boolean')
 })
 
+test('nokhwa initialize should resolve with the grant result', async () => {
+  const granted = await nokhwaInitializeAsync()
+  assert.strictEqual(typeof granted, 'boolean')
+  if (process.platform !== 'darwin') {
+    assert.strictEqual(granted, true)
+  }
+})
+
+test('watch devices should reject a zero interval', () => {
+  assert.throws(() => watchDevices(() => {}, 0), { code: 'InvalidArg' })
+})
+
 test('frame formats function should return a non-empty array', () => {
   const formats = frameFormats()
   assert.strictEqual(Array.isArray(formats), true)
@@ -18,3 +69,248 @@ test('all known camera controls function should return a non-empty array', () =>
   assert.strictEqual(Array.isArray(controls), true)
   assert.ok(controls.length > 0)
 })
+
+test('all known camera controls should map every nokhwa control to a distinct value', () => {
+  const controls = allKnownCameraControls()
+  assert.strictEqual(new Set(controls).size, controls.length)
+  for (const control of ['Brightness', 'Contrast', 'Hue', 'Saturation', 'Sharpness', 'Gamma', 'WhiteBalance']) {
+    assert.ok(controls.includes(control), control)
+  }
+  for (const control of ['BacklightComp', 'Gain', 'Pan', 'Tilt', 'Zoom', 'Exposure', 'Iris', 'Focus']) {
+    assert.ok(controls.includes(control), control)
+  }
+})
+
+test('rgba average color should average the RGB channels of every pixel', () => {
+  const data = Buffer.from([255, 0, 0, 255, 0, 0, 255, 255])
+  const color = rgbaAverageColor(data, 2, 1)
+  assert.deepStrictEqual(color, { r: 127, g: 0, b: 127 })
+})
+
+test('rgba sharpness should be zero for flat images and grow with edges', () => {
+  const flat = Buffer.alloc(4 * 4 * 4, 128)
+  assert.strictEqual(rgbaSharpness(flat, 4, 4), 0)
+
+  const checker = Buffer.alloc(4 * 4 * 4)
+  for (let i = 0; i < 16; i++) {
+    const value = (i % 4) % 2 === Math.floor(i / 4) % 2 ? 255 : 0
+    checker.fill(value, i * 4, i * 4 + 3)
+    checker[i * 4 + 3] = 255
+  }
+  assert.ok(rgbaSharpness(checker, 4, 4) > 0)
+})
+
+test('rgba deinterlace should rebuild frames from the top field', () => {
+  // 1x4 image with one gray value per row
+  const rows = [10, 20, 30, 40]
+  const data = Buffer.from(rows.flatMap((v) => [v, v, v, 255]))
+  const reds = (frame) => Array.from({ length: frame.height }, (_, y) => frame.data[y * 4])
+
+  assert.deepStrictEqual(reds(rgbaDeinterlace(data, 1, 4, 'Bob')), [10, 10, 30, 30])
+  assert.deepStrictEqual(reds(rgbaDeinterlace(data, 1, 4, 'Blend')), [15, 25, 35, 40])
+
+  const discarded = rgbaDeinterlace(data, 1, 4, 'DiscardField')
+  assert.strictEqual(discarded.height, 2)
+  assert.deepStrictEqual(reds(discarded), [10, 30])
+  assert.throws(() => rgbaDeinterlace(data, 1, 3, 'Bob'))
+})
+
+test('rgba adjust should leave neutral settings unchanged and keep alpha', () => {
+  const data = Buffer.from([0, 64, 128, 200, 255, 32, 16, 100])
+  assert.deepStrictEqual(rgbaAdjust(data, 2, 1, 0, 1, 1).data, data)
+
+  const brighter = rgbaAdjust(data, 2, 1, 0.1, 1, 1).data
+  assert.ok(brighter[1] > data[1])
+  assert.strictEqual(brighter[3], 200)
+  assert.strictEqual(brighter[4], 255)
+  assert.throws(() => rgbaAdjust(data, 2, 1, 0, 1, 0))
+})
+
+test('rgba overlay should blend by alpha and opacity and clip to the frame', () => {
+  const base = Buffer.from([0, 0, 0, 255, 0, 0, 0, 255])
+  const overlay = Buffer.from([255, 255, 255, 255, 255, 0, 0, 0])
+
+  const full = rgbaOverlay(base, 2, 1, overlay, 2, 1, 0, 0, 1).data
+  assert.deepStrictEqual(full, Buffer.from([255, 255, 255, 255, 0, 0, 0, 255]))
+
+  const half = rgbaOverlay(base, 2, 1, overlay, 2, 1, 1, 0, 0.5).data
+  assert.deepStrictEqual(half, Buffer.from([0, 0, 0, 255, 128, 128, 128, 255]))
+
+  assert.deepStrictEqual(rgbaOverlay(base, 2, 1, overlay, 2, 1, 5, -3, 1).data, base)
+  assert.throws(() => rgbaOverlay(base, 2, 1, overlay, 2, 1, 0, 0, 1.5))
+})
+
+test('camera buffer from file should validate the size against the format', () => {
+  const path = join(mkdtempSync(join(tmpdir(), 'nokhwa-')), 'frame.yuyv')
+  writeFileSync(path, Buffer.alloc(4 * 2 * 2))
+
+  const buffer = CameraBuffer.fromFile(path, { width: 4, height: 2 }, 'YUYV')
+  assert.strictEqual(buffer.size(), 16)
+  assert.strictEqual(buffer.sourceFrameFormat(), 'YUYV')
+
+  assert.throws(() => CameraBuffer.fromFile(path, { width: 4, height: 2 }, 'RGB'))
+})
+
+test('camera buffer with data should lend the bytes only for the duration of the call', () => {
+  const buffer = new CameraBuffer({ width: 2, height: 1 }, Buffer.from([1, 2, 3, 4]), 'YUYV')
+  let retained
+  buffer.withData((data) => {
+    assert.deepStrictEqual(Array.from(data), [1, 2, 3, 4])
+    retained = data
+  })
+  assert.strictEqual(retained.length, 0)
+  assert.deepStrictEqual(Array.from(buffer.data()), [1, 2, 3, 4])
+})
+
+test('camera buffer data ref should share the bytes and outlive the buffer', () => {
+  let buffer = new CameraBuffer({ width: 2, height: 1 }, Buffer.from([1, 2, 3, 4]), 'YUYV')
+  const view = buffer.dataRef()
+  const second = buffer.dataRef()
+  assert.ok(view instanceof Uint8Array)
+  assert.deepStrictEqual(Array.from(second), [1, 2, 3, 4])
+  buffer.withData((data) => assert.deepStrictEqual(Array.from(data), [1, 2, 3, 4]))
+  buffer = null
+  assert.deepStrictEqual(Array.from(view), [1, 2, 3, 4])
+})
+
+test('pooled frames should view their buffer and return it to the pool on release', () => {
+  const pool = new FramePool(2)
+  const frame = pool.acquire()
+  assert.ok(frame.data() instanceof Uint8Array)
+  assert.strictEqual(frame.data().length, 0)
+  frame.release()
+  assert.strictEqual(pool.idleCount(), 1)
+  assert.throws(() => frame.data(), /released/)
+})
+
+test('camera buffer should decode to RGB and RGBA with its stored resolution', () => {
+  const yuyv = Buffer.from([120, 90, 120, 200])
+  const buffer = new CameraBuffer({ width: 2, height: 1 }, yuyv, 'YUYV')
+  assert.deepStrictEqual(buffer.decodeRgb(), yuyv422ToRgb(yuyv, 2, 1))
+
+  const rgb = new CameraBuffer({ width: 2, height: 1 }, Buffer.from([1, 2, 3, 4, 5, 6]), 'RGB')
+  assert.deepStrictEqual(Array.from(rgb.decodeRgba()), [1, 2, 3, 255, 4, 5, 6, 255])
+
+  const garbage = new CameraBuffer({ width: 2, height: 1 }, Buffer.from([1, 2, 3]), 'MJPEG')
+  assert.throws(() => garbage.decodeRgb())
+})
+
+test('yuyv conversion should default to BT.601 and differ for BT.709', () => {
+  const yuyv = Buffer.from([120, 90, 120, 200])
+  const defaultRgb = yuyv422ToRgb(yuyv, 2, 1)
+  assert.deepStrictEqual(yuyv422ToRgb(yuyv, 2, 1, 'Bt601'), defaultRgb)
+  assert.notDeepStrictEqual(yuyv422ToRgb(yuyv, 2, 1, 'Bt709'), defaultRgb)
+})
+
+test('gray camera buffers should expand each luma byte to opaque RGBA', () => {
+  const gray = new CameraBuffer({ width: 3, height: 1 }, Buffer.from([0, 128, 255]), 'GRAY')
+  assert.deepStrictEqual(Array.from(gray.decodeRgba()), [0, 0, 0, 255, 128, 128, 128, 255, 255, 255, 255, 255])
+  assert.deepStrictEqual(Array.from(gray.decodeRgb()), [0, 0, 0, 128, 128, 128, 255, 255, 255])
+})
+
+test('decode failures should carry the DecodeFailed code', () => {
+  const garbage = new CameraBuffer({ width: 2, height: 1 }, Buffer.from([1, 2, 3]), 'MJPEG')
+  assert.throws(() => garbage.decodeRgba(), { code: 'DecodeFailed' })
+  assert.throws(() => yuyv422ToRgb(Buffer.from([1, 2, 3]), 1, 1, 'Bt709'), { code: 'DecodeFailed' })
+})
+
+test('gray conversion should use Rec.601 weights and validate the size', () => {
+  const rgb = Buffer.from([255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255])
+  const gray = rgbToGray(2, 2, rgb)
+  assert.strictEqual(gray.length, 4)
+  assert.ok(gray[1] > gray[0] && gray[0] > gray[2])
+  assert.strictEqual(gray[3], 255)
+
+  const rgba = Buffer.from([255, 0, 0, 0, 0, 255, 0, 0, 0, 0, 255, 0, 255, 255, 255, 0])
+  assert.deepStrictEqual(rgbaToGray(2, 2, rgba), gray)
+  assert.throws(() => rgbToGray(2, 2, Buffer.alloc(11)), { code: 'InvalidArg' })
+})
+
+test('rgba to rgb should drop the alpha bytes', () => {
+  assert.deepStrictEqual(Array.from(rgbaToRgb(Buffer.from([1, 2, 3, 255, 4, 5, 6, 0]))), [1, 2, 3, 4, 5, 6])
+  assert.throws(() => rgbaToRgb(Buffer.alloc(6)), { code: 'InvalidArg' })
+})
+
+test('jpeg metadata should be embedded as an EXIF APP1 segment', () => {
+  const rgba = Buffer.alloc(8 * 8 * 4, 128)
+  const plain = rgbaToJpeg(rgba, 8, 8, 80)
+  const jpeg = rgbaToJpeg(rgba, 8, 8, 80, { cameraName: 'Test Cam', orientation: 6 })
+  assert.deepStrictEqual(Array.from(jpeg.subarray(0, 2)), [0xff, 0xd8])
+
+  const findApp1 = (data: Buffer) => {
+    for (let offset = 2; data[offset] === 0xff && data[offset + 1] >= 0xe0 && data[offset + 1] <= 0xef; ) {
+      if (data[offset + 1] === 0xe1) return offset
+      offset += 2 + data.readUInt16BE(offset + 2)
+    }
+    return -1
+  }
+  assert.strictEqual(findApp1(plain), -1)
+  const app1 = findApp1(jpeg)
+  assert.ok(app1 > 0)
+  assert.strictEqual(jpeg.toString('latin1', app1 + 4, app1 + 10), 'Exif\0\0')
+
+  const tiff = jpeg.subarray(app1 + 10)
+  assert.strictEqual(tiff.toString('latin1', 0, 2), 'MM')
+  const ifd = tiff.readUInt32BE(4)
+  const entries = Array.from({ length: tiff.readUInt16BE(ifd) }, (_, i) => ifd + 2 + i * 12)
+  const orientation = entries.find((entry) => tiff.readUInt16BE(entry) === 0x0112)
+  assert.ok(orientation !== undefined)
+  assert.strictEqual(tiff.readUInt16BE(orientation + 8), 6)
+
+  assert.throws(() => rgbaToJpeg(rgba, 8, 8, 80, { orientation: 9 }))
+  assert.throws(() => rgbaToJpeg(Buffer.alloc(10), 8, 8, 80), { code: 'InvalidArg' })
+})
+
+test('yuyv and nv12 should convert into each other without touching luma', () => {
+  // 2x2 frame: rows share U/V after averaging
+  const yuyv = Buffer.from([10, 100, 20, 200, 30, 110, 40, 210])
+  const nv12 = bufYuyv422ToNv12(2, 2, yuyv)
+  assert.deepStrictEqual(Array.from(nv12), [10, 20, 30, 40, 105, 205])
+  assert.deepStrictEqual(Array.from(bufNv12ToYuyv422(2, 2, nv12)), [10, 105, 20, 205, 30, 105, 40, 205])
+  assert.throws(() => bufYuyv422ToNv12(2, 2, Buffer.alloc(6)), { code: 'InvalidArg' })
+  assert.throws(() => bufNv12ToYuyv422(3, 2, Buffer.alloc(9)), { code: 'InvalidArg' })
+})
+
+test('yuyv predicted size should follow the buffer length', () => {
+  assert.strictEqual(yuyv422PredictedSize(8), 12)
+  assert.strictEqual(yuyv422PredictedSizeForResolution(2, 2), yuyv422PredictedSize(8))
+})
+
+test('guess frame format should rank the formats matching the buffer size', () => {
+  assert.deepStrictEqual(guessFrameFormat(Buffer.alloc(4 * 2 * 2), 4, 2), ['YUYV'])
+  assert.deepStrictEqual(guessFrameFormat(Buffer.alloc(4 * 2 * 3), 4, 2), ['RGB'])
+  assert.deepStrictEqual(guessFrameFormat(Buffer.alloc(6), 2, 2), ['NV12'])
+  assert.deepStrictEqual(guessFrameFormat(Buffer.from([0xff, 0xd8, 0xff, 0xe0]), 2, 2), ['MJPEG'])
+  assert.deepStrictEqual(guessFrameFormat(Buffer.alloc(5), 2, 2), [])
+})
+
+test('camera configs should round-trip per stable id and keep other entries', () => {
+  const path = join(mkdtempSync(join(tmpdir(), 'nokhwa-')), 'cameras.json')
+  assert.strictEqual(loadCameraConfig('usb-1', path), null)
+
+  saveCameraConfig('usb-1', JSON.stringify({ Brightness: 10 }), path)
+  saveCameraConfig('usb-2', JSON.stringify({ Brightness: 20 }), path)
+  saveCameraConfig('usb-1', JSON.stringify({ Brightness: 30 }), path)
+
+  assert.deepStrictEqual(JSON.parse(loadCameraConfig('usb-1', path)), { Brightness: 30 })
+  assert.deepStrictEqual(JSON.parse(loadCameraConfig('usb-2', path)), { Brightness: 20 })
+  assert.strictEqual(loadCameraConfig('usb-3', path), null)
+  assert.throws(() => saveCameraConfig('usb-1', '{not json', path))
+})
+
+test('frame iterators should work with for await and stop when the loop is left', { skip: noCamera }, async () => {
+  const camera = new Camera(cameras[0].index)
+  const iterator = camera.frameIterator()
+  const seen = []
+  for await (const frame of iterator) {
+    assert.strictEqual(frame.data.length, frame.width * frame.height * 4)
+    seen.push(frame)
+    if (seen.length === 2) break
+  }
+  assert.strictEqual(seen.length, 2)
+  assert.strictEqual(iterator.isRunning(), false)
+
+  const steps = iterator[Symbol.asyncIterator]()
+  assert.deepStrictEqual(steps.return(), { done: true })
+  camera.close()
+})
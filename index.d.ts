@@ -1,64 +1,525 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
-/** Camera instance for capturing frames with full nokhwa functionality */
+/**
+ * Async iterator returned by `FrameIterator[Symbol.asyncIterator]()`
+ * Shares the capture thread of its `FrameIterator`; leaving the loop early
+ * (`break`, `return` or a throw) stops that thread.
+ */
+export declare class AsyncFrameIterator {
+  /** Wait for the next frame; `done` is set once the capture thread has stopped */
+  next(): Promise<FrameIteratorResult>
+  /** Stop the capture thread, called by `for await` when the loop is left early */
+  return(): FrameIteratorResult
+}
+
+/**
+ * Camera instance for capturing frames with full nokhwa functionality
+ *
+ * All methods are serialized through an internal lock on the device, so
+ * overlapping calls (e.g. from async tasks) run one after another. A call that
+ * cannot get the device within two seconds fails with a "busy" error.
+ */
 export declare class Camera {
   /**
    * Create a new camera instance with the given index
-   * The camera stream is opened immediately with automatic format detection
+   * The camera stream is opened immediately with automatic format detection.
+   * Pass `frame_format` to skip detection and open the device with exactly that
+   * source format, failing fast if it isn't supported.
+   * Throws with code `NotInitialized` if camera access still has to be
+   * initialized via nokhwa_initialize_async (macOS), and `PermissionDenied` if the OS
+   * refuses access to the device (e.g. user not in the `video` group on Linux).
+   * With `retry`, opening is retried while the device is busy (code
+   * `DeviceBusy`); permanent failures are thrown right away.
    */
-  constructor(cameraIndex: string)
+  constructor(cameraIndex: string, frameFormat?: FrameFormat | undefined | null, retry?: RetryConfig | undefined | null)
   /**
    * Create a new camera instance with the given index and format configuration
    * The camera stream is opened immediately with the specified format
+   * Request type `None` opens whatever format the device offers first, the
+   * quickest way to open a camera; check camera_format() for the result.
    */
   static newWithFormat(cameraIndex: string, formatConfig: RequestedFormatConfig): Camera
+  /**
+   * Create a new camera instance pinned to a specific backend
+   * Uses the same automatic format detection as the constructor, but never lets
+   * nokhwa pick another backend (e.g. to force Video4Linux on Linux).
+   */
+  static newWithBackend(cameraIndex: string, backend: ApiBackend): Camera
+  /**
+   * Open the first camera whose human-readable name matches `name`
+   * With `exact`, the name must match exactly and be unique among connected devices.
+   * Otherwise a case-insensitive substring match is used and the first match wins.
+   */
+  static fromName(name: string, exact: boolean): Camera
   /**
    * Capture a single frame from the camera
    * Returns the frame as RGBA buffer with width and height
+   * Frames whose size doesn't match the reported resolution are corrected when
+   * the real size can be inferred, otherwise fail with code `ResolutionMismatch`.
+   * With `timeout_ms`, waiting for the device gives up after that long with code
+   * `Timeout`. The device stays locked until the late frame arrives, which is
+   * then discarded, so other calls wait meanwhile (and fail as busy after two seconds).
+   */
+  captureFrame(timeoutMs?: number | undefined | null): Frame
+  /**
+   * Capture a frame and scale it to `width`x`height` with a triangle (bilinear) filter
+   * With `keep_aspect_ratio` the frame is instead scaled to the largest size
+   * that fits in `width`x`height` without distorting it, so one side may come
+   * out smaller; check the returned frame's dimensions. A zero width or height
+   * throws with code `InvalidArg`.
+   */
+  captureFrameResized(width: number, height: number, keepAspectRatio?: boolean | undefined | null): Frame
+  /**
+   * Capture a frame decoded to `format`: RGB (3 bytes per pixel), RGBA (4) or GRAY (1)
+   * RGB and GRAY are decoded directly, skipping the RGBA conversion and its
+   * bandwidth; GRAY takes YUYV/NV12 luma as is and Rec.601 luma otherwise.
+   * Deinterlacing, the transform, the region of interest and the output origin
+   * apply as for `capture_frame`. With a software adjustment or overlay set,
+   * the frame goes through RGBA for those and is converted afterwards. Other
+   * formats throw with code `InvalidArg`.
+   */
+  captureFrameAs(format: FrameFormat): Frame
+  /**
+   * Capture a single frame without blocking the event loop
+   * Waiting for the device and decoding run on the libuv thread pool; the
+   * promise resolves to the same RGBA `Frame` as `captureFrame()`, with the same
+   * settings except the decode cache. Overlapping calls are serialized on the
+   * device lock: each waits for the previous capture, and a call that can't
+   * get the device within two seconds rejects with code `DeviceBusy`. Errors
+   * carry the same codes as the synchronous capture.
+   */
+  captureFrameAsync(): Promise<Frame>
+  /**
+   * Capture a frame into a buffer taken from `pool`
+   * The frame's buffer is reused across captures once it is released back to
+   * the pool via `release()` (or garbage collection).
+   */
+  captureIntoPooled(pool: FramePool): PooledFrame
+  /**
+   * Capture an RGBA frame into `buffer`, e.g. one allocation reused by a render loop
+   * Returns the frame's resolution; the frame fills the first `width * height * 4`
+   * bytes and the rest of `buffer` is left untouched. A buffer that is too small
+   * throws with code `InvalidArg` before anything is written. The same settings
+   * as `capture_frame` apply, except that each frame is decoded afresh.
+   */
+  captureFrameInto(buffer: Uint8Array): Resolution
+  /**
+   * Capture a frame shaped for the browser `ImageData` constructor
+   * The data is guaranteed to be `width * height * 4` bytes of tightly packed
+   * RGBA with no padding, in the sRGB color space.
    */
-  captureFrame(): Frame
+  captureImageData(): ImageDataFrame
+  /**
+   * Capture only the `width`x`height` region at (`x`, `y`), e.g. a QR code zone
+   * For YUYV, NV12, RGB and GRAY sources only the region's pixels are decoded,
+   * which is much cheaper than decoding the full frame on large sensors. MJPEG
+   * frames are always decoded in full and then cropped. Coordinates are in
+   * the camera image (top-left origin); the output origin setting still applies.
+   */
+  captureRegion(x: number, y: number, width: number, height: number): Frame
+  /**
+   * Configure the steps `capture_processed()` applies to every frame, in order
+   * Steps are `Resize`, `Crop`, `Flip`, `Rotate`, `Grayscale`, and at the end
+   * optionally `EncodeJpeg` or `EncodePng`. The pipeline is validated here, so
+   * misplaced encoders or bad parameters throw with code `InvalidArg` before
+   * any frame is captured.
+   */
+  setOutputPipeline(ops: Array<FrameOp>): void
+  /**
+   * Capture a frame and run it through the output pipeline
+   * Returns the encoded image when the pipeline ends with an encode step,
+   * otherwise the raw pixels (RGBA, or one byte per pixel after `Grayscale`).
+   */
+  captureProcessed(): Buffer
+  /**
+   * Capture a frame as planar I420 (YUV 4:2:0), the usual input of video encoders
+   * Uses the camera's color space; `u` and `v` are `width / 2 * height / 2`
+   * bytes each, so the frame dimensions must be even.
+   */
+  captureI420(): I420Frame
+  /**
+   * Capture a frame encoded as JPEG at `quality` (1-100)
+   * Pass `metadata` to embed a timestamp, camera name and orientation as EXIF
+   * tags; without it no EXIF segment is written, keeping the output smallest.
+   */
+  captureJpeg(quality: number, metadata?: JpegMetadata | undefined | null): Buffer
+  /**
+   * Capture a frame and write it to `path` as an image file
+   * The format follows the extension: `.png`, `.jpg`/`.jpeg`
+   * (quality 90) or `.bmp`; any other extension fails before capturing.
+   * `metadata` is embedded as EXIF tags like in `capture_jpeg`; only JPEG files
+   * carry it, so passing it for another format throws with code `InvalidArg`.
+   * Missing parent directories are created.
+   */
+  saveFrame(path: string, metadata?: JpegMetadata | undefined | null): void
+  /**
+   * Capture a frame at a wall-clock time given as Unix milliseconds
+   * Blocks until the target time using a precise sleep, then captures. Fails if
+   * the target is more than 50ms in the past. With NTP-synced clocks this gives
+   * rough synchronization of captures across machines.
+   */
+  captureAt(unixMs: number): Frame
+  /** Capture a frame and compute its average color */
+  captureAverageColor(): RgbColor
+  /**
+   * Capture a frame and compute its sharpness (variance of the Laplacian)
+   * Higher is sharper; compare values while adjusting the `Focus` control.
+   */
+  captureSharpness(): number
+  /** Capture a frame and compute the mean luma of each cell in a `cols`x`rows` grid */
+  captureZoneBrightness(cols: number, rows: number): Array<number>
+  /**
+   * Capture a frame and render it as ASCII art `cols` characters wide
+   * Handy to check that a camera works on a headless machine
+   */
+  captureAscii(cols: number): string
+  /**
+   * Set where row 0 of captured frames is placed
+   * `BottomLeft` flips frames vertically, matching WebGL/canvas texture
+   * orientation. Applies to the capture methods returning decoded frames.
+   */
+  setOutputOrigin(origin: ImageOrigin): void
+  /** Get the current output origin */
+  outputOrigin(): ImageOrigin
+  /**
+   * Mirror and rotate captured frames, e.g. for a mirrored webcam or one mounted sideways
+   * The flips are applied first, then the clockwise rotation, which must be
+   * 0, 90, 180 or 270 degrees (other values throw with code `InvalidArg`); 90
+   * and 270 swap the frame's width and height. Applies to the capture methods
+   * returning full decoded frames, before any software adjustment or overlay.
+   */
+  setTransform(flipHorizontal: boolean, flipVertical: boolean, rotate: number): void
+  /** Get the current mirroring and rotation */
+  transform(): FrameTransform
+  /**
+   * Crop captured frames to the `width`x`height` region at (`x`, `y`)
+   * Coordinates are in the frame after `set_transform`, and the region must fit
+   * in the current resolution (throws with code `InvalidArg` otherwise). Unlike
+   * `capture_region` the full frame is still decoded; only the cropped pixels
+   * are copied to JavaScript. The adjust and overlay settings apply to the crop.
+   */
+  setRegionOfInterest(x: number, y: number, width: number, height: number): void
+  /** Stop cropping captured frames, see `set_region_of_interest` */
+  clearRegionOfInterest(): void
+  /**
+   * Deinterlace captured frames, e.g. from analog capture cards, or pass null to stop
+   * Applies to the capture methods returning full decoded frames (not regions).
+   * With `DiscardField` frames come back half as tall as the camera resolution.
+   */
+  setDeinterlace(mode?: DeinterlaceMode | undefined | null): void
+  /** Get the current deinterlace mode, null when frames are passed through */
+  deinterlace(): DeinterlaceMode | null
+  /**
+   * Adjust brightness (-1 to 1), contrast (0 and up) and gamma (above 0) in software
+   * A fallback for cameras without those hardware controls, applied through a
+   * 256-entry lookup table to every frame the capture methods decode. The
+   * neutral values 0, 1, 1 turn the adjustment off; values out of range throw
+   * with code `InvalidArg`.
+   */
+  setSoftwareAdjust(brightness: number, contrast: number, gamma: number): void
+  /**
+   * Composite an RGBA image (e.g. a logo) onto every frame the capture methods decode
+   * The overlay's top-left corner goes to (`x`, `y`) in the frame, measured
+   * from the top left before any `BottomLeft` flip; parts outside the frame are
+   * clipped. Its alpha channel is respected and scaled by `opacity` (0 to 1).
+   * A buffer that doesn't match `width`x`height` or an opacity out of range
+   * throws with code `InvalidArg`.
+   */
+  setOverlay(data: Buffer, width: number, height: number, x: number, y: number, opacity: number): void
+  /** Stop compositing the overlay set with `set_overlay` */
+  clearOverlay(): void
+  /**
+   * Choose between in-order frames (`Balanced`, default) and always the newest one
+   * `LowestLatency` drops frames that were already buffered by the driver
+   * before each capture, for interactive previews where lag matters more than
+   * throughput. Applies to the capture methods returning decoded frames.
+   */
+  setLatencyMode(mode: LatencyMode): void
+  /** Get the current latency mode */
+  latencyMode(): LatencyMode
+  /**
+   * Set the YUV color matrix used when decoding YUYV and NV12 frames
+   * Defaults to BT.601; HD cameras usually need BT.709 for accurate colors
+   */
+  setColorSpace(colorSpace: ColorSpace): void
+  /** Get the YUV color matrix used for decoding */
+  colorSpace(): ColorSpace
+  /**
+   * Enable or disable reuse of the last decoded frame when the camera repeats it
+   * When enabled, identical source frames (static scene, paused device) skip
+   * decoding. This keeps one extra decoded frame in memory; disabling drops it.
+   */
+  enableDecodeCache(enabled: boolean): void
   /** Get the camera index */
   index(): string
   /** Get the backend being used */
   backend(): ApiBackend
   /** Get camera information */
   info(): CameraDevice
+  /**
+   * Get index, name, backend, stream state and format in a single call
+   * `measuredFps` reflects captures made through this object (not background
+   * streams) and is null until two frames have been captured close together.
+   */
+  status(): CameraStatus
   /**
    * Get the current camera format
    * Note: This returns the requested frame rate. Use refresh_camera_format()
    * to get the actual active frame rate from the camera.
    */
   cameraFormat(): CameraFormat
+  /**
+   * Get the resolution of the negotiated format, e.g. to size buffers before capturing
+   * Frames may still come out differently sized after deinterlacing, rotation
+   * or a region of interest.
+   */
+  resolution(): Resolution
+  /** Get the pixel format the device delivers in the negotiated format */
+  frameFormat(): FrameFormat
   /** Refresh and get the camera format */
   refreshCameraFormat(): CameraFormat
-  /** Set camera format with requested configuration */
+  /**
+   * Set camera format with requested configuration
+   * With `width`, `height` or `frameRate` set, the device format closest to
+   * them is picked (e.g. 1280x720 at 30fps); otherwise formats are ranked by
+   * the request type. Returns the format the camera actually negotiated.
+   */
   setCameraRequest(request: RequestedFormatConfig): CameraFormat
+  /**
+   * Change only the frame rate, keeping the current resolution and pixel format
+   * The device mode with the rate nearest to `fps` is picked, e.g. 30 when
+   * asking for 25 from a camera offering 15 and 30. Returns the negotiated
+   * format; throws with code `FormatUnsupported` if the device lists no mode
+   * at the current resolution and pixel format, or none within 25% of `fps`.
+   */
+  setFrameRate(fps: number): CameraFormat
+  /**
+   * Change only the resolution, keeping the current pixel format and frame rate
+   * The device mode nearest to `width`x`height` is picked, preferring the
+   * current frame rate among modes of that size. Returns the negotiated format,
+   * so check it when the device may not offer the exact resolution.
+   */
+  setResolution(width: number, height: number): CameraFormat
   /** Get compatible camera formats */
   compatibleCameraFormats(): Array<CameraFormat>
+  /**
+   * Get compatible camera formats in a deterministic "best first" order
+   * Sorted by resolution (largest first), then frame rate (highest first), then
+   * by position in `prefer`; formats missing from `prefer` come last.
+   */
+  compatibleFormatsSorted(prefer: Array<FrameFormat>): Array<CameraFormat>
+  /**
+   * Get compatible camera formats nested by pixel format, then resolution
+   * Formats keep the order the device reports them in; within each, resolutions
+   * are sorted highest pixel count first and list their distinct frame rates.
+   */
+  compatibleFormatsGrouped(): Array<FormatGroup>
+  /**
+   * Get the exact frame rate as a fraction, e.g. 30000/1001 for 29.97 fps
+   * `camera_format().frame_rate` is rounded to an integer. On Video4Linux the
+   * driver's frame interval is read; other backends only report the integer
+   * rate, which is returned as `fps/1`.
+   */
+  frameInterval(): FrameInterval
+  /**
+   * Get the distinct frame rates the device supports at `width`x`height`, lowest first
+   * Rates offered in several pixel formats are only listed once.
+   */
+  frameRatesForResolution(width: number, height: number): Array<number>
+  /** Get the distinct resolutions the device supports, highest pixel count first */
+  supportedResolutions(): Array<Resolution>
   /** Get supported camera controls */
   supportedCameraControls(): Array<KnownCameraControl>
+  /** Get the ids of the supported vendor-specific controls, i.e. the `Other` entries */
+  supportedOtherControls(): Array<string>
+  /**
+   * Check if the device supports a control, e.g. to enable or disable its slider
+   * With `other_id`, `Other` matches only that vendor control, otherwise any of them.
+   */
+  isControlSupported(control: KnownCameraControl, otherId?: string | undefined | null): boolean
   /** Get all camera controls */
   cameraControls(): Array<CameraControl>
+  /**
+   * Get the descriptor of a single control, including its current value
+   * Fails naming the control if the device doesn't support it.
+   */
+  getCameraControl(control: KnownCameraControl, otherId?: string | undefined | null): CameraControl
+  /**
+   * Read the current value of every supported control
+   * Controls whose value can't be read, or has no JS representation, are skipped
+   */
+  controlsSnapshot(): Array<ControlSnapshot>
   /** Set a camera control value */
-  setCameraControl(control: KnownCameraControl, value: ControlValueSetter): void
+  setCameraControl(control: KnownCameraControl, value: ControlValueSetter, otherId?: string | undefined | null): void
+  /**
+   * Set several controls in one go, e.g. to apply a saved profile
+   * The camera stays locked for the whole batch so no capture lands between the
+   * writes. A control that fails is reported in its entry and the rest are still
+   * applied; the results are in the order of `controls`.
+   */
+  setCameraControls(controls: Array<ControlSetting>): Array<ControlSetResult>
+  /**
+   * Serialize the current value of every readable control as a JSON preset
+   * Restore it later, or on another camera of the same model, with `import_controls`.
+   */
+  exportControls(): string
+  /**
+   * Apply a JSON preset written by `export_controls`
+   * Entries this version can't read are skipped with a warning instead of
+   * failing the import; the others are applied as by `set_camera_controls`.
+   */
+  importControls(json: string): ControlsImport
+  /**
+   * Set a camera control value and return the value the camera actually holds afterwards
+   * Cameras snap requests to their supported steps (e.g. 55 becomes 50), so the
+   * control is read back after writing instead of echoing the request.
+   */
+  setCameraControlChecked(control: KnownCameraControl, value: ControlValueSetter, otherId?: string | undefined | null): ControlValueSetter
+  /**
+   * Read a control's current value mapped linearly onto 0.0-1.0 of its native min/max
+   * Lets one slider drive controls whose native units differ between cameras.
+   * Boolean controls read as 0 or 1; controls without a range are an error.
+   */
+  getControlNormalized(control: KnownCameraControl, otherId?: string | undefined | null): number
+  /**
+   * Set a control from a 0.0-1.0 value mapped linearly onto its native min/max
+   * The value is clamped to 0.0-1.0 and snapped to the control's step.
+   */
+  setControlNormalized(control: KnownCameraControl, value: number, otherId?: string | undefined | null): void
+  /**
+   * Let auto white balance settle over a few frames, then lock it at that value
+   * Keeps colors consistent across a capture session. Throws if the camera
+   * has no manual white balance control.
+   */
+  lockWhiteBalance(): void
+  /**
+   * Capture as fast as possible for `duration_ms` and compare the real frame rate with the advertised one
+   * USB bandwidth or lighting often keep cameras below their nominal rate. The
+   * device stays locked for the whole run, so other calls on this camera wait
+   * (and fail as busy after two seconds).
+   */
+  benchmarkFps(durationMs: number): FpsBenchmark
+  /**
+   * Stop the stream and release the device right away instead of on garbage collection
+   * Use this when another process or `Camera` needs the device. Background
+   * streams end, and later calls on this object throw with code `CameraClosed`.
+   * Closing twice is harmless.
+   */
+  close(): void
+  /**
+   * Reopen the device after it was unplugged or stopped responding
+   * Opens the same index and backend again with automatic format detection, as
+   * the constructor does, and starts its stream. Streams that ended with a
+   * `Disconnected` error have to be started again afterwards. Throws like the
+   * constructor while the device isn't back yet; the call can be retried.
+   */
+  reconnect(): void
+  /** Check if `close()` has released the camera */
+  isClosed(): boolean
   /** Check if stream is open */
   isStreamOpen(): boolean
   /** Open the camera stream */
   openStream(): void
   /** Stop the camera stream */
   stopStream(): void
-  /** Get raw frame data */
-  frameRaw(): CameraBuffer
+  /**
+   * Start streaming decoded RGBA frames to a callback
+   * A native thread captures at the device's own rate and calls `callback` with
+   * `{ data, width, height, fps, dropped, total }` for every frame, using this camera's color
+   * space and output origin. Capture errors are passed as the callback's first
+   * argument. Call `stop()` on the returned handle to end the stream; dropping
+   * the handle stops and joins the thread as well.
+   */
+  startStream(callback: ((err: Error | null, arg: StreamFrame) => unknown), config?: StreamConfig | undefined | null): StreamHandle
+  /**
+   * Start streaming RGBA frames through a fixed ring of `capacity` reusable buffers
+   * Like `start_stream`, but frames are decoded straight into the ring's slots
+   * instead of a new buffer each. The callback gets `{ slot, data, width,
+   * height, fps, timestampMs }` where `data` views the slot's memory: it is
+   * detached once the callback returns, so copy out anything kept longer. A
+   * frame arriving while its slot is still in a callback is dropped and
+   * counted in `droppedFrames()`.
+   */
+  startRingStream(capacity: number, callback: ((err: Error | null, arg: RingFrame) => unknown), config?: StreamConfig | undefined | null): StreamHandle
+  /**
+   * Start streaming JPEG frames to a callback, e.g. for `multipart/x-mixed-replace` HTTP endpoints
+   * Capture and encoding run on a native thread at up to `fps` frames per second.
+   * MJPEG sources are passed through without re-encoding; other formats are
+   * encoded at `quality` (1-100). Capture errors are passed as the callback's
+   * first argument. Call `stop()` on the returned handle to end the stream.
+   * `config` tunes the native capture thread (priority, CPU affinity) and how many
+   * frames may queue up for a slow callback before `queuePolicy` drops them.
+   */
+  mjpegStream(quality: number, fps: number, callback: ((err: Error | null, arg: Buffer) => unknown), config?: StreamConfig | undefined | null): StreamHandle
+  /**
+   * Prepare one capture thread at `fps` that feeds several outputs at once
+   * Register sinks with `onFrame`, `recordTo` and `mjpegTo`, then `start()`.
+   * Frames are captured and decoded once for all of them, with this camera's
+   * color space; the output origin applies to `onFrame` only.
+   */
+  streamBuilder(fps: number, config?: StreamConfig | undefined | null): StreamBuilder
+  /**
+   * Start a pull-based frame stream
+   * Each `next()` resolves to the next RGBA frame (with the current origin and
+   * color space settings), or `null` once the iterator is stopped:
+   * `let f; while ((f = await it.next()) !== null) { ... }`, or
+   * `for await (const f of cam.frameIterator()) { ... }`
+   */
+  frameIterator(): FrameIterator & AsyncIterable<Frame>
+  /**
+   * Record `duration_ms` of video at `fps` to a YUV4MPEG2 (.y4m) file
+   * Frames are captured and written on a native thread; convert the result with
+   * e.g. `ffmpeg -i out.y4m out.mp4`. Call `stop()` on the handle to end early.
+   */
+  recordToY4M(path: string, durationMs: number, fps: number): RecordingHandle
+  /**
+   * Get raw frame data
+   * Fails with code `InvalidResolution` if the camera keeps reporting a 0x0 size.
+   * `timeout_ms` works as for `capture_frame`.
+   */
+  frameRaw(timeoutMs?: number | undefined | null): CameraBuffer
 }
 
 /** Buffer struct representing raw camera frame data */
 export declare class CameraBuffer {
   /** Create a new buffer with resolution, data, and format */
   constructor(resolution: Resolution, data: Buffer, sourceFrameFormat: FrameFormat)
+  /**
+   * Load a raw frame from disk, e.g. a fixture saved from `frame_raw()`
+   * The file size must match the size implied by `resolution` and `format`;
+   * MJPEG frames are variable-length and only have to be non-empty.
+   */
+  static fromFile(path: string, resolution: Resolution, format: FrameFormat): CameraBuffer
   /** Get the resolution of the buffer */
   resolution(): Resolution
   /** Get the raw buffer data */
   data(): Buffer
+  /**
+   * Get the raw buffer data as a `Uint8Array` over the same memory, without copying
+   * The view holds its own reference to the bytes, so it stays valid after this
+   * `CameraBuffer` is dropped or garbage collected; the memory is freed once
+   * both are gone. The view is read-only by contract: the bytes are shared with
+   * this buffer and every other view of it, so don't write to it. Runtimes
+   * without external buffers (e.g. Electron) get a copy instead.
+   */
+  dataRef(): Uint8Array
+  /**
+   * Call `callback` with a `Uint8Array` viewing the frame bytes in place, without copying them
+   * The view is only valid while the callback runs: it must not be kept, stored
+   * or used from a later tick, and must not be written to. Once the callback
+   * returns the view is detached, so a retained reference reads as empty rather
+   * than seeing freed memory. Runtimes without external buffers (e.g. Electron)
+   * get a copy instead.
+   */
+  withData(callback: (data: Uint8Array) => void): void
+  /**
+   * Decode the frame to RGB, 3 bytes per pixel
+   * Uses the same decoders as `Camera::capture_frame` with the stored
+   * resolution (BT.601 for YUV formats); undecodable data throws.
+   */
+  decodeRgb(): Buffer
+  /** Decode the frame to RGBA, 4 bytes per pixel, see `decode_rgb` */
+  decodeRgba(): Buffer
   /** Get the source frame format */
   sourceFrameFormat(): FrameFormat
   /** Get the width of the buffer */
@@ -71,6 +532,159 @@ export declare class CameraBuffer {
   isEmpty(): boolean
 }
 
+/** Several cameras captured together, see `CameraGroup::capture_all` */
+export declare class CameraGroup {
+  /**
+   * Open every camera in `indices` with automatic format detection
+   * Throws if any camera fails to open; the ones already opened are released.
+   */
+  constructor(indices: Array<string>)
+  /** Get the indices of the cameras, in the order `capture_all` reports them */
+  indices(): Array<string>
+  /**
+   * Capture one RGBA frame from every camera at once
+   * Each camera is read on its own thread and the call returns when all are
+   * done. A camera that fails is reported in its entry instead of failing the
+   * batch. Compare the frames' `timestampMs` to measure the skew between them.
+   */
+  captureAll(): Array<GroupCapture>
+  /**
+   * Stop every camera's stream and release the devices
+   * Later captures report code `CameraClosed` for each camera.
+   */
+  close(): void
+}
+
+/**
+ * Handle to a running device watcher, see `watch_devices`
+ * Dropping the handle stops the watcher as well
+ */
+export declare class DeviceWatcher {
+  /** Stop watching and wait for the polling thread to exit */
+  stop(): void
+  /** Check if the watcher is still running */
+  isRunning(): boolean
+}
+
+/**
+ * Pull-based frame stream backed by a native capture thread
+ * `next()` resolves to the next frame, or `null` once the iterator is stopped.
+ */
+export declare class FrameIterator {
+  /**
+   * Wait for the next frame without blocking the event loop
+   * Resolves to `null` after `stop()` or once the camera has been released;
+   * capture errors reject the promise with their `err.code` but don't end the
+   * iteration.
+   */
+  next(): Promise<Frame | null>
+  /** Stop the capture thread; later `next()` calls resolve to `null` */
+  stop(): void
+  /** Check if the iterator is still producing frames */
+  isRunning(): boolean
+}
+
+/** Pool of reusable frame buffers for `Camera.captureIntoPooled()` */
+export declare class FramePool {
+  /** Create a pool that keeps up to `capacity` idle buffers (default 4) */
+  constructor(capacity?: number | undefined | null)
+  /** Take an empty frame from the pool, reusing an idle buffer when one is available */
+  acquire(): PooledFrame
+  /** Number of idle buffers currently held by the pool */
+  idleCount(): number
+}
+
+/** RGBA frame whose buffer goes back to its pool on `release()` or garbage collection */
+export declare class PooledFrame {
+  /**
+   * Get a `Uint8Array` viewing the RGBA data in place, without copying it
+   * The view holds on to the bytes, so it stays valid after `release()`; a
+   * buffer that is still viewed then isn't put back into the pool. The view is
+   * read-only by contract. Fails once the frame has been released.
+   */
+  data(): Uint8Array
+  /** Get the frame width */
+  width(): number
+  /** Get the frame height */
+  height(): number
+  /** Return the buffer to the pool; the frame is unusable afterwards */
+  release(): void
+}
+
+/**
+ * Handle to a running recording
+ * Dropping the handle stops the recording as well
+ */
+export declare class RecordingHandle {
+  /** Stop recording early and wait for the file to be flushed */
+  stop(): void
+  /** Check if the recording is still in progress */
+  isRunning(): boolean
+  /** Number of frames written so far */
+  framesWritten(): number
+  /** Get the error that ended the recording, if any */
+  error(): string | null
+}
+
+/**
+ * Collects several outputs for one shared capture thread
+ * Every frame is pulled from the device once and decoded at most once, then
+ * handed to all registered sinks. Register sinks, then call `start()`.
+ */
+export declare class StreamBuilder {
+  /** Deliver every frame as RGBA (with the camera's origin setting) to `callback` */
+  onFrame(callback: ((err: Error | null, arg: Frame) => unknown)): void
+  /**
+   * Append every frame to a YUV4MPEG2 (.y4m) file at the stream's frame rate
+   * The file is created right away, so path errors throw here.
+   */
+  recordTo(path: string): void
+  /** Deliver every frame as JPEG to `callback`, encoded at `quality` unless the source is MJPEG */
+  mjpegTo(callback: ((err: Error | null, arg: Buffer) => unknown), quality: number): void
+  /**
+   * Start the capture thread driving all registered sinks
+   * The sinks move to the returned handle; the builder is empty afterwards.
+   */
+  start(): StreamHandle
+}
+
+/**
+ * Handle to a running background stream
+ * Dropping the handle stops the stream as well
+ */
+export declare class StreamHandle {
+  /** Stop the stream and wait for its capture thread to exit */
+  stop(): void
+  /** Check if the stream is still running */
+  isRunning(): boolean
+  /**
+   * Stop capturing and delivering frames while keeping the device streaming
+   * The capture thread sleeps without reading from or locking the camera, so
+   * other calls can use it meanwhile. The first frames after resume() may be
+   * ones the driver buffered before the pause.
+   */
+  pause(): void
+  /** Resume delivering frames after pause() */
+  resume(): void
+  /** Check if frame delivery is paused */
+  isPaused(): boolean
+  /**
+   * Check if the stream ended because the device stopped delivering frames
+   * The callback got a final error with code `Disconnected`; call
+   * `camera.reconnect()` and start a new stream to recover.
+   */
+  isDisconnected(): boolean
+  /** Get the message of the most recent capture error, if any */
+  lastError(): string | null
+  /**
+   * Get the most recent capture error with its code and timestamp, if any
+   * The error is kept after later frames succeed, so check `timestampMs` to see how old it is.
+   */
+  lastErrorInfo(): StreamErrorInfo | null
+  /** Number of frames discarded because the callback couldn't keep up */
+  droppedFrames(): number
+}
+
 /** Get all known camera controls */
 export declare function allKnownCameraControls(): Array<KnownCameraControl>
 
@@ -80,7 +694,11 @@ export declare const enum ApiBackend {
   MediaFoundation = 'MediaFoundation',
   AVFoundation = 'AVFoundation',
   OpenCv = 'OpenCv',
-  Browser = 'Browser'
+  Browser = 'Browser',
+  Video4Linux = 'Video4Linux',
+  UniversalVideoClass = 'UniversalVideoClass',
+  GStreamer = 'GStreamer',
+  Network = 'Network'
 }
 
 /** Convert BGR buffer to RGB */
@@ -89,16 +707,49 @@ export declare function bufBgrToRgb(width: number, height: number, bgr: Buffer):
 /** Convert MJPEG buffer to RGB */
 export declare function bufMjpegToRgb(width: number, height: number, mjpeg: Buffer): Buffer
 
-/** Convert NV12 buffer to RGB */
-export declare function bufNv12ToRgb(width: number, height: number, nv12: Buffer): Buffer
+/**
+ * Convert NV12 buffer to RGB
+ * `color_space` selects the YUV matrix (default BT.601)
+ */
+export declare function bufNv12ToRgb(width: number, height: number, nv12: Buffer, colorSpace?: ColorSpace | undefined | null): Buffer
+
+/**
+ * Convert an NV12 buffer to YUYV422 directly in YUV space
+ * Width and height must be even.
+ * A wrong buffer size or odd dimensions throw with code `InvalidArg`.
+ */
+export declare function bufNv12ToYuyv422(width: number, height: number, nv12: Buffer): Buffer
+
+/**
+ * Convert a YUYV422 buffer to NV12 directly in YUV space
+ * Chroma is averaged over each pair of rows; width and height must be even.
+ * A wrong buffer size or odd dimensions throw with code `InvalidArg`.
+ */
+export declare function bufYuyv422ToNv12(width: number, height: number, yuyv: Buffer): Buffer
 
-/** Convert YUYV422 buffer to RGB */
-export declare function bufYuyv422ToRgb(width: number, height: number, yuyv: Buffer): Buffer
+/**
+ * Convert YUYV422 buffer to RGB
+ * `color_space` selects the YUV matrix (default BT.601)
+ */
+export declare function bufYuyv422ToRgb(width: number, height: number, yuyv: Buffer, colorSpace?: ColorSpace | undefined | null): Buffer
 
-/** Camera control descriptor */
+/**
+ * Camera control descriptor
+ * The numeric fields are only set for integer and enum controls (enums have no
+ * range or step); read other kinds of values with `Camera::controls_snapshot`.
+ */
 export interface CameraControl {
   name: string
   controlType: string
+  /** Vendor-specific control id, only set when the control is `Other` */
+  otherId?: string
+  value?: number
+  minimum?: number
+  maximum?: number
+  step?: number
+  default?: number
+  /** Whether the control currently has an effect (e.g. manual exposure while auto exposure is on) */
+  active: boolean
 }
 
 /** Camera device information */
@@ -107,6 +758,14 @@ export interface CameraDevice {
   name: string
 }
 
+/** Camera device information with its current availability */
+export interface CameraDeviceStatus {
+  index: string
+  name: string
+  /** Whether the device could be opened, i.e. it isn't busy in another process */
+  available: boolean
+}
+
 /** Camera format specification */
 export interface CameraFormat {
   resolution: Resolution
@@ -114,21 +773,161 @@ export interface CameraFormat {
   format: FrameFormat
 }
 
+/** Which camera `openBestCamera` picks when several are connected */
+export declare const enum CameraPreference {
+  /** Most pixels in the largest format, then the highest frame rate */
+  HighestResolution = 'HighestResolution',
+  /** Highest frame rate in any format, then the most pixels */
+  HighestFrameRate = 'HighestFrameRate',
+  /** USB cameras over built-in ones, then by resolution */
+  ExternalFirst = 'ExternalFirst'
+}
+
+/**
+ * Check that a camera works: open it, capture 3 frames and report the outcome
+ * Checks that frames arrive, match the reported size and aren't black. Problems
+ * end up in `warnings` rather than being thrown; only an invalid index throws.
+ * The camera is always stopped and released before this returns.
+ */
+export declare function cameraSelfTest(index: string): SelfTestReport
+
+/** Snapshot of a camera's identity, stream state and format */
+export interface CameraStatus {
+  index: string
+  name: string
+  backend: ApiBackend
+  isStreamOpen: boolean
+  /** Format the camera was configured with */
+  currentFormat: CameraFormat
+  /** Pixel format of the frames the device actually delivers */
+  negotiatedPixelFormat: FrameFormat
+  /** Rate of recent captures through this camera object, null until two frames were captured */
+  measuredFps?: number
+}
+
+/** Camera device information with a small preview frame */
+export interface CameraThumbnail {
+  device: CameraDevice
+  /** JPEG snapshot, null if the device was busy or failed to deliver a frame */
+  thumbnail?: Buffer
+}
+
 /** Get all color frame formats */
 export declare function colorFrameFormats(): Array<FrameFormat>
 
-/** Control value types for setting camera properties */
+/**
+ * YUV to RGB color matrix
+ * SD sources typically use BT.601, HD (720p and up) sources BT.709
+ */
+export declare const enum ColorSpace {
+  Bt601 = 'Bt601',
+  Bt709 = 'Bt709'
+}
+
+/** Color with the fraction of the frame it covers */
+export interface ColorWeight {
+  r: number
+  g: number
+  b: number
+  weight: number
+}
+
+/** Outcome of one entry of `Camera::set_camera_controls` */
+export interface ControlSetResult {
+  control: KnownCameraControl
+  otherId?: string
+  ok: boolean
+  /** Why the control couldn't be set, null when `ok` */
+  error?: string
+}
+
+/** One control to set in `Camera::set_camera_controls` */
+export interface ControlSetting {
+  control: KnownCameraControl
+  value: ControlValueSetter
+  /** Vendor-specific control id, required when `control` is `Other` */
+  otherId?: string
+}
+
+/** Result of `Camera::import_controls` */
+export interface ControlsImport {
+  /** One entry per recognized control in the preset, as from `set_camera_controls` */
+  applied: Array<ControlSetResult>
+  /** Preset entries that were skipped, e.g. controls this version doesn't know */
+  warnings: Array<string>
+}
+
+/** Current value of a camera control */
+export interface ControlSnapshot {
+  name: string
+  control: KnownCameraControl
+  /** Vendor-specific control id, only set when `control` is `Other` */
+  otherId?: string
+  value: ControlValueSetter
+}
+
+/**
+ * Control value types for setting camera properties
+ * In control presets the value is stored bare, e.g. `128`, `0.5` or `true`.
+ */
 export type ControlValueSetter =
   | { type: 'Integer', field0: number }
   | { type: 'Float', field0: number }
   | { type: 'Boolean', field0: boolean }
   | { type: 'String', field0: string }
 
-/** Frame structure exported to JavaScript/TypeScript */
+/** How interlaced frames are turned into progressive ones */
+export declare const enum DeinterlaceMode {
+  /** Line-double the top field; full height, half the vertical detail */
+  Bob = 'Bob',
+  /** Average each line with the next; full height, softer motion */
+  Blend = 'Blend',
+  /** Keep only the top field; the frame becomes half as tall */
+  DiscardField = 'DiscardField'
+}
+
+/** Cameras plugged in and unplugged since the last event, see `watch_devices` */
+export interface DeviceChange {
+  added: Array<CameraDevice>
+  removed: Array<CameraDevice>
+}
+
+/** Resolutions a camera offers in one pixel format, see `Camera::compatible_formats_grouped` */
+export interface FormatGroup {
+  format: FrameFormat
+  /** Distinct resolutions, highest pixel count first */
+  resolutions: Array<ResolutionFrameRates>
+}
+
+/** Result of `Camera::benchmark_fps` */
+export interface FpsBenchmark {
+  /** Frame rate the current format advertises */
+  requestedFps: number
+  /** Rate frames actually arrived at */
+  measuredFps: number
+  frames: number
+  /** Frames the advertised rate promised that never arrived */
+  dropped: number
+}
+
+/**
+ * Frame structure exported to JavaScript/TypeScript
+ * `data` is tightly packed 8-bit RGBA, row-major from the top-left (unless the
+ * output origin is BottomLeft), `width * height * 4` bytes with no row padding
+ * and alpha always 255. That is the layout `SharedPixelBuffer::<Rgba8Pixel>::
+ * clone_from_slice` in Slint and `ImageData` in browsers expect.
+ */
 export interface Frame {
   data: Buffer
   width: number
   height: number
+  /**
+   * Wall-clock time the frame arrived from the device, in Unix milliseconds
+   * Taken from the system clock, not a monotonic one, so it can be matched with
+   * timestamps from other processes and devices but jumps if the clock is set.
+   * Frames built by the `rgba*` helpers carry the time they were built.
+   */
+  timestampMs: number
 }
 
 /** Frame format types supported by the camera */
@@ -144,7 +943,110 @@ export declare const enum FrameFormat {
 /** Get all available frame formats */
 export declare function frameFormats(): Array<FrameFormat>
 
-/** Known camera control properties */
+/**
+ * Exact frame rate as a fraction: `numerator / denominator` frames per second
+ * NTSC-style rates come out as e.g. 30000/1001 (29.97 fps).
+ */
+export interface FrameInterval {
+  numerator: number
+  denominator: number
+}
+
+/** Step of a `for await` loop over a `FrameIterator` */
+export interface FrameIteratorResult {
+  value?: Frame
+  done: boolean
+}
+
+/** One step of a camera's output pipeline, see `Camera::set_output_pipeline` */
+export type FrameOp =
+  | { type: 'Resize', width: number, height: number }
+  | { type: 'Crop', x: number, y: number, width: number, height: number }
+  | { type: 'Flip', horizontal: boolean, vertical: boolean }
+  | { type: 'Rotate', degrees: number }
+  | { type: 'Grayscale' }
+  | { type: 'EncodeJpeg', quality: number }
+  | { type: 'EncodePng' }
+
+/** Mirroring and rotation applied to captured frames, see `Camera::set_transform` */
+export interface FrameTransform {
+  flipHorizontal: boolean
+  flipVertical: boolean
+  /** Clockwise rotation applied after the flips: 0, 90, 180 or 270 degrees */
+  rotate: number
+}
+
+/**
+ * Result of one camera in `CameraGroup::capture_all`
+ * Exactly one of `frame` and `error` is set.
+ */
+export interface GroupCapture {
+  /** Index the camera was opened with */
+  index: string
+  frame?: Frame
+  /** Stable error code, same as `err.code` on thrown errors */
+  code?: string
+  error?: string
+}
+
+/**
+ * Guess the pixel format of a raw frame from its size
+ * Returns every format whose frame size at `width`x`height` equals the buffer
+ * length, most common first (YUYV, NV12, RGB, RGBA, GRAY), or `[MJPEG]` for
+ * data starting with a JPEG marker. An empty result means nothing fits.
+ */
+export declare function guessFrameFormat(data: Buffer, width: number, height: number): Array<FrameFormat>
+
+/**
+ * Planar I420 (YUV 4:2:0) frame exported to JavaScript/TypeScript
+ * `y` is `width * height` bytes, `u` and `v` are `width / 2 * height / 2` bytes each.
+ */
+export interface I420Frame {
+  y: Buffer
+  u: Buffer
+  v: Buffer
+  width: number
+  height: number
+}
+
+/**
+ * Frame shaped for the browser `ImageData` constructor
+ * `data` is always exactly `width * height * 4` bytes of tightly packed RGBA
+ * (no row padding), so `new ImageData(new Uint8ClampedArray(data.buffer,
+ * data.byteOffset, data.length), width, height)` works as-is.
+ */
+export interface ImageDataFrame {
+  data: Uint8Array
+  width: number
+  height: number
+  /** Always "srgb" */
+  colorSpace: string
+}
+
+/**
+ * Where row 0 of a returned frame sits in the image
+ * Cameras deliver TopLeft; WebGL textures expect BottomLeft
+ */
+export declare const enum ImageOrigin {
+  TopLeft = 'TopLeft',
+  BottomLeft = 'BottomLeft'
+}
+
+/** Metadata embedded as EXIF tags in JPEG output; unset fields are left out */
+export interface JpegMetadata {
+  /** Capture time in Unix milliseconds, written as DateTime in UTC */
+  timestampMs?: number
+  /** Written as the camera Model */
+  cameraName?: string
+  /** EXIF orientation, 1 (upright) to 8 */
+  orientation?: number
+}
+
+/**
+ * Known camera control properties
+ * Mirrors every variant of nokhwa's `KnownCameraControl`; the conversions match
+ * exhaustively, so a new upstream variant fails to compile instead of collapsing.
+ */
 export declare const enum KnownCameraControl {
   Brightness = 'Brightness',
   Contrast = 'Contrast',
@@ -161,12 +1063,48 @@ export declare const enum KnownCameraControl {
   Exposure = 'Exposure',
   Iris = 'Iris',
   Focus = 'Focus',
+  /** Vendor-specific control; its id travels separately as `otherId` */
   Other = 'Other'
 }
 
+/** Trade-off between latency and throughput for captures on a `Camera` */
+export declare const enum LatencyMode {
+  /** Return frames in the order the driver queued them (default) */
+  Balanced = 'Balanced',
+  /** Skip frames that were already waiting in the driver queue and return the newest */
+  LowestLatency = 'LowestLatency'
+}
+
+/**
+ * List cameras that can be opened right now
+ * Each device is briefly opened and closed, so devices already in use by
+ * another process are left out
+ */
+export declare function listAvailableCameras(): Array<CameraDevice>
+
 /** List all available cameras */
 export declare function listCameras(): Array<CameraDevice>
 
+/**
+ * List all detected cameras with an `available` flag per device
+ * Availability is checked the same way as in list_available_cameras()
+ */
+export declare function listCamerasWithStatus(): Array<CameraDeviceStatus>
+
+/**
+ * List all detected cameras with a JPEG thumbnail of what each one sees
+ * Every device is opened in turn, a few warmup frames are dropped and the next
+ * frame is scaled to `thumb_width` (keeping the aspect ratio) and encoded at
+ * `quality` (1-100). Busy or failing devices are listed with a null thumbnail.
+ */
+export declare function listCamerasWithThumbnails(thumbWidth: number, quality: number): Array<CameraThumbnail>
+
+/**
+ * Load the profile saved under `stable_id` as JSON text
+ * Returns null if the file doesn't exist or has no profile for this id.
+ */
+export declare function loadCameraConfig(stableId: string, path: string): string | null
+
 /** Convert MJPEG to RGB (convenience function) */
 export declare function mjpegToRgb(mjpeg: Buffer, width: number, height: number): Buffer
 
@@ -176,22 +1114,66 @@ export declare function nativeApiBackend(): ApiBackend | null
 /** Check if nokhwa is initialized */
 export declare function nokhwaCheck(): boolean
 
-/** Convert NV12 to RGB (convenience function) */
-export declare function nv12ToRgb(nv12: Buffer, width: number, height: number): Buffer
+/**
+ * Ask the OS for camera access and resolve with whether it was granted
+ * On macOS this shows the permission prompt the first time and must be awaited
+ * before `listCameras()`, `query()` or opening a camera, which otherwise find
+ * no devices. Other platforms need no initialization and resolve with `true`.
+ */
+export declare function nokhwaInitializeAsync(): Promise<boolean>
+
+/**
+ * Convert NV12 to RGB (convenience function)
+ * `color_space` selects the YUV matrix (default BT.601)
+ */
+export declare function nv12ToRgb(nv12: Buffer, width: number, height: number, colorSpace?: ColorSpace | undefined | null): Buffer
+
+/**
+ * Open the camera `prefer` ranks highest among all connected devices
+ * Each device is opened briefly to read its formats; the winner is opened in
+ * the format it was ranked by, or with automatic detection if the device
+ * refuses that format. ExternalFirst guesses from device names and paths, so
+ * an unusual name may rank a built-in camera as external.
+ */
+export declare function openBestCamera(prefer: CameraPreference): Camera
 
 /** Query available cameras with specific backend */
 export declare function query(backend: ApiBackend): Array<CameraDevice>
 
-/** Requested format configuration */
+/** What a stream does with new frames while the JS callback is behind */
+export declare const enum QueuePolicy {
+  /** Discard the oldest queued frame to make room (default, keeps latency low) */
+  DropOldest = 'DropOldest',
+  /** Discard the frame that was just captured */
+  DropNewest = 'DropNewest',
+  /** Stop capturing until the callback catches up */
+  Block = 'Block'
+}
+
+/**
+ * Requested format configuration
+ * Setting any of `width`, `height` or `frame_rate` asks for the device format
+ * closest to that target instead of ranking formats by `request_type`.
+ */
 export interface RequestedFormatConfig {
   requestType: RequestedFormatType
+  /** Pixel format; with a target only device formats of this source format are considered */
   format?: FrameFormat
+  width?: number
+  height?: number
+  frameRate?: number
 }
 
 /** Format request types for automatic format selection */
 export declare const enum RequestedFormatType {
   AbsoluteHighestResolution = 'AbsoluteHighestResolution',
-  AbsoluteHighestFrameRate = 'AbsoluteHighestFrameRate'
+  AbsoluteHighestFrameRate = 'AbsoluteHighestFrameRate',
+  /** No preference: take the first format the device lists, skipping the capability ranking */
+  None = 'None',
+  /** The device format nearest to the config's width, height and frame rate */
+  Closest = 'Closest',
+  /** A device format matching the config's width, height and frame rate, or an error */
+  Exact = 'Exact'
 }
 
 /** Resolution dimensions */
@@ -200,8 +1182,205 @@ export interface Resolution {
   height: number
 }
 
-/** Get predicted size for YUYV422 format */
-export declare function yuyv422PredictedSize(width: number, height: number): number
+/** Frame rates a camera offers at one resolution, see `Camera::compatible_formats_grouped` */
+export interface ResolutionFrameRates {
+  resolution: Resolution
+  /** Distinct frame rates, lowest first */
+  frameRates: Array<number>
+}
+
+/** Retries for opening a camera that is busy, e.g. just released by another process */
+export interface RetryConfig {
+  /** Extra attempts after the first one */
+  retries: number
+  /** Wait between attempts in milliseconds (default 200) */
+  delayMs?: number
+}
+
+/**
+ * Apply a software brightness/contrast/gamma adjustment to an RGBA buffer
+ * Same parameters as `Camera.setSoftwareAdjust`; alpha is left untouched.
+ */
+export declare function rgbaAdjust(data: Buffer, width: number, height: number, brightness: number, contrast: number, gamma: number): Frame
+
+/** Compute the average color of an RGBA buffer */
+export declare function rgbaAverageColor(data: Buffer, width: number, height: number): RgbColor
+
+/** Deinterlace an RGBA buffer, see `DeinterlaceMode` */
+export declare function rgbaDeinterlace(data: Buffer, width: number, height: number, mode: DeinterlaceMode): Frame
+
+/** Find the `k` dominant colors of an RGBA buffer, most common first */
+export declare function rgbaDominantColors(data: Buffer, width: number, height: number, k: number): Array<ColorWeight>
+
+/**
+ * Alpha-blend an RGBA overlay onto an RGBA frame at (`x`, `y`)
+ * Same compositing as `Camera.setOverlay`: clipped to the frame, weighted by
+ * the overlay's alpha times `opacity` (0 to 1).
+ */
+export declare function rgbaOverlay(base: Buffer, baseWidth: number, baseHeight: number, overlay: Buffer, overlayWidth: number, overlayHeight: number, x: number, y: number, opacity: number): Frame
+
+/**
+ * Compute the sharpness of an RGBA buffer as the variance of its luma Laplacian
+ * Higher values mean a sharper (better focused) image; only relative values
+ * of the same scene are meaningful.
+ */
+export declare function rgbaSharpness(data: Buffer, width: number, height: number): number
+
+/**
+ * Render an RGBA buffer as ASCII art `out_cols` characters wide
+ * Returns one line per character row, each terminated by a newline
+ */
+export declare function rgbaToAscii(data: Buffer, width: number, height: number, outCols: number): string
+
+/**
+ * Convert an RGBA buffer to single-channel grayscale with Rec.601 luma weights
+ * Alpha is ignored.
+ */
+export declare function rgbaToGray(width: number, height: number, rgba: Buffer): Buffer
+
+/**
+ * Encode an RGBA buffer as JPEG, same as `Camera::capture_jpeg` does for a captured frame
+ * `metadata` is embedded as EXIF tags; without it no EXIF is written.
+ */
+export declare function rgbaToJpeg(data: Buffer, width: number, height: number, quality: number, metadata?: JpegMetadata | undefined | null): Buffer
+
+/** Pack an RGBA buffer as RGB by dropping the alpha byte of every pixel */
+export declare function rgbaToRgb(data: Buffer): Buffer
+
+/**
+ * Compute the mean luma of each cell in a `cols`x`rows` grid over an RGBA buffer
+ * Values are returned in row-major order
+ */
+export declare function rgbaZoneBrightness(data: Buffer, width: number, height: number, cols: number, rows: number): Array<number>
+
+/** 8-bit RGB color */
+export interface RgbColor {
+  r: number
+  g: number
+  b: number
+}
+
+/** Convert an RGB buffer to single-channel grayscale with Rec.601 luma weights */
+export declare function rgbToGray(width: number, height: number, rgb: Buffer): Buffer
+
+/**
+ * Frame delivered by `Camera::start_ring_stream`
+ * `data` views the memory of ring slot `slot` and is detached once the callback
+ * returns: copy out whatever is needed while it runs.
+ */
+export interface RingFrame {
+  slot: number
+  data: Uint8Array
+  width: number
+  height: number
+  /** Measured capture rate, or the format's advertised rate for the first frames */
+  fps: number
+  /** Wall-clock arrival time in Unix milliseconds, see `Frame::timestamp_ms` */
+  timestampMs: number
+  /** Frames lost so far, counted as for `StreamFrame::dropped` */
+  dropped: number
+  /** Frames this stream tried to deliver so far, see `StreamFrame::total` */
+  total: number
+}
+
+/**
+ * Save a camera profile (any JSON text) under `stable_id` in the JSON file at `path`
+ * The file is created if missing and profiles of other cameras are kept. Access
+ * is guarded by a file lock, so several processes may share one file.
+ */
+export declare function saveCameraConfig(stableId: string, profileJson: string, path: string): void
+
+/** Result of `cameraSelfTest` */
+export interface SelfTestReport {
+  opened: boolean
+  framesCaptured: number
+  /** Mean time to capture and decode one frame, null if no frame was captured */
+  avgCaptureMs?: number
+  backend?: ApiBackend
+  negotiatedFormat?: CameraFormat
+  /** Everything that went wrong or looked suspicious; empty when the camera is healthy */
+  warnings: Array<string>
+}
+
+/** Native thread settings for background streams */
+export interface StreamConfig {
+  /** Scheduling priority of the capture thread (best effort, may need elevated rights for High) */
+  threadPriority?: ThreadPriority
+  /** CPU core to pin the capture thread to, as a one-element list; not supported on macOS */
+  cpuAffinity?: Array<number>
+  /** What to do with new frames when the queue for the callback is full (default DropOldest) */
+  queuePolicy?: QueuePolicy
+  /** Frames that may wait for the callback besides the one being delivered (default 2, minimum 1) */
+  queueDepth?: number
+  /** Deliver only every Nth captured frame; the others are discarded before decoding (default 1) */
+  deliverEveryNth?: number
+  /**
+   * Cap on the frames per second delivered by device-paced streams (`start_stream`,
+   * `start_ring_stream`); frames in between are discarded before decoding. 0 or unset
+   * is uncapped. Streams that take an `fps` argument pace themselves with that instead.
+   */
+  targetFps?: number
+}
+
+/** Most recent error reported by a background stream */
+export interface StreamErrorInfo {
+  /** Stable error code, same as `err.code` on thrown errors */
+  code: string
+  message: string
+  /** When the error happened, in Unix milliseconds */
+  timestampMs: number
+}
+
+/** Frame delivered by `Camera::start_stream`, with the rate frames are arriving at */
+export interface StreamFrame {
+  data: Buffer
+  width: number
+  height: number
+  /** Measured capture rate, or the format's advertised rate for the first frames */
+  fps: number
+  /** Wall-clock arrival time in Unix milliseconds, see `Frame::timestamp_ms` */
+  timestampMs: number
+  /**
+   * Frames this stream lost so far: failed captures or decodes, plus frames
+   * discarded because the callback fell behind
+   */
+  dropped: number
+  /**
+   * Frames this stream tried to deliver so far, including the dropped ones;
+   * frames skipped on purpose (paused, `deliverEveryNth`, `targetFps`) don't count
+   */
+  total: number
+}
+
+/** OS scheduling priority for native capture threads */
+export declare const enum ThreadPriority {
+  Low = 'Low',
+  Normal = 'Normal',
+  High = 'High'
+}
+
+/**
+ * Call `callback` with the cameras plugged in and unplugged, until the watcher is stopped
+ * The camera list is polled every `interval_ms` (default 1000) on a background
+ * thread. A burst of changes, e.g. a device re-enumerating, is reported as one
+ * event once the list has settled. Throws if the initial list can't be read.
+ */
+export declare function watchDevices(callback: ((err: Error | null, arg: DeviceChange) => unknown), intervalMs?: number | undefined | null): DeviceWatcher
+
+/**
+ * Get the size of the RGB output nokhwa predicts for a YUYV422 buffer of `data_len` bytes
+ * Pass the length of a captured buffer, which may include row padding.
+ */
+export declare function yuyv422PredictedSize(dataLen: number): number
+
+/**
+ * Get the predicted RGB output size for an unpadded `width`x`height` YUYV422 frame
+ * This was `yuyv422PredictedSize(width, height)` before it took a buffer length.
+ */
+export declare function yuyv422PredictedSizeForResolution(width: number, height: number): number
 
-/** Convert YUYV422 to RGB (convenience function) */
-export declare function yuyv422ToRgb(yuyv: Buffer, width: number, height: number): Buffer
+/**
+ * Convert YUYV422 to RGB (convenience function)
+ * `color_space` selects the YUV matrix (default BT.601)
+ */
+export declare function yuyv422ToRgb(yuyv: Buffer, width: number, height: number, colorSpace?: ColorSpace | undefined | null): Buffer
@@ -576,24 +576,65 @@ if (!nativeBinding) {
 }
 
 module.exports = nativeBinding
+module.exports.AsyncFrameIterator = nativeBinding.AsyncFrameIterator
 module.exports.Camera = nativeBinding.Camera
 module.exports.CameraBuffer = nativeBinding.CameraBuffer
+module.exports.CameraGroup = nativeBinding.CameraGroup
+module.exports.DeviceWatcher = nativeBinding.DeviceWatcher
+module.exports.FrameIterator = nativeBinding.FrameIterator
+module.exports.FramePool = nativeBinding.FramePool
+module.exports.PooledFrame = nativeBinding.PooledFrame
+module.exports.RecordingHandle = nativeBinding.RecordingHandle
+module.exports.StreamBuilder = nativeBinding.StreamBuilder
+module.exports.StreamHandle = nativeBinding.StreamHandle
 module.exports.allKnownCameraControls = nativeBinding.allKnownCameraControls
 module.exports.ApiBackend = nativeBinding.ApiBackend
 module.exports.bufBgrToRgb = nativeBinding.bufBgrToRgb
 module.exports.bufMjpegToRgb = nativeBinding.bufMjpegToRgb
 module.exports.bufNv12ToRgb = nativeBinding.bufNv12ToRgb
+module.exports.bufNv12ToYuyv422 = nativeBinding.bufNv12ToYuyv422
+module.exports.bufYuyv422ToNv12 = nativeBinding.bufYuyv422ToNv12
 module.exports.bufYuyv422ToRgb = nativeBinding.bufYuyv422ToRgb
+module.exports.CameraPreference = nativeBinding.CameraPreference
+module.exports.cameraSelfTest = nativeBinding.cameraSelfTest
 module.exports.colorFrameFormats = nativeBinding.colorFrameFormats
+module.exports.ColorSpace = nativeBinding.ColorSpace
+module.exports.DeinterlaceMode = nativeBinding.DeinterlaceMode
 module.exports.FrameFormat = nativeBinding.FrameFormat
 module.exports.frameFormats = nativeBinding.frameFormats
+module.exports.guessFrameFormat = nativeBinding.guessFrameFormat
+module.exports.ImageOrigin = nativeBinding.ImageOrigin
 module.exports.KnownCameraControl = nativeBinding.KnownCameraControl
+module.exports.LatencyMode = nativeBinding.LatencyMode
+module.exports.listAvailableCameras = nativeBinding.listAvailableCameras
 module.exports.listCameras = nativeBinding.listCameras
+module.exports.listCamerasWithStatus = nativeBinding.listCamerasWithStatus
+module.exports.listCamerasWithThumbnails = nativeBinding.listCamerasWithThumbnails
+module.exports.loadCameraConfig = nativeBinding.loadCameraConfig
 module.exports.mjpegToRgb = nativeBinding.mjpegToRgb
 module.exports.nativeApiBackend = nativeBinding.nativeApiBackend
 module.exports.nokhwaCheck = nativeBinding.nokhwaCheck
+module.exports.nokhwaInitializeAsync = nativeBinding.nokhwaInitializeAsync
 module.exports.nv12ToRgb = nativeBinding.nv12ToRgb
+module.exports.openBestCamera = nativeBinding.openBestCamera
 module.exports.query = nativeBinding.query
+module.exports.QueuePolicy = nativeBinding.QueuePolicy
 module.exports.RequestedFormatType = nativeBinding.RequestedFormatType
+module.exports.rgbaAdjust = nativeBinding.rgbaAdjust
+module.exports.rgbaAverageColor = nativeBinding.rgbaAverageColor
+module.exports.rgbaDeinterlace = nativeBinding.rgbaDeinterlace
+module.exports.rgbaDominantColors = nativeBinding.rgbaDominantColors
+module.exports.rgbaOverlay = nativeBinding.rgbaOverlay
+module.exports.rgbaSharpness = nativeBinding.rgbaSharpness
+module.exports.rgbaToAscii = nativeBinding.rgbaToAscii
+module.exports.rgbaToGray = nativeBinding.rgbaToGray
+module.exports.rgbaToJpeg = nativeBinding.rgbaToJpeg
+module.exports.rgbaToRgb = nativeBinding.rgbaToRgb
+module.exports.rgbaZoneBrightness = nativeBinding.rgbaZoneBrightness
+module.exports.rgbToGray = nativeBinding.rgbToGray
+module.exports.saveCameraConfig = nativeBinding.saveCameraConfig
+module.exports.ThreadPriority = nativeBinding.ThreadPriority
+module.exports.watchDevices = nativeBinding.watchDevices
 module.exports.yuyv422PredictedSize = nativeBinding.yuyv422PredictedSize
+module.exports.yuyv422PredictedSizeForResolution = nativeBinding.yuyv422PredictedSizeForResolution
 module.exports.yuyv422ToRgb = nativeBinding.yuyv422ToRgb
@@ -56,24 +56,65 @@ const {
   },
 })
 export default __napiModule.exports
+export const AsyncFrameIterator = __napiModule.exports.AsyncFrameIterator
 export const Camera = __napiModule.exports.Camera
 export const CameraBuffer = __napiModule.exports.CameraBuffer
+export const CameraGroup = __napiModule.exports.CameraGroup
+export const DeviceWatcher = __napiModule.exports.DeviceWatcher
+export const FrameIterator = __napiModule.exports.FrameIterator
+export const FramePool = __napiModule.exports.FramePool
+export const PooledFrame = __napiModule.exports.PooledFrame
+export const RecordingHandle = __napiModule.exports.RecordingHandle
+export const StreamBuilder = __napiModule.exports.StreamBuilder
+export const StreamHandle = __napiModule.exports.StreamHandle
 export const allKnownCameraControls = __napiModule.exports.allKnownCameraControls
 export const ApiBackend = __napiModule.exports.ApiBackend
 export const bufBgrToRgb = __napiModule.exports.bufBgrToRgb
 export const bufMjpegToRgb = __napiModule.exports.bufMjpegToRgb
 export const bufNv12ToRgb = __napiModule.exports.bufNv12ToRgb
+export const bufNv12ToYuyv422 = __napiModule.exports.bufNv12ToYuyv422
+export const bufYuyv422ToNv12 = __napiModule.exports.bufYuyv422ToNv12
 export const bufYuyv422ToRgb = __napiModule.exports.bufYuyv422ToRgb
+export const CameraPreference = __napiModule.exports.CameraPreference
+export const cameraSelfTest = __napiModule.exports.cameraSelfTest
 export const colorFrameFormats = __napiModule.exports.colorFrameFormats
+export const ColorSpace = __napiModule.exports.ColorSpace
+export const DeinterlaceMode = __napiModule.exports.DeinterlaceMode
 export const FrameFormat = __napiModule.exports.FrameFormat
 export const frameFormats = __napiModule.exports.frameFormats
+export const guessFrameFormat = __napiModule.exports.guessFrameFormat
+export const ImageOrigin = __napiModule.exports.ImageOrigin
 export const KnownCameraControl = __napiModule.exports.KnownCameraControl
+export const LatencyMode = __napiModule.exports.LatencyMode
+export const listAvailableCameras = __napiModule.exports.listAvailableCameras
 export const listCameras = __napiModule.exports.listCameras
+export const listCamerasWithStatus = __napiModule.exports.listCamerasWithStatus
+export const listCamerasWithThumbnails = __napiModule.exports.listCamerasWithThumbnails
+export const loadCameraConfig = __napiModule.exports.loadCameraConfig
 export const mjpegToRgb = __napiModule.exports.mjpegToRgb
 export const nativeApiBackend = __napiModule.exports.nativeApiBackend
 export const nokhwaCheck = __napiModule.exports.nokhwaCheck
+export const nokhwaInitializeAsync = __napiModule.exports.nokhwaInitializeAsync
 export const nv12ToRgb = __napiModule.exports.nv12ToRgb
+export const openBestCamera = __napiModule.exports.openBestCamera
 export const query = __napiModule.exports.query
+export const QueuePolicy = __napiModule.exports.QueuePolicy
 export const RequestedFormatType = __napiModule.exports.RequestedFormatType
+export const rgbaAdjust = __napiModule.exports.rgbaAdjust
+export const rgbaAverageColor = __napiModule.exports.rgbaAverageColor
+export const rgbaDeinterlace = __napiModule.exports.rgbaDeinterlace
+export const rgbaDominantColors = __napiModule.exports.rgbaDominantColors
+export const rgbaOverlay = __napiModule.exports.rgbaOverlay
+export const rgbaSharpness = __napiModule.exports.rgbaSharpness
+export const rgbaToAscii = __napiModule.exports.rgbaToAscii
+export const rgbaToGray = __napiModule.exports.rgbaToGray
+export const rgbaToJpeg = __napiModule.exports.rgbaToJpeg
+export const rgbaToRgb = __napiModule.exports.rgbaToRgb
+export const rgbaZoneBrightness = __napiModule.exports.rgbaZoneBrightness
+export const rgbToGray = __napiModule.exports.rgbToGray
+export const saveCameraConfig = __napiModule.exports.saveCameraConfig
+export const ThreadPriority = __napiModule.exports.ThreadPriority
+export const watchDevices = __napiModule.exports.watchDevices
 export const yuyv422PredictedSize = __napiModule.exports.yuyv422PredictedSize
+export const yuyv422PredictedSizeForResolution = __napiModule.exports.yuyv422PredictedSizeForResolution
 export const yuyv422ToRgb = __napiModule.exports.yuyv422ToRgb
@@ -108,24 +108,65 @@ const { instance: __napiInstance, module: __wasiModule, napiModule: __napiModule
   },
 })
 module.exports = __napiModule.exports
+module.exports.AsyncFrameIterator = __napiModule.exports.AsyncFrameIterator
 module.exports.Camera = __napiModule.exports.Camera
 module.exports.CameraBuffer = __napiModule.exports.CameraBuffer
+module.exports.CameraGroup = __napiModule.exports.CameraGroup
+module.exports.DeviceWatcher = __napiModule.exports.DeviceWatcher
+module.exports.FrameIterator = __napiModule.exports.FrameIterator
+module.exports.FramePool = __napiModule.exports.FramePool
+module.exports.PooledFrame = __napiModule.exports.PooledFrame
+module.exports.RecordingHandle = __napiModule.exports.RecordingHandle
+module.exports.StreamBuilder = __napiModule.exports.StreamBuilder
+module.exports.StreamHandle = __napiModule.exports.StreamHandle
 module.exports.allKnownCameraControls = __napiModule.exports.allKnownCameraControls
 module.exports.ApiBackend = __napiModule.exports.ApiBackend
 module.exports.bufBgrToRgb = __napiModule.exports.bufBgrToRgb
 module.exports.bufMjpegToRgb = __napiModule.exports.bufMjpegToRgb
 module.exports.bufNv12ToRgb = __napiModule.exports.bufNv12ToRgb
+module.exports.bufNv12ToYuyv422 = __napiModule.exports.bufNv12ToYuyv422
+module.exports.bufYuyv422ToNv12 = __napiModule.exports.bufYuyv422ToNv12
 module.exports.bufYuyv422ToRgb = __napiModule.exports.bufYuyv422ToRgb
+module.exports.CameraPreference = __napiModule.exports.CameraPreference
+module.exports.cameraSelfTest = __napiModule.exports.cameraSelfTest
 module.exports.colorFrameFormats = __napiModule.exports.colorFrameFormats
+module.exports.ColorSpace = __napiModule.exports.ColorSpace
+module.exports.DeinterlaceMode = __napiModule.exports.DeinterlaceMode
 module.exports.FrameFormat = __napiModule.exports.FrameFormat
 module.exports.frameFormats = __napiModule.exports.frameFormats
+module.exports.guessFrameFormat = __napiModule.exports.guessFrameFormat
+module.exports.ImageOrigin = __napiModule.exports.ImageOrigin
 module.exports.KnownCameraControl = __napiModule.exports.KnownCameraControl
+module.exports.LatencyMode = __napiModule.exports.LatencyMode
+module.exports.listAvailableCameras = __napiModule.exports.listAvailableCameras
 module.exports.listCameras = __napiModule.exports.listCameras
+module.exports.listCamerasWithStatus = __napiModule.exports.listCamerasWithStatus
+module.exports.listCamerasWithThumbnails = __napiModule.exports.listCamerasWithThumbnails
+module.exports.loadCameraConfig = __napiModule.exports.loadCameraConfig
 module.exports.mjpegToRgb = __napiModule.exports.mjpegToRgb
 module.exports.nativeApiBackend = __napiModule.exports.nativeApiBackend
 module.exports.nokhwaCheck = __napiModule.exports.nokhwaCheck
+module.exports.nokhwaInitializeAsync = __napiModule.exports.nokhwaInitializeAsync
 module.exports.nv12ToRgb = __napiModule.exports.nv12ToRgb
+module.exports.openBestCamera = __napiModule.exports.openBestCamera
 module.exports.query = __napiModule.exports.query
+module.exports.QueuePolicy = __napiModule.exports.QueuePolicy
 module.exports.RequestedFormatType = __napiModule.exports.RequestedFormatType
+module.exports.rgbaAdjust = __napiModule.exports.rgbaAdjust
+module.exports.rgbaAverageColor = __napiModule.exports.rgbaAverageColor
+module.exports.rgbaDeinterlace = __napiModule.exports.rgbaDeinterlace
+module.exports.rgbaDominantColors = __napiModule.exports.rgbaDominantColors
+module.exports.rgbaOverlay = __napiModule.exports.rgbaOverlay
+module.exports.rgbaSharpness = __napiModule.exports.rgbaSharpness
+module.exports.rgbaToAscii = __napiModule.exports.rgbaToAscii
+module.exports.rgbaToGray = __napiModule.exports.rgbaToGray
+module.exports.rgbaToJpeg = __napiModule.exports.rgbaToJpeg
+module.exports.rgbaToRgb = __napiModule.exports.rgbaToRgb
+module.exports.rgbaZoneBrightness = __napiModule.exports.rgbaZoneBrightness
+module.exports.rgbToGray = __napiModule.exports.rgbToGray
+module.exports.saveCameraConfig = __napiModule.exports.saveCameraConfig
+module.exports.ThreadPriority = __napiModule.exports.ThreadPriority
+module.exports.watchDevices = __napiModule.exports.watchDevices
 module.exports.yuyv422PredictedSize = __napiModule.exports.yuyv422PredictedSize
+module.exports.yuyv422PredictedSizeForResolution = __napiModule.exports.yuyv422PredictedSizeForResolution
 module.exports.yuyv422ToRgb = __napiModule.exports.yuyv422ToRgb
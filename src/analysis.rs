@@ -0,0 +1,201 @@
+//! Frame analysis module for nokhwa-node
+//!
+//! This module computes small summaries (colors, brightness, ...) from RGBA
+//! frames so callers don't need to ship whole frames to JavaScript.
+
+use anyhow::anyhow;
+
+use crate::types::{ColorWeight, RgbColor};
+
+/// Bits kept per channel when bucketing colors for the dominant color histogram
+const HISTOGRAM_BITS: u32 = 4;
+
+/// Checks that an RGBA buffer matches its declared dimensions
+pub fn check_rgba_len(data: &[u8], width: u32, height: u32) -> anyhow::Result<()> {
+  let expected = width as usize * height as usize * 4;
+  if data.len() != expected {
+    return Err(anyhow!(
+      "Invalid RGBA buffer: expected {} bytes for {}x{}, got {}",
+      expected,
+      width,
+      height,
+      data.len()
+    ));
+  }
+  Ok(())
+}
+
+/// Rec.601 luma of an RGB pixel
+#[inline]
+pub fn luma(r: u8, g: u8, b: u8) -> u8 {
+  ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+}
+
+/// Computes the mean color of an RGBA buffer (alpha is ignored)
+pub fn average_color(rgba: &[u8]) -> RgbColor {
+  let pixels = (rgba.len() / 4) as u64;
+  if pixels == 0 {
+    return RgbColor { r: 0, g: 0, b: 0 };
+  }
+
+  let mut sums = [0u64; 3];
+  for px in rgba.chunks_exact(4) {
+    sums[0] += px[0] as u64;
+    sums[1] += px[1] as u64;
+    sums[2] += px[2] as u64;
+  }
+
+  RgbColor {
+    r: (sums[0] / pixels) as u8,
+    g: (sums[1] / pixels) as u8,
+    b: (sums[2] / pixels) as u8,
+  }
+}
+
+/// Finds the `k` most common colors of an RGBA buffer
+/// Pixels are bucketed into a coarse histogram; each result is the mean color of
+/// one bucket with `weight` being the fraction of pixels that fell into it.
+pub fn dominant_colors(rgba: &[u8], k: u32) -> Vec<ColorWeight> {
+  let pixels = rgba.len() / 4;
+  if pixels == 0 || k == 0 {
+    return Vec::new();
+  }
+
+  let shift = 8 - HISTOGRAM_BITS;
+  let buckets = 1usize << (HISTOGRAM_BITS * 3);
+  // Per bucket: pixel count and channel sums
+  let mut histogram = vec![[0u64; 4]; buckets];
+  for px in rgba.chunks_exact(4) {
+    let index = ((px[0] as usize >> shift) << (HISTOGRAM_BITS * 2))
+      | ((px[1] as usize >> shift) << HISTOGRAM_BITS)
+      | (px[2] as usize >> shift);
+    let bucket = &mut histogram[index];
+    bucket[0] += 1;
+    bucket[1] += px[0] as u64;
+    bucket[2] += px[1] as u64;
+    bucket[3] += px[2] as u64;
+  }
+
+  let mut used: Vec<&[u64; 4]> = histogram.iter().filter(|b| b[0] > 0).collect();
+  used.sort_by(|a, b| b[0].cmp(&a[0]));
+
+  used
+    .into_iter()
+    .take(k as usize)
+    .map(|bucket| ColorWeight {
+      r: (bucket[1] / bucket[0]) as u8,
+      g: (bucket[2] / bucket[0]) as u8,
+      b: (bucket[3] / bucket[0]) as u8,
+      weight: bucket[0] as f64 / pixels as f64,
+    })
+    .collect()
+}
+
+/// Computes the mean luma of each cell of a `cols`x`rows` grid over an RGBA frame
+/// Results are in row-major order. Pixels are split between cells as evenly as
+/// possible when the frame size isn't a multiple of the grid size.
+pub fn zone_brightness(
+  rgba: &[u8],
+  width: u32,
+  height: u32,
+  cols: u32,
+  rows: u32,
+) -> anyhow::Result<Vec<u8>> {
+  if cols == 0 || rows == 0 || cols > width || rows > height {
+    return Err(anyhow!(
+      "Invalid grid {}x{} for a {}x{} frame",
+      cols,
+      rows,
+      width,
+      height
+    ));
+  }
+
+  let (width, height, cols) = (width as usize, height as usize, cols as usize);
+  let rows = rows as usize;
+  // Precompute which grid column each x falls into so the pixel loop stays branch-free
+  let cell_x: Vec<usize> = (0..width).map(|x| x * cols / width).collect();
+
+  let mut sums = vec![0u64; cols * rows];
+  let mut counts = vec![0u64; cols * rows];
+  for (y, line) in rgba.chunks_exact(width * 4).take(height).enumerate() {
+    let row_offset = (y * rows / height) * cols;
+    for (x, px) in line.chunks_exact(4).enumerate() {
+      let cell = row_offset + cell_x[x];
+      sums[cell] += luma(px[0], px[1], px[2]) as u64;
+      counts[cell] += 1;
+    }
+  }
+
+  Ok(
+    sums
+      .iter()
+      .zip(counts.iter())
+      .map(|(sum, count)| (sum / (*count).max(1)) as u8)
+      .collect(),
+  )
+}
+
+/// Characters used for ASCII previews, from darkest to brightest
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Renders an RGBA frame as ASCII art `out_cols` characters wide
+/// The row count follows the frame's aspect ratio, halved because terminal
+/// cells are roughly twice as tall as they are wide. Each character is the
+/// mean luma of the pixels it covers, mapped onto a dark-to-bright ramp.
+pub fn rgba_to_ascii(
+  rgba: &[u8],
+  width: u32,
+  height: u32,
+  out_cols: u32,
+) -> anyhow::Result<String> {
+  if out_cols == 0 || width == 0 || height == 0 {
+    return Err(anyhow!(
+      "Invalid ASCII preview: {} columns for a {}x{} frame",
+      out_cols,
+      width,
+      height
+    ));
+  }
+
+  let cols = out_cols.min(width);
+  let rows = ((height as u64 * cols as u64) / (width as u64 * 2)).clamp(1, height as u64) as u32;
+  let cells = zone_brightness(rgba, width, height, cols, rows)?;
+
+  let last = (ASCII_RAMP.len() - 1) as u32;
+  let mut out = String::with_capacity(((cols + 1) * rows) as usize);
+  for line in cells.chunks_exact(cols as usize) {
+    for value in line {
+      out.push(ASCII_RAMP[(*value as u32 * last / 255) as usize] as char);
+    }
+    out.push('\n');
+  }
+  Ok(out)
+}
+
+/// Focus measure: variance of the 3x3 Laplacian over the luma channel
+/// Sharp edges give strong second derivatives, so higher values mean a sharper
+/// image. Frames smaller than 3x3 have no interior pixels and score 0.
+pub fn sharpness(rgba: &[u8], width: u32, height: u32) -> f64 {
+  let (w, h) = (width as usize, height as usize);
+  if w < 3 || h < 3 {
+    return 0.0;
+  }
+  let lum: Vec<i32> = rgba
+    .chunks_exact(4)
+    .map(|px| luma(px[0], px[1], px[2]) as i32)
+    .collect();
+
+  let (mut sum, mut sum_sq) = (0f64, 0f64);
+  for y in 1..h - 1 {
+    for x in 1..w - 1 {
+      let i = y * w + x;
+      let laplacian = lum[i - w] + lum[i + w] + lum[i - 1] + lum[i + 1] - 4 * lum[i];
+      sum += laplacian as f64;
+      sum_sq += (laplacian as f64).powi(2);
+    }
+  }
+  let n = ((w - 2) * (h - 2)) as f64;
+  let mean = sum / n;
+  sum_sq / n - mean * mean
+}
@@ -2,7 +2,13 @@
 //!
 //! This module provides the CameraBuffer struct for managing raw camera frame data.
 
-use crate::types::{FrameFormat, Resolution};
+use std::sync::Arc;
+
+use crate::analysis::check_rgba_len;
+use crate::conversions::{decode_as, to_source_format};
+use crate::error::{coded_error, CodedFailure, ErrorCode};
+use crate::processing::rgba_to_rgb;
+use crate::types::{ColorSpace, FrameFormat, Resolution};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
@@ -10,7 +16,8 @@ use napi_derive::napi;
 #[napi]
 pub struct CameraBuffer {
   pub(crate) resolution: Resolution,
-  pub(crate) data: Vec<u8>,
+  /// Shared with the views handed out by `data_ref`, which keep it alive
+  pub(crate) data: Arc<Vec<u8>>,
   pub(crate) source_frame_format: FrameFormat,
 }
 
@@ -21,11 +28,47 @@ impl CameraBuffer {
   pub fn new(resolution: Resolution, data: Buffer, source_frame_format: FrameFormat) -> Self {
     CameraBuffer {
       resolution,
-      data: data.to_vec(),
+      data: Arc::new(data.to_vec()),
       source_frame_format,
     }
   }
 
+  /// Load a raw frame from disk, e.g. a fixture saved from `frame_raw()`
+  /// The file size must match the size implied by `resolution` and `format`;
+  /// MJPEG frames are variable-length and only have to be non-empty.
+  #[napi(factory)]
+  pub fn from_file(path: String, resolution: Resolution, format: FrameFormat) -> Result<Self> {
+    let data = std::fs::read(&path)
+      .map_err(|e| Error::from_reason(format!("Failed to read {}: {}", path, e)))?;
+
+    match expected_frame_size(format, resolution.width, resolution.height) {
+      Some(expected) if data.len() != expected => {
+        return Err(Error::from_reason(format!(
+          "Invalid frame file {}: expected {} bytes for {}x{} {:?}, got {}",
+          path,
+          expected,
+          resolution.width,
+          resolution.height,
+          format,
+          data.len()
+        )))
+      }
+      None if data.is_empty() => {
+        return Err(Error::from_reason(format!(
+          "Invalid frame file {}: file is empty",
+          path
+        )))
+      }
+      _ => {}
+    }
+
+    Ok(CameraBuffer {
+      resolution,
+      data: Arc::new(data),
+      source_frame_format: format,
+    })
+  }
+
   /// Get the resolution of the buffer
   #[napi]
   pub fn resolution(&self) -> Resolution {
@@ -37,10 +80,71 @@ impl CameraBuffer {
   pub fn data(&self) -> Buffer {
     // IMPORTANT: Clone the data to ensure we return a独立-owned Buffer
     // This prevents dangling references when Bun accesses the buffer later
-    let owned_data = self.data.clone();
+    let owned_data = self.data.to_vec();
     Buffer::from(owned_data)
   }
 
+  /// Get the raw buffer data as a `Uint8Array` over the same memory, without copying
+  /// The view holds its own reference to the bytes, so it stays valid after this
+  /// `CameraBuffer` is dropped or garbage collected; the memory is freed once
+  /// both are gone. The view is read-only by contract: the bytes are shared with
+  /// this buffer and every other view of it, so don't write to it. Runtimes
+  /// without external buffers (e.g. Electron) get a copy instead.
+  #[napi]
+  pub fn data_ref(&self) -> Uint8Array {
+    if self.data.is_empty() {
+      return Uint8Array::new(Vec::new());
+    }
+    let data = Arc::clone(&self.data);
+    let (ptr, len) = (data.as_ptr().cast_mut(), data.len());
+    // SAFETY: the closure owns a reference to the bytes until the view is finalized
+    unsafe { Uint8Array::with_external_data(ptr, len, move |_, _| drop(data)) }
+  }
+
+  /// Call `callback` with a `Uint8Array` viewing the frame bytes in place, without copying them
+  /// The view is only valid while the callback runs: it must not be kept, stored
+  /// or used from a later tick, and must not be written to. Once the callback
+  /// returns the view is detached, so a retained reference reads as empty rather
+  /// than seeing freed memory. Runtimes without external buffers (e.g. Electron)
+  /// get a copy instead.
+  #[napi(ts_args_type = "callback: (data: Uint8Array) => void")]
+  pub fn with_data(&self, env: Env, callback: Function<Unknown, Unknown>) -> Result<()> {
+    if self.data.is_empty() {
+      let empty = Uint8ArraySlice::from_data(&env, Vec::new())?;
+      return callback.call(empty.to_unknown()).map(|_| ());
+    }
+
+    // SAFETY: the bytes outlive the view, which is detached before this returns
+    let arraybuffer = unsafe {
+      ArrayBuffer::from_external(
+        &env,
+        self.data.as_ptr().cast_mut(),
+        self.data.len(),
+        (),
+        |_, _| {},
+      )
+    }?;
+    let view = Uint8ArraySlice::from_arraybuffer(&arraybuffer, 0, self.data.len())?;
+    let result = callback.call(view.to_unknown());
+    // Fails harmlessly when the runtime made a copy, which needs no detaching
+    let _ = arraybuffer.detach();
+    result.map(|_| ())
+  }
+
+  /// Decode the frame to RGB, 3 bytes per pixel
+  /// Uses the same decoders as `Camera::capture_frame` with the stored
+  /// resolution (BT.601 for YUV formats); undecodable data throws.
+  #[napi]
+  pub fn decode_rgb(&self) -> Result<Buffer, ErrorCode> {
+    self.decode(FrameFormat::RGB).map(Buffer::from)
+  }
+
+  /// Decode the frame to RGBA, 4 bytes per pixel, see `decode_rgb`
+  #[napi]
+  pub fn decode_rgba(&self) -> Result<Buffer, ErrorCode> {
+    self.decode(FrameFormat::RGBA).map(Buffer::from)
+  }
+
   /// Get the source frame format
   #[napi]
   pub fn source_frame_format(&self) -> FrameFormat {
@@ -71,3 +175,73 @@ impl CameraBuffer {
     self.data.is_empty()
   }
 }
+
+impl CameraBuffer {
+  fn decode(&self, output: FrameFormat) -> Result<Vec<u8>, ErrorCode> {
+    let (width, height) = (self.resolution.width, self.resolution.height);
+    let Some(source) = to_source_format(self.source_frame_format) else {
+      // RGBA data, which nokhwa has no source format for, only needs the alpha dropped
+      check_rgba_len(&self.data, width, height)
+        .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+      return Ok(match output {
+        FrameFormat::RGB => rgba_to_rgb(&self.data),
+        _ => self.data.to_vec(),
+      });
+    };
+
+    let buffer = nokhwa::Buffer::new(
+      nokhwa::utils::Resolution::new(width, height),
+      &self.data,
+      source,
+    );
+    decode_as(&buffer, width, height, ColorSpace::Bt601, output)
+      .map(|(data, _, _)| data)
+      .map_err(|e| {
+        // Size mismatches keep their own code, everything else is a broken frame
+        let code = e
+          .downcast_ref::<CodedFailure>()
+          .map_or(ErrorCode::DecodeFailed, |failure| failure.code);
+        coded_error(
+          code,
+          format!(
+            "Failed to decode {:?} buffer: {}",
+            self.source_frame_format, e
+          ),
+        )
+      })
+  }
+}
+
+/// Byte size of one uncompressed frame in `format`, or `None` for compressed formats
+pub(crate) fn expected_frame_size(format: FrameFormat, width: u32, height: u32) -> Option<usize> {
+  let pixels = width as usize * height as usize;
+  match format {
+    FrameFormat::MJPEG => None,
+    FrameFormat::YUYV => Some(pixels * 2),
+    FrameFormat::NV12 => Some(pixels * 3 / 2),
+    FrameFormat::RGB => Some(pixels * 3),
+    FrameFormat::RGBA => Some(pixels * 4),
+    FrameFormat::GRAY => Some(pixels),
+  }
+}
+
+/// Formats whose frame size at `width`x`height` matches `data`, most common first
+/// Data starting with a JPEG SOI marker is reported as MJPEG only.
+pub(crate) fn guess_frame_formats(data: &[u8], width: u32, height: u32) -> Vec<FrameFormat> {
+  if data.starts_with(&[0xFF, 0xD8]) {
+    return vec![FrameFormat::MJPEG];
+  }
+  // NV12 chroma is subsampled in both directions, so odd sizes can't be NV12
+  let nv12_ok = width.is_multiple_of(2) && height.is_multiple_of(2);
+  [
+    FrameFormat::YUYV,
+    FrameFormat::NV12,
+    FrameFormat::RGB,
+    FrameFormat::RGBA,
+    FrameFormat::GRAY,
+  ]
+  .into_iter()
+  .filter(|format| *format != FrameFormat::NV12 || nv12_ok)
+  .filter(|format| expected_frame_size(*format, width, height) == Some(data.len()))
+  .collect()
+}
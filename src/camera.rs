@@ -1,11 +1,36 @@
 //! Camera module for nokhwa-node
 //!
-//! This module provides camera listing and discovery functionality.
+//! This module provides camera listing and discovery functionality, plus the
+//! self-test used to validate a deployment.
 
-use anyhow::Result;
-use nokhwa::utils::ApiBackend;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Instant;
 
-use crate::types::CameraDevice;
+use anyhow::{anyhow, Result};
+use nokhwa::utils::{ApiBackend, CameraIndex};
+
+use napi::bindgen_prelude::Buffer;
+
+use crate::analysis::luma;
+use crate::conversions::{
+  capture_frame, convert_backend_to_napi, convert_frame_format, create_camera_with_fallback,
+};
+use crate::encode::encode_jpeg;
+use crate::error::{coded_failure, ErrorCode};
+use crate::processing::{resize, rgba_to_rgb};
+use crate::types::{
+  CameraDevice, CameraDeviceStatus, CameraFormat, CameraPreference, CameraThumbnail, ColorSpace,
+  Resolution, SelfTestReport,
+};
+
+/// Frames captured by the self-test
+const SELF_TEST_FRAMES: u32 = 3;
+
+/// Brightest luma a frame may reach and still count as black
+const BLACK_FRAME_LUMA: u8 = 16;
+
+/// Frames dropped before a thumbnail is taken, giving auto exposure a moment to settle
+const THUMBNAIL_WARMUP_FRAMES: u32 = 3;
 
 /// Gets information about available cameras
 pub fn list_cameras() -> Result<Vec<CameraDevice>> {
@@ -32,3 +57,335 @@ pub fn list_cameras() -> Result<Vec<CameraDevice>> {
 
   Ok(camera_info)
 }
+
+/// Gets every detected camera along with whether it can currently be opened
+pub fn list_cameras_with_status() -> Result<Vec<CameraDeviceStatus>> {
+  let cameras = nokhwa::query(ApiBackend::Auto)?;
+
+  Ok(
+    cameras
+      .into_iter()
+      .map(|cam| CameraDeviceStatus {
+        index: cam.index().as_string(),
+        name: cam.human_name(),
+        available: probe_camera(cam.index()),
+      })
+      .collect(),
+  )
+}
+
+/// Briefly opens and closes a camera's stream to check that it isn't busy
+/// Some backends panic when a device is held by another process, so the whole
+/// probe (including the drop) runs under catch_unwind and counts as unavailable.
+fn probe_camera(index: &CameraIndex) -> bool {
+  use nokhwa::pixel_format::RgbFormat;
+  use nokhwa::utils::{RequestedFormat, RequestedFormatType};
+
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    let request = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
+    let mut cam = match nokhwa::Camera::new(index.clone(), request) {
+      Ok(cam) => cam,
+      Err(_) => return false,
+    };
+    let opened = cam.open_stream().is_ok();
+    if opened {
+      let _ = cam.stop_stream();
+    }
+    opened
+  }));
+
+  result.unwrap_or(false)
+}
+
+/// Gets every detected camera with a JPEG snapshot `thumb_width` pixels wide
+/// Devices are opened one after another and released before the next one is
+/// tried. A device that is busy, fails or panics gets no thumbnail.
+pub fn list_cameras_with_thumbnails(
+  thumb_width: u32,
+  quality: u32,
+) -> Result<Vec<CameraThumbnail>> {
+  if thumb_width == 0 {
+    return Err(anyhow!("Thumbnail width must be greater than 0"));
+  }
+  let cameras = nokhwa::query(ApiBackend::Auto)?;
+
+  Ok(
+    cameras
+      .into_iter()
+      .map(|cam| {
+        let thumbnail = catch_unwind(AssertUnwindSafe(|| {
+          capture_thumbnail(cam.index(), thumb_width, quality)
+        }));
+        CameraThumbnail {
+          device: CameraDevice {
+            index: cam.index().as_string(),
+            name: cam.human_name(),
+          },
+          thumbnail: thumbnail.ok().flatten().map(Buffer::from),
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Opens a camera, skips the warmup frames and encodes the next one as a scaled JPEG
+/// The camera is stopped and dropped before returning, whether or not capture worked.
+fn capture_thumbnail(index: &CameraIndex, thumb_width: u32, quality: u32) -> Option<Vec<u8>> {
+  let mut camera = create_camera_with_fallback(index.clone(), ApiBackend::Auto).ok()?;
+  let thumbnail = (|| -> Result<Vec<u8>> {
+    for _ in 0..THUMBNAIL_WARMUP_FRAMES {
+      camera.frame_raw()?;
+    }
+    let frame = capture_frame(&mut camera, ColorSpace::Bt601)?;
+    let thumb_height =
+      ((frame.height as u64 * thumb_width as u64) / frame.width as u64).max(1) as u32;
+    let scaled = resize(
+      &frame.data,
+      frame.width,
+      frame.height,
+      4,
+      thumb_width,
+      thumb_height,
+    )?;
+    encode_jpeg(&rgba_to_rgb(&scaled), thumb_width, thumb_height, quality)
+  })();
+  let _ = camera.stop_stream();
+  thumbnail.ok()
+}
+
+/// Finds the index of the camera whose human-readable name matches `name`
+/// With `exact`, the name must match exactly and be unique among connected devices.
+/// Otherwise a case-insensitive substring match is used and the first match wins.
+pub fn find_camera_by_name(name: &str, exact: bool) -> Result<CameraIndex> {
+  let needle = name.to_lowercase();
+  let matches: Vec<CameraIndex> = nokhwa::query(ApiBackend::Auto)?
+    .into_iter()
+    .filter(|cam| {
+      if exact {
+        cam.human_name() == name
+      } else {
+        cam.human_name().to_lowercase().contains(&needle)
+      }
+    })
+    .map(|cam| cam.index().clone())
+    .collect();
+
+  match matches.as_slice() {
+    [] => Err(coded_failure(
+      ErrorCode::DeviceNotFound,
+      format!("No camera found matching name \"{}\"", name),
+    )),
+    [index] => Ok(index.clone()),
+    [first, ..] if !exact => Ok(first.clone()),
+    _ => {
+      let indices: Vec<String> = matches.iter().map(|i| i.as_string()).collect();
+      Err(anyhow!(
+        "Multiple cameras named \"{}\" (indices: {}); open one by index instead",
+        name,
+        indices.join(", ")
+      ))
+    }
+  }
+}
+
+/// Name fragments of cameras built into laptops and displays
+const BUILT_IN_NAME_HINTS: [&str; 6] = [
+  "integrated",
+  "built-in",
+  "builtin",
+  "internal",
+  "facetime",
+  "front camera",
+];
+
+/// Name fragments of cameras plugged in from outside
+const EXTERNAL_NAME_HINTS: [&str; 3] = ["usb", "external", "webcam"];
+
+/// Largest format of a probed camera in each of the ranked dimensions
+struct ProbedCamera {
+  index: CameraIndex,
+  /// External-ness guessed from name and path: 0 built-in, 1 unknown, 2 external
+  external: u8,
+  /// Format with the most pixels, ties broken by frame rate
+  largest: nokhwa::utils::CameraFormat,
+  /// Format with the highest frame rate, ties broken by pixels
+  fastest: nokhwa::utils::CameraFormat,
+}
+
+/// Guesses whether a camera is plugged in rather than built in
+/// Backends don't report the bus reliably (built-in webcams are often USB
+/// devices internally), so this goes by name, description and device path.
+fn external_hint(info: &nokhwa::utils::CameraInfo) -> u8 {
+  let text = format!(
+    "{} {} {}",
+    info.human_name(),
+    info.description(),
+    info.misc()
+  )
+  .to_lowercase();
+  if BUILT_IN_NAME_HINTS.iter().any(|hint| text.contains(hint)) {
+    0
+  } else if EXTERNAL_NAME_HINTS.iter().any(|hint| text.contains(hint)) {
+    2
+  } else {
+    1
+  }
+}
+
+fn pixels(format: &nokhwa::utils::CameraFormat) -> u32 {
+  format.width() * format.height()
+}
+
+/// Opens a camera without streaming and reads its largest and fastest formats
+/// Like `probe_camera` this runs under catch_unwind; devices that fail, panic or
+/// list no formats (e.g. Linux metadata nodes) are left out.
+fn probe_formats(info: &nokhwa::utils::CameraInfo) -> Option<ProbedCamera> {
+  use nokhwa::pixel_format::RgbFormat;
+  use nokhwa::utils::{RequestedFormat, RequestedFormatType};
+
+  let formats = catch_unwind(AssertUnwindSafe(|| {
+    let request = RequestedFormat::new::<RgbFormat>(RequestedFormatType::None);
+    let mut cam = nokhwa::Camera::new(info.index().clone(), request).ok()?;
+    cam.compatible_camera_formats().ok()
+  }))
+  .ok()
+  .flatten()?;
+
+  let largest = *formats.iter().max_by_key(|f| (pixels(f), f.frame_rate()))?;
+  let fastest = *formats.iter().max_by_key(|f| (f.frame_rate(), pixels(f)))?;
+  Some(ProbedCamera {
+    index: info.index().clone(),
+    external: external_hint(info),
+    largest,
+    fastest,
+  })
+}
+
+/// Probes every detected camera and returns the one `prefer` ranks highest
+/// along with the format it was ranked by. Ties go to the first device listed.
+pub fn find_best_camera(
+  prefer: CameraPreference,
+) -> Result<(CameraIndex, nokhwa::utils::CameraFormat)> {
+  let probed: Vec<ProbedCamera> = nokhwa::query(ApiBackend::Auto)?
+    .iter()
+    .filter_map(probe_formats)
+    .collect();
+
+  let best = probed
+    .into_iter()
+    .rev()
+    .max_by_key(|cam| match prefer {
+      CameraPreference::HighestResolution => (0, pixels(&cam.largest), cam.largest.frame_rate()),
+      CameraPreference::HighestFrameRate => (0, cam.fastest.frame_rate(), pixels(&cam.fastest)),
+      CameraPreference::ExternalFirst => {
+        (cam.external, pixels(&cam.largest), cam.largest.frame_rate())
+      }
+    })
+    .ok_or_else(|| anyhow!("No camera with a usable format was found"))?;
+
+  let format = match prefer {
+    CameraPreference::HighestFrameRate => best.fastest,
+    _ => best.largest,
+  };
+  Ok((best.index, format))
+}
+
+/// Opens a camera, captures a few frames and reports what worked
+/// Problems are reported as warnings instead of errors so the report is always
+/// complete. The camera is stopped and dropped before returning, also when a
+/// backend panics along the way.
+pub fn self_test(index: CameraIndex) -> SelfTestReport {
+  let mut report = SelfTestReport {
+    opened: false,
+    frames_captured: 0,
+    avg_capture_ms: None,
+    backend: None,
+    negotiated_format: None,
+    warnings: Vec::new(),
+  };
+
+  if !nokhwa::nokhwa_check() {
+    report
+      .warnings
+      .push("Camera access is not initialized: await nokhwaInitializeAsync() first".to_string());
+    return report;
+  }
+
+  let outcome = catch_unwind(AssertUnwindSafe(|| run_self_test(index, &mut report)));
+  if outcome.is_err() {
+    report
+      .warnings
+      .push("The camera backend panicked during the self-test".to_string());
+  }
+  report
+}
+
+fn run_self_test(index: CameraIndex, report: &mut SelfTestReport) {
+  let mut camera = match create_camera_with_fallback(index, ApiBackend::Auto) {
+    Ok(camera) => camera,
+    Err(e) => {
+      report
+        .warnings
+        .push(format!("Failed to open camera: {}", e.reason));
+      return;
+    }
+  };
+  report.opened = true;
+  report.backend = Some(convert_backend_to_napi(camera.backend()));
+
+  let fmt = camera.camera_format();
+  report.negotiated_format = Some(CameraFormat {
+    resolution: Resolution {
+      width: fmt.width(),
+      height: fmt.height(),
+    },
+    frame_rate: fmt.frame_rate(),
+    format: convert_frame_format(camera.frame_format()),
+  });
+
+  let mut total_ms = 0.0;
+  for n in 1..=SELF_TEST_FRAMES {
+    let started = Instant::now();
+    let frame = match capture_frame(&mut camera, ColorSpace::Bt601) {
+      Ok(frame) => frame,
+      Err(e) => {
+        report.warnings.push(format!("Frame {} failed: {}", n, e));
+        continue;
+      }
+    };
+    total_ms += started.elapsed().as_secs_f64() * 1000.0;
+    report.frames_captured += 1;
+
+    if (frame.width, frame.height) != (fmt.width(), fmt.height()) {
+      report.warnings.push(format!(
+        "Frame {} is {}x{}, but the camera reported {}x{}",
+        n,
+        frame.width,
+        frame.height,
+        fmt.width(),
+        fmt.height()
+      ));
+    }
+    let brightest = frame
+      .data
+      .chunks_exact(4)
+      .map(|px| luma(px[0], px[1], px[2]))
+      .max()
+      .unwrap_or(0);
+    if brightest <= BLACK_FRAME_LUMA {
+      report.warnings.push(format!(
+        "Frame {} is black (lens covered, privacy shutter closed or sensor not ready?)",
+        n
+      ));
+    }
+  }
+  if report.frames_captured > 0 {
+    report.avg_capture_ms = Some(total_ms / report.frames_captured as f64);
+  }
+
+  if let Err(e) = camera.stop_stream() {
+    report
+      .warnings
+      .push(format!("Failed to stop stream: {}", e));
+  }
+}
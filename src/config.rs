@@ -0,0 +1,77 @@
+//! Config storage module for nokhwa-node
+//!
+//! This module persists per-camera profiles in a single JSON file, keyed by the
+//! device's stable id. Every access holds an OS file lock, so several processes
+//! can share the file.
+
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+/// Parse the store, treating an empty (just created) file as holding no profiles
+fn read_store(file: &mut File, path: &str) -> Result<Map<String, Value>> {
+  let mut contents = String::new();
+  file
+    .read_to_string(&mut contents)
+    .map_err(|e| anyhow!("Reading {}: {}", path, e))?;
+  if contents.trim().is_empty() {
+    return Ok(Map::new());
+  }
+  match serde_json::from_str(&contents) {
+    Ok(Value::Object(store)) => Ok(store),
+    Ok(_) => Err(anyhow!(
+      "{} is not a camera config file: expected a JSON object",
+      path
+    )),
+    Err(e) => Err(anyhow!("{} is not valid JSON: {}", path, e)),
+  }
+}
+
+/// Store `profile_json` under `stable_id`, creating the file if it's missing
+/// Profiles of other cameras in the file are kept as they are.
+pub fn save_profile(path: &str, stable_id: &str, profile_json: &str) -> Result<()> {
+  let profile: Value =
+    serde_json::from_str(profile_json).map_err(|e| anyhow!("Profile is not valid JSON: {}", e))?;
+  let mut file = OpenOptions::new()
+    .read(true)
+    .write(true)
+    .create(true)
+    .truncate(false)
+    .open(path)
+    .map_err(|e| anyhow!("Opening {}: {}", path, e))?;
+  file
+    .lock()
+    .map_err(|e| anyhow!("Locking {}: {}", path, e))?;
+
+  let mut store = read_store(&mut file, path)?;
+  store.insert(stable_id.to_string(), profile);
+  let contents = serde_json::to_string_pretty(&Value::Object(store))?;
+
+  file
+    .set_len(0)
+    .and_then(|_| file.seek(SeekFrom::Start(0)))
+    .and_then(|_| file.write_all(contents.as_bytes()))
+    .and_then(|_| file.sync_all())
+    .map_err(|e| anyhow!("Writing {}: {}", path, e))
+}
+
+/// Read the profile stored under `stable_id` as JSON text
+/// A missing file or id yields `None`.
+pub fn load_profile(path: &str, stable_id: &str) -> Result<Option<String>> {
+  let mut file = match File::open(path) {
+    Ok(file) => file,
+    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+    Err(e) => return Err(anyhow!("Opening {}: {}", path, e)),
+  };
+  file
+    .lock_shared()
+    .map_err(|e| anyhow!("Locking {}: {}", path, e))?;
+
+  let store = read_store(&mut file, path)?;
+  store
+    .get(stable_id)
+    .map(|profile| serde_json::to_string(profile).map_err(Into::into))
+    .transpose()
+}
@@ -0,0 +1,171 @@
+//! Camera control helpers for nokhwa-node
+//!
+//! This module maps camera control descriptors to device-independent values,
+//! coordinates captures with control changes (white balance locking), and
+//! reads and writes control presets.
+
+use anyhow::anyhow;
+use nokhwa::utils::{
+  CameraControl, ControlValueDescription, ControlValueSetter, KnownCameraControl,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types;
+
+/// Frames captured while auto white balance converges before it is locked
+pub const WHITE_BALANCE_WARMUP_FRAMES: u32 = 15;
+
+/// V4L2_CID_AUTO_WHITE_BALANCE, which nokhwa only reaches through `Other`
+#[cfg(target_os = "linux")]
+const V4L2_CID_AUTO_WHITE_BALANCE: u128 = 0x0098_090c;
+
+/// Reads a control's current value as a fraction of its native range
+/// The mapping is linear between the descriptor's min (0.0) and max (1.0);
+/// booleans map to 0.0 / 1.0.
+pub fn normalized_value(control: &CameraControl) -> anyhow::Result<f64> {
+  let (value, min, max) = match control.description() {
+    ControlValueDescription::IntegerRange {
+      min, max, value, ..
+    } => (*value as f64, *min as f64, *max as f64),
+    ControlValueDescription::FloatRange {
+      min, max, value, ..
+    } => (*value, *min, *max),
+    ControlValueDescription::Boolean { value, .. } => return Ok(if *value { 1.0 } else { 0.0 }),
+    other => return Err(no_range_error(control, other)),
+  };
+
+  if max <= min {
+    return Ok(0.0);
+  }
+  Ok(((value - min) / (max - min)).clamp(0.0, 1.0))
+}
+
+/// Converts a 0.0-1.0 fraction into a native value for `control`
+/// Inputs are clamped to 0.0-1.0; integer controls are snapped to their step.
+pub fn denormalize_value(
+  control: &CameraControl,
+  fraction: f64,
+) -> anyhow::Result<ControlValueSetter> {
+  if !fraction.is_finite() {
+    return Err(anyhow!("Invalid normalized value: {}", fraction));
+  }
+  let fraction = fraction.clamp(0.0, 1.0);
+
+  match control.description() {
+    ControlValueDescription::IntegerRange { min, max, step, .. } => {
+      let step = (*step).max(1);
+      let steps = ((max - min) as f64 * fraction / step as f64).round() as i64;
+      Ok(ControlValueSetter::Integer((min + steps * step).min(*max)))
+    }
+    ControlValueDescription::FloatRange { min, max, .. } => {
+      Ok(ControlValueSetter::Float(min + (max - min) * fraction))
+    }
+    ControlValueDescription::Boolean { .. } => Ok(ControlValueSetter::Boolean(fraction >= 0.5)),
+    other => Err(no_range_error(control, other)),
+  }
+}
+
+fn no_range_error(control: &CameraControl, description: &ControlValueDescription) -> anyhow::Error {
+  anyhow!(
+    "Control {} has no min/max range to normalize against ({:?})",
+    control.name(),
+    description
+  )
+}
+
+/// Lets auto white balance settle, then freezes it at the value it converged to
+/// Auto mode is switched on and off explicitly where nokhwa can reach the switch
+/// (V4L2). Elsewhere the camera's current auto mode is relied upon, and writing
+/// the value switches the backend to manual mode.
+pub fn lock_white_balance(camera: &mut nokhwa::Camera, warmup_frames: u32) -> anyhow::Result<()> {
+  let descriptor = camera
+    .camera_control(KnownCameraControl::WhiteBalance)
+    .map_err(|e| anyhow!("Camera doesn't support white balance control: {}", e))?;
+  if !matches!(
+    descriptor.description(),
+    ControlValueDescription::IntegerRange { .. }
+      | ControlValueDescription::Integer { .. }
+      | ControlValueDescription::FloatRange { .. }
+      | ControlValueDescription::Float { .. }
+  ) {
+    return Err(anyhow!(
+      "Camera doesn't support manual white balance ({:?})",
+      descriptor.description()
+    ));
+  }
+
+  set_auto_white_balance(camera, true);
+  for _ in 0..warmup_frames {
+    camera
+      .frame()
+      .map_err(|e| anyhow!("Capturing warmup frame: {}", e))?;
+  }
+
+  let converged = camera
+    .camera_control(KnownCameraControl::WhiteBalance)
+    .map_err(|e| anyhow!("Reading white balance: {}", e))?
+    .value();
+  set_auto_white_balance(camera, false);
+  camera
+    .set_camera_control(KnownCameraControl::WhiteBalance, converged)
+    .map_err(|e| anyhow!("Camera doesn't support manual white balance: {}", e))
+}
+
+/// Best-effort switch of the auto white balance mode
+/// Boolean read-back differs between drivers, so the result isn't verified.
+#[cfg(target_os = "linux")]
+fn set_auto_white_balance(camera: &mut nokhwa::Camera, enabled: bool) {
+  let _ = camera.set_camera_control(
+    KnownCameraControl::Other(V4L2_CID_AUTO_WHITE_BALANCE),
+    ControlValueSetter::Boolean(enabled),
+  );
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_auto_white_balance(_camera: &mut nokhwa::Camera, _enabled: bool) {}
+
+/// One control in a JSON preset, e.g. `{ "control": "Brightness", "value": 128 }`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetEntry {
+  control: types::KnownCameraControl,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  other_id: Option<String>,
+  value: types::ControlValueSetter,
+}
+
+/// Serializes control values as a preset: a JSON array with one entry per control
+pub fn export_preset(snapshot: Vec<types::ControlSnapshot>) -> anyhow::Result<String> {
+  let entries: Vec<PresetEntry> = snapshot
+    .into_iter()
+    .map(|control| PresetEntry {
+      control: control.control,
+      other_id: control.other_id,
+      value: control.value,
+    })
+    .collect();
+  Ok(serde_json::to_string_pretty(&entries)?)
+}
+
+/// Parses a preset written by `export_preset` into the settings to apply
+/// Entries that don't parse, e.g. a control name from a newer version, are
+/// skipped and described in the returned warnings. Only JSON that isn't an
+/// array at all is an error.
+pub fn parse_preset(json: &str) -> anyhow::Result<(Vec<types::ControlSetting>, Vec<String>)> {
+  let entries: Vec<Value> =
+    serde_json::from_str(json).map_err(|e| anyhow!("Preset is not a JSON array: {}", e))?;
+  let mut settings = Vec::with_capacity(entries.len());
+  let mut warnings = Vec::new();
+  for (i, entry) in entries.into_iter().enumerate() {
+    match serde_json::from_value::<PresetEntry>(entry) {
+      Ok(entry) => settings.push(types::ControlSetting {
+        control: entry.control,
+        value: entry.value,
+        other_id: entry.other_id,
+      }),
+      Err(e) => warnings.push(format!("Skipped preset entry {}: {}", i, e)),
+    }
+  }
+  Ok((settings, warnings))
+}
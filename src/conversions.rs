@@ -3,12 +3,20 @@
 //! This module provides conversion functions between nokhwa types and N-API types,
 //! as well as frame format conversions.
 
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
+
 use anyhow::anyhow;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use nokhwa::pixel_format::*;
 use nokhwa::utils::FrameFormat;
 
+use crate::error::{
+  coded_error, coded_failure, is_busy_error, is_permission_error, nokhwa_error, nokhwa_error_code,
+  nokhwa_failure, permission_denied_error, with_cause, CodedFailure, ErrorCode,
+};
+use crate::timing::unix_time_ms;
 use crate::types::*;
 
 // ============================================================================
@@ -16,68 +24,512 @@ use crate::types::*;
 // ============================================================================
 
 /// RGBA frame data (internal representation)
+#[derive(Clone)]
 pub struct RgbaFrame {
   pub data: Vec<u8>,
   pub width: u32,
   pub height: u32,
+  /// See `Frame::timestamp_ms`
+  pub timestamp_ms: f64,
+}
+
+/// Pulls the next raw frame, making sure the camera reports a usable resolution
+/// Some backends report 0x0 right after the stream opens, so one more frame is
+/// tried before failing with `InvalidResolution`.
+pub fn next_buffer(camera: &mut nokhwa::Camera) -> anyhow::Result<nokhwa::Buffer> {
+  for _ in 0..2 {
+    let buffer = camera
+      .frame()
+      .map_err(|e| nokhwa_failure("Capturing frame", e))?;
+    let resolution = camera.resolution();
+    if resolution.width() > 0 && resolution.height() > 0 {
+      return Ok(buffer);
+    }
+  }
+
+  let resolution = camera.resolution();
+  Err(
+    CodedFailure {
+      code: ErrorCode::InvalidResolution,
+      message: format!(
+        "Camera reported an invalid resolution of {}x{}",
+        resolution.width(),
+        resolution.height()
+      ),
+    }
+    .into(),
+  )
+}
+
+/// Most buffered frames `next_fresh_buffer` skips before settling for the last one
+const MAX_STALE_FRAMES: u32 = 8;
+
+/// Pulls frames until one arrives that wasn't already waiting in the driver queue
+/// nokhwa has no non-blocking read, so a frame that comes back in less than a
+/// quarter of the frame interval counts as stale and the next one is pulled.
+pub fn next_fresh_buffer(camera: &mut nokhwa::Camera) -> anyhow::Result<nokhwa::Buffer> {
+  let stale_below = Duration::from_secs_f64(0.25 / camera.frame_rate().max(1) as f64);
+  let mut buffer = None;
+  for _ in 0..MAX_STALE_FRAMES {
+    let started = Instant::now();
+    let next = next_buffer(camera)?;
+    if started.elapsed() >= stale_below {
+      return Ok(next);
+    }
+    buffer = Some(next);
+  }
+  buffer.ok_or_else(|| anyhow!("No frame captured"))
+}
+
+/// Pulls the next raw frame the way `latency_mode` asks for
+pub fn next_buffer_for(
+  camera: &mut nokhwa::Camera,
+  latency_mode: LatencyMode,
+) -> anyhow::Result<nokhwa::Buffer> {
+  match latency_mode {
+    LatencyMode::Balanced => next_buffer(camera),
+    LatencyMode::LowestLatency => next_fresh_buffer(camera),
+  }
 }
 
 /// Captures a single frame from camera and converts it to RGBA format
-pub fn capture_frame(camera: &mut nokhwa::Camera) -> anyhow::Result<RgbaFrame> {
-  let buffer = camera
-    .frame()
-    .map_err(|e| anyhow!("Capturing frame: {}", e))?;
+/// YUYV and NV12 sources are converted with `color_space`
+pub fn capture_frame(
+  camera: &mut nokhwa::Camera,
+  color_space: ColorSpace,
+) -> anyhow::Result<RgbaFrame> {
+  let buffer = next_buffer(camera)?;
 
   let resolution = camera.resolution();
-  let width = resolution.width();
-  let height = resolution.height();
+  decode_to_rgba(
+    &buffer,
+    resolution.width(),
+    resolution.height(),
+    color_space,
+  )
+}
+
+/// Decodes a captured buffer like `decode_to_rgba`, skipping the decode when the
+/// source bytes are identical to the previous capture
+pub fn decode_to_rgba_cached(
+  buffer: &nokhwa::Buffer,
+  width: u32,
+  height: u32,
+  color_space: ColorSpace,
+  cache: &mut DecodeCache,
+) -> anyhow::Result<RgbaFrame> {
+  let hash = hash_bytes(buffer.buffer());
+  if let Some((cached_hash, frame)) = &cache.entry {
+    if *cached_hash == hash {
+      return Ok(RgbaFrame {
+        timestamp_ms: unix_time_ms(),
+        ..frame.clone()
+      });
+    }
+  }
+
+  let frame = decode_to_rgba(buffer, width, height, color_space)?;
+  cache.entry = Some((hash, frame.clone()));
+  Ok(frame)
+}
+
+/// Captures a frame and decodes it as RGBA into `dest`, reusing its allocation
+/// `dest` is resized to the frame size; returns the frame's width and height.
+pub fn capture_frame_into(
+  camera: &mut nokhwa::Camera,
+  color_space: ColorSpace,
+  latency_mode: LatencyMode,
+  dest: &mut Vec<u8>,
+) -> anyhow::Result<(u32, u32)> {
+  let buffer = next_buffer_for(camera, latency_mode)?;
+  let resolution = camera.resolution();
+  decode_frame_into(
+    &buffer,
+    resolution.width(),
+    resolution.height(),
+    color_space,
+    dest,
+  )
+}
+
+/// Decodes a captured buffer as RGBA into `dest`, like `capture_frame_into`
+/// `width` and `height` are the resolution the camera reports, see `decode_to_rgba`.
+pub fn decode_frame_into(
+  buffer: &nokhwa::Buffer,
+  width: u32,
+  height: u32,
+  color_space: ColorSpace,
+  dest: &mut Vec<u8>,
+) -> anyhow::Result<(u32, u32)> {
+  let buffer = checked_buffer(buffer, width, height)?;
+  let resolution = buffer.resolution();
+  let (width, height) = (resolution.width(), resolution.height());
   let source_format = buffer.source_frame_format();
 
-  // Decode buffer based on its format
-  let data = match source_format {
-    // MJPEG format - decode as RGBA directly
-    FrameFormat::MJPEG => {
-      let decoded = buffer
-        .decode_image::<RgbAFormat>()
-        .map_err(|e| anyhow!("Decoding MJPEG: {}", e))?;
-      decoded.to_vec()
-    }
-    // YUYV422 format - decode then convert to RGBA
-    FrameFormat::YUYV => {
-      let decoded = buffer
-        .decode_image::<YuyvFormat>()
-        .map_err(|e| anyhow!("Decoding YUYV: {}", e))?;
-      rgb_to_rgba(&decoded)
-    }
-    // NV12 format - decode as RGBA
+  // Only nokhwa's BT.601 decoders can write into an existing buffer
+  let yuv = matches!(source_format, FrameFormat::YUYV | FrameFormat::NV12);
+  if yuv && color_space != ColorSpace::Bt601 {
+    let frame = decode_to_rgba(&buffer, width, height, color_space)?;
+    dest.clear();
+    dest.extend_from_slice(&frame.data);
+    return Ok((width, height));
+  }
+
+  if source_format == FrameFormat::GRAY {
+    gray_to_rgba_into(buffer.buffer(), dest);
+    return Ok((width, height));
+  }
+
+  dest.resize(width as usize * height as usize * 4, 0);
+  buffer
+    .decode_image_to_buffer::<RgbAFormat>(dest)
+    .map_err(|e| {
+      coded_failure(
+        ErrorCode::DecodeFailed,
+        format!("Decoding {:?}: {}", source_format, e),
+      )
+    })?;
+  Ok((width, height))
+}
+
+/// Captures a frame and decodes only the `w`x`h` region at (`x`, `y`) to RGBA
+/// YUYV, NV12, RGB and GRAY sources are converted straight from the region's
+/// source pixels; MJPEG can't be partially decoded, so it is decoded in full
+/// and then cropped.
+pub fn capture_region(
+  camera: &mut nokhwa::Camera,
+  color_space: ColorSpace,
+  latency_mode: LatencyMode,
+  (x, y, w, h): (u32, u32, u32, u32),
+) -> anyhow::Result<RgbaFrame> {
+  let buffer = next_buffer_for(camera, latency_mode)?;
+  let timestamp_ms = unix_time_ms();
+
+  let resolution = camera.resolution();
+  let buffer = checked_buffer(&buffer, resolution.width(), resolution.height())?;
+  let resolution = buffer.resolution();
+  let (width, height) = (resolution.width(), resolution.height());
+  if w == 0 || h == 0 || x as u64 + w as u64 > width as u64 || y as u64 + h as u64 > height as u64 {
+    return Err(anyhow!(
+      "Region {}x{} at ({}, {}) is outside the {}x{} frame",
+      w,
+      h,
+      x,
+      y,
+      width,
+      height
+    ));
+  }
+
+  let data = buffer.buffer();
+  let rgba = match buffer.source_frame_format() {
+    FrameFormat::YUYV => crate::yuv::yuyv_region_to_rgba(data, width, x, y, w, h, color_space),
     FrameFormat::NV12 => {
-      let decoded = buffer
-        .decode_image::<RgbAFormat>()
-        .map_err(|e| anyhow!("Decoding NV12: {}", e))?;
-      decoded.to_vec()
-    }
-    // For other formats
-    _ => {
-      if let Ok(decoded) = buffer.decode_image::<RgbFormat>() {
-        rgb_to_rgba(&decoded)
-      } else if let Ok(decoded) = buffer.decode_image::<RgbAFormat>() {
-        decoded.to_vec()
-      } else {
-        return Err(anyhow!(
-          "Failed to decode frame with format {:?}",
-          source_format
-        ));
+      crate::yuv::nv12_region_to_rgba(data, width, height, x, y, w, h, color_space)
+    }
+    FrameFormat::RAWRGB => rgb_to_rgba(&crate::processing::crop(data, width, 3, x, y, w, h)),
+    FrameFormat::RAWBGR => {
+      let mut rgba = rgb_to_rgba(&crate::processing::crop(data, width, 3, x, y, w, h));
+      for px in rgba.chunks_exact_mut(4) {
+        px.swap(0, 2);
+      }
+      rgba
+    }
+    FrameFormat::GRAY => gray_to_rgba(&crate::processing::crop(data, width, 1, x, y, w, h)),
+    FrameFormat::MJPEG => {
+      let frame = decode_to_rgba(&buffer, width, height, color_space)?;
+      crate::processing::crop(&frame.data, frame.width, 4, x, y, w, h)
+    }
+  };
+
+  Ok(RgbaFrame {
+    data: rgba,
+    width: w,
+    height: h,
+    timestamp_ms,
+  })
+}
+
+/// Aspect ratios tried when working out the real size of a mis-reported frame
+const KNOWN_ASPECT_RATIOS: [(u32, u32); 5] = [(16, 9), (4, 3), (16, 10), (5, 4), (1, 1)];
+
+/// Returns the buffer with its true resolution
+/// Some cheap cameras advertise one resolution but deliver frames of another.
+/// The real size is read from the JPEG header for MJPEG, or derived from the
+/// byte count for uncompressed formats by trying common aspect ratios. When no
+/// size fits, fails with `ResolutionMismatch` rather than decoding garbage.
+fn checked_buffer<'a>(
+  buffer: &'a nokhwa::Buffer,
+  width: u32,
+  height: u32,
+) -> anyhow::Result<Cow<'a, nokhwa::Buffer>> {
+  let (true_width, true_height) = verified_resolution(buffer, width, height)?;
+  let resolution = buffer.resolution();
+  if (resolution.width(), resolution.height()) == (true_width, true_height) {
+    return Ok(Cow::Borrowed(buffer));
+  }
+  Ok(Cow::Owned(nokhwa::Buffer::new(
+    nokhwa::utils::Resolution::new(true_width, true_height),
+    buffer.buffer(),
+    buffer.source_frame_format(),
+  )))
+}
+
+fn verified_resolution(
+  buffer: &nokhwa::Buffer,
+  width: u32,
+  height: u32,
+) -> anyhow::Result<(u32, u32)> {
+  let data = buffer.buffer();
+  let format = buffer.source_frame_format();
+  // Twice the bytes per pixel, so NV12's 1.5 stays an integer
+  let double_bpp = match format {
+    // mozjpeg aborts the process on data it can't parse instead of returning an
+    // error, so anything without a readable JPEG header is rejected up front
+    FrameFormat::MJPEG => {
+      return jpeg_dimensions(data).ok_or_else(|| {
+        coded_failure(
+          ErrorCode::DecodeFailed,
+          format!(
+            "Invalid MJPEG frame: {} bytes without a readable JPEG header",
+            data.len()
+          ),
+        )
+      })
+    }
+    FrameFormat::YUYV => 4,
+    FrameFormat::NV12 => 3,
+    FrameFormat::GRAY => 2,
+    FrameFormat::RAWRGB | FrameFormat::RAWBGR => 6,
+  };
+
+  if data.len() == width as usize * height as usize * double_bpp / 2 {
+    return Ok((width, height));
+  }
+
+  if (data.len() * 2).is_multiple_of(double_bpp) {
+    let pixels = data.len() * 2 / double_bpp;
+    let divisor = gcd(width, height).max(1);
+    let reported = (width / divisor, height / divisor);
+    let candidates = std::iter::once(reported).chain(KNOWN_ASPECT_RATIOS);
+    for (aspect_w, aspect_h) in candidates {
+      let unit = aspect_w as usize * aspect_h as usize;
+      if unit == 0 || !pixels.is_multiple_of(unit) {
+        continue;
+      }
+      let scale = ((pixels / unit) as f64).sqrt().round() as usize;
+      let (w, h) = (aspect_w as usize * scale, aspect_h as usize * scale);
+      // Chroma subsampling needs even widths (and heights for NV12)
+      let even_ok = match format {
+        FrameFormat::YUYV => w.is_multiple_of(2),
+        FrameFormat::NV12 => w.is_multiple_of(2) && h.is_multiple_of(2),
+        _ => true,
+      };
+      if w * h == pixels && even_ok {
+        return Ok((w as u32, h as u32));
+      }
+    }
+  }
+
+  Err(
+    CodedFailure {
+      code: ErrorCode::ResolutionMismatch,
+      message: format!(
+        "Camera reported {}x{} but delivered {} bytes of {:?}, which matches no known frame size",
+        width,
+        height,
+        data.len(),
+        format
+      ),
+    }
+    .into(),
+  )
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+/// Reads the frame size from a JPEG's start-of-frame header
+pub fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+  if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+    return None;
+  }
+
+  let mut i = 2;
+  while i + 9 < data.len() {
+    if data[i] != 0xFF {
+      return None;
+    }
+    let marker = data[i + 1];
+    match marker {
+      // Fill byte before a marker
+      0xFF => i += 1,
+      // Markers without a length field
+      0x01 | 0xD0..=0xD7 => i += 2,
+      // SOF0-SOF15, except DHT, JPG and DAC which share the range
+      0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+        let height = u16::from_be_bytes([data[i + 5], data[i + 6]]);
+        let width = u16::from_be_bytes([data[i + 7], data[i + 8]]);
+        return Some((width as u32, height as u32));
+      }
+      _ => {
+        let length = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        i += 2 + length;
       }
     }
+  }
+  None
+}
+
+/// Decodes a captured buffer into RGBA based on its source format
+pub fn decode_to_rgba(
+  buffer: &nokhwa::Buffer,
+  width: u32,
+  height: u32,
+  color_space: ColorSpace,
+) -> anyhow::Result<RgbaFrame> {
+  // Callers decode right after dequeuing the frame, so this is when it arrived
+  let timestamp_ms = unix_time_ms();
+  let buffer = checked_buffer(buffer, width, height)?;
+  let resolution = buffer.resolution();
+  let (width, height) = (resolution.width(), resolution.height());
+  let source_format = buffer.source_frame_format();
+
+  // Decode buffer based on its format
+  let data = match source_format {
+    // nokhwa's YUV decoders only know BT.601, use our own matrix for anything else
+    FrameFormat::YUYV if color_space != ColorSpace::Bt601 => {
+      crate::yuv::yuyv_to_rgb(buffer.buffer(), color_space, true)
+        .map_err(|e| coded_failure(ErrorCode::DecodeFailed, format!("Decoding YUYV: {}", e)))?
+    }
+    FrameFormat::NV12 if color_space != ColorSpace::Bt601 => {
+      crate::yuv::nv12_to_rgb(buffer.buffer(), width, height, color_space, true)
+        .map_err(|e| coded_failure(ErrorCode::DecodeFailed, format!("Decoding NV12: {}", e)))?
+    }
+    // Single-channel sources (IR, monochrome industrial cameras) only need
+    // their luma repeated; checked_buffer already verified one byte per pixel
+    FrameFormat::GRAY => gray_to_rgba(buffer.buffer()),
+    // Everything else decodes straight to RGBA in one pass; `into_raw` takes over
+    // the decoder's allocation instead of copying it, and no format goes through
+    // an intermediate RGB buffer
+    _ => buffer
+      .decode_image::<RgbAFormat>()
+      .map_err(|e| {
+        coded_failure(
+          ErrorCode::DecodeFailed,
+          format!("Decoding {:?}: {}", source_format, e),
+        )
+      })?
+      .into_raw(),
   };
 
   Ok(RgbaFrame {
     data,
     width,
     height,
+    timestamp_ms,
   })
 }
 
+/// Decodes a raw frame to RGB (3 bytes per pixel), RGBA (4) or GRAY (1)
+/// RGB skips the alpha channel entirely. GRAY reads the luma of YUYV and NV12
+/// frames directly and computes Rec.601 luma from the RGB decode otherwise.
+/// Returns the pixels with the frame's true width and height.
+pub fn decode_as(
+  buffer: &nokhwa::Buffer,
+  width: u32,
+  height: u32,
+  color_space: ColorSpace,
+  format: crate::types::FrameFormat,
+) -> anyhow::Result<(Vec<u8>, u32, u32)> {
+  if format == crate::types::FrameFormat::RGBA {
+    let frame = decode_to_rgba(buffer, width, height, color_space)?;
+    return Ok((frame.data, frame.width, frame.height));
+  }
+
+  let buffer = checked_buffer(buffer, width, height)?;
+  let resolution = buffer.resolution();
+  let (width, height) = (resolution.width(), resolution.height());
+  let source_format = buffer.source_frame_format();
+  let decode_rgb = || {
+    buffer
+      .decode_image::<RgbFormat>()
+      .map(|image| image.into_raw())
+      .map_err(|e| {
+        coded_failure(
+          ErrorCode::DecodeFailed,
+          format!("Decoding {:?}: {}", source_format, e),
+        )
+      })
+  };
+
+  let data = match (format, source_format) {
+    (crate::types::FrameFormat::RGB, FrameFormat::YUYV) if color_space != ColorSpace::Bt601 => {
+      crate::yuv::yuyv_to_rgb(buffer.buffer(), color_space, false)
+        .map_err(|e| coded_failure(ErrorCode::DecodeFailed, format!("Decoding YUYV: {}", e)))?
+    }
+    (crate::types::FrameFormat::RGB, FrameFormat::NV12) if color_space != ColorSpace::Bt601 => {
+      crate::yuv::nv12_to_rgb(buffer.buffer(), width, height, color_space, false)
+        .map_err(|e| coded_failure(ErrorCode::DecodeFailed, format!("Decoding NV12: {}", e)))?
+    }
+    (crate::types::FrameFormat::RGB, _) => decode_rgb()?,
+    (crate::types::FrameFormat::GRAY, FrameFormat::GRAY) => buffer.buffer().to_vec(),
+    (crate::types::FrameFormat::GRAY, FrameFormat::YUYV) => {
+      crate::yuv::luma_plane(buffer.buffer(), width, height, true)?
+    }
+    (crate::types::FrameFormat::GRAY, FrameFormat::NV12) => {
+      crate::yuv::luma_plane(buffer.buffer(), width, height, false)?
+    }
+    (crate::types::FrameFormat::GRAY, _) => decode_rgb()?
+      .chunks_exact(3)
+      .map(|px| crate::analysis::luma(px[0], px[1], px[2]))
+      .collect(),
+    (other, _) => {
+      return Err(
+        CodedFailure {
+          code: ErrorCode::Napi(Status::InvalidArg),
+          message: format!(
+            "{:?} is not a decoded output format; use RGB, RGBA or GRAY",
+            other
+          ),
+        }
+        .into(),
+      )
+    }
+  };
+
+  Ok((data, width, height))
+}
+
+/// One-entry cache of the last decoded frame, keyed by a hash of its source bytes
+/// Static scenes (or paused cameras) repeat the same compressed frame, so this
+/// avoids decoding identical data over and over at the cost of one retained frame.
+#[derive(Default)]
+pub struct DecodeCache {
+  entry: Option<(u64, RgbaFrame)>,
+}
+
+impl DecodeCache {
+  /// Drop the cached frame, e.g. after a decode setting changed
+  pub fn clear(&mut self) {
+    self.entry = None;
+  }
+}
+
+/// Hashes frame bytes for change detection
+fn hash_bytes(data: &[u8]) -> u64 {
+  use std::hash::{DefaultHasher, Hasher};
+
+  let mut hasher = DefaultHasher::new();
+  hasher.write(data);
+  hasher.finish()
+}
+
 /// Converts an RGBA frame to a N-API Frame object with Buffer
 pub fn convert_to_napi_frame(rgba_frame: RgbaFrame) -> napi::Result<Frame> {
   let buffer = Buffer::from(rgba_frame.data);
@@ -86,6 +538,7 @@ pub fn convert_to_napi_frame(rgba_frame: RgbaFrame) -> napi::Result<Frame> {
     data: buffer,
     width: rgba_frame.width,
     height: rgba_frame.height,
+    timestamp_ms: rgba_frame.timestamp_ms,
   })
 }
 
@@ -102,12 +555,123 @@ fn rgb_to_rgba(rgb: &[u8]) -> Vec<u8> {
   rgba
 }
 
+/// Expands single-channel luma to opaque gray RGBA (r = g = b = luma)
+/// Monochrome and IR cameras deliver GRAY frames.
+#[inline]
+fn gray_to_rgba(luma: &[u8]) -> Vec<u8> {
+  let mut rgba = Vec::with_capacity(luma.len() * 4);
+  gray_to_rgba_into(luma, &mut rgba);
+  rgba
+}
+
+/// Like `gray_to_rgba`, replacing the contents of `dest` and reusing its allocation
+#[inline]
+fn gray_to_rgba_into(luma: &[u8], dest: &mut Vec<u8>) {
+  dest.clear();
+  for &y in luma {
+    dest.extend_from_slice(&[y, y, y, 255]);
+  }
+}
+
 /// Frame structure exported to JavaScript/TypeScript
+/// `data` is tightly packed 8-bit RGBA, row-major from the top-left (unless the
+/// output origin is BottomLeft), `width * height * 4` bytes with no row padding
+/// and alpha always 255. That is the layout `SharedPixelBuffer::<Rgba8Pixel>::
+/// clone_from_slice` in Slint and `ImageData` in browsers expect.
 #[napi(object)]
 pub struct Frame {
   pub data: Buffer,
   pub width: u32,
   pub height: u32,
+  /// Wall-clock time the frame arrived from the device, in Unix milliseconds
+  /// Taken from the system clock, not a monotonic one, so it can be matched with
+  /// timestamps from other processes and devices but jumps if the clock is set.
+  /// Frames built by the `rgba*` helpers carry the time they were built.
+  pub timestamp_ms: f64,
+}
+
+/// Frame delivered by `Camera::start_stream`, with the rate frames are arriving at
+#[napi(object)]
+pub struct StreamFrame {
+  pub data: Buffer,
+  pub width: u32,
+  pub height: u32,
+  /// Measured capture rate, or the format's advertised rate for the first frames
+  pub fps: f64,
+  /// Wall-clock arrival time in Unix milliseconds, see `Frame::timestamp_ms`
+  pub timestamp_ms: f64,
+  /// Frames this stream lost so far: failed captures or decodes, plus frames
+  /// discarded because the callback fell behind
+  pub dropped: u32,
+  /// Frames this stream tried to deliver so far, including the dropped ones;
+  /// frames skipped on purpose (paused, `deliverEveryNth`, `targetFps`) don't count
+  pub total: u32,
+}
+
+/// Frame shaped for the browser `ImageData` constructor
+/// `data` is always exactly `width * height * 4` bytes of tightly packed RGBA
+/// (no row padding), so `new ImageData(new Uint8ClampedArray(data.buffer,
+/// data.byteOffset, data.length), width, height)` works as-is.
+#[napi(object)]
+pub struct ImageDataFrame {
+  pub data: Uint8Array,
+  pub width: u32,
+  pub height: u32,
+  /// Always "srgb"
+  pub color_space: String,
+}
+
+/// Planar I420 (YUV 4:2:0) frame exported to JavaScript/TypeScript
+/// `y` is `width * height` bytes, `u` and `v` are `width / 2 * height / 2` bytes each.
+#[napi(object)]
+pub struct I420Frame {
+  pub y: Buffer,
+  pub u: Buffer,
+  pub v: Buffer,
+  pub width: u32,
+  pub height: u32,
+}
+
+/// Converts an RGBA frame to planar I420 using the given color matrix
+pub fn convert_to_i420(
+  rgba_frame: RgbaFrame,
+  color_space: ColorSpace,
+) -> anyhow::Result<I420Frame> {
+  let planes = crate::yuv::rgba_to_i420(
+    &rgba_frame.data,
+    rgba_frame.width,
+    rgba_frame.height,
+    color_space,
+  )?;
+  Ok(I420Frame {
+    y: Buffer::from(planes.y),
+    u: Buffer::from(planes.u),
+    v: Buffer::from(planes.v),
+    width: rgba_frame.width,
+    height: rgba_frame.height,
+  })
+}
+
+/// Converts an RGBA frame to an ImageData-compatible object
+/// Rejects frames whose buffer doesn't match the tightly packed RGBA size
+pub fn convert_to_image_data(rgba_frame: RgbaFrame) -> napi::Result<ImageDataFrame> {
+  let expected = rgba_frame.width as usize * rgba_frame.height as usize * 4;
+  if rgba_frame.data.len() != expected {
+    return Err(Error::from_reason(format!(
+      "Decoded frame is {} bytes, expected {} for {}x{} RGBA",
+      rgba_frame.data.len(),
+      expected,
+      rgba_frame.width,
+      rgba_frame.height
+    )));
+  }
+
+  Ok(ImageDataFrame {
+    data: Uint8Array::new(rgba_frame.data),
+    width: rgba_frame.width,
+    height: rgba_frame.height,
+    color_space: "srgb".to_string(),
+  })
 }
 
 // ============================================================================
@@ -130,6 +694,10 @@ pub fn convert_backend(backend: ApiBackend) -> nokhwa::utils::ApiBackend {
     ApiBackend::AVFoundation => nokhwa::utils::ApiBackend::AVFoundation,
     ApiBackend::OpenCv => nokhwa::utils::ApiBackend::OpenCv,
     ApiBackend::Browser => nokhwa::utils::ApiBackend::Browser,
+    ApiBackend::Video4Linux => nokhwa::utils::ApiBackend::Video4Linux,
+    ApiBackend::UniversalVideoClass => nokhwa::utils::ApiBackend::UniversalVideoClass,
+    ApiBackend::GStreamer => nokhwa::utils::ApiBackend::GStreamer,
+    ApiBackend::Network => nokhwa::utils::ApiBackend::Network,
   }
 }
 
@@ -141,10 +709,10 @@ pub fn convert_backend_to_napi(backend: nokhwa::utils::ApiBackend) -> ApiBackend
     nokhwa::utils::ApiBackend::AVFoundation => ApiBackend::AVFoundation,
     nokhwa::utils::ApiBackend::OpenCv => ApiBackend::OpenCv,
     nokhwa::utils::ApiBackend::Browser => ApiBackend::Browser,
-    nokhwa::utils::ApiBackend::Video4Linux => ApiBackend::Auto, // Fallback
-    nokhwa::utils::ApiBackend::UniversalVideoClass => ApiBackend::Auto, // Fallback
-    nokhwa::utils::ApiBackend::GStreamer => ApiBackend::Auto,   // Fallback
-    nokhwa::utils::ApiBackend::Network => ApiBackend::Auto,     // Fallback
+    nokhwa::utils::ApiBackend::Video4Linux => ApiBackend::Video4Linux,
+    nokhwa::utils::ApiBackend::UniversalVideoClass => ApiBackend::UniversalVideoClass,
+    nokhwa::utils::ApiBackend::GStreamer => ApiBackend::GStreamer,
+    nokhwa::utils::ApiBackend::Network => ApiBackend::Network,
   }
 }
 
@@ -176,6 +744,14 @@ pub fn convert_requested_format(
     RequestedFormatType::AbsoluteHighestFrameRate => {
       nokhwa::utils::RequestedFormatType::AbsoluteHighestFrameRate
     }
+    RequestedFormatType::None => nokhwa::utils::RequestedFormatType::None,
+    // Both are resolved against the device's formats by `apply_format_request`
+    RequestedFormatType::Closest | RequestedFormatType::Exact => {
+      return Err(napi::Error::new(
+        Status::InvalidArg,
+        "Closest and Exact requests need a target: set width, height or frameRate",
+      ))
+    }
   };
 
   // NV12, MJPEG are runtime formats that cameras report but cannot be directly requested.
@@ -210,11 +786,253 @@ pub fn convert_requested_format(
   Ok(request)
 }
 
+/// Whether `config` names a target width, height or frame rate
+pub fn has_format_target(config: &RequestedFormatConfig) -> bool {
+  config.width.is_some() || config.height.is_some() || config.frame_rate.is_some()
+}
+
+/// Maps a pixel format to the nokhwa source format a device would deliver
+/// RGBA is decoded output only and has no source format.
+pub fn to_source_format(format: crate::types::FrameFormat) -> Option<FrameFormat> {
+  match format {
+    crate::types::FrameFormat::MJPEG => Some(FrameFormat::MJPEG),
+    crate::types::FrameFormat::YUYV => Some(FrameFormat::YUYV),
+    crate::types::FrameFormat::NV12 => Some(FrameFormat::NV12),
+    crate::types::FrameFormat::RGB => Some(FrameFormat::RAWRGB),
+    crate::types::FrameFormat::GRAY => Some(FrameFormat::GRAY),
+    crate::types::FrameFormat::RGBA => None,
+  }
+}
+
+/// Formats of the requested source format, nearest to the target set on `config` first
+/// Candidates rank by squared distance to the target resolution, then by
+/// distance to the target frame rate; unset fields rank higher values first.
+/// With a source `format` set, formats of other pixel formats aren't candidates.
+fn rank_formats(
+  config: &RequestedFormatConfig,
+  formats: &[nokhwa::utils::CameraFormat],
+) -> Vec<nokhwa::utils::CameraFormat> {
+  // RGBA, like no format at all, matches any source
+  let source = config.format.and_then(to_source_format);
+  let mut ranked: Vec<_> = formats
+    .iter()
+    .filter(|f| source.is_none_or(|source| f.format() == source))
+    .copied()
+    .collect();
+  ranked.sort_by_key(|f| {
+    let dx = config.width.map_or(0, |w| f.width().abs_diff(w) as u64);
+    let dy = config.height.map_or(0, |h| f.height().abs_diff(h) as u64);
+    let dfps = config
+      .frame_rate
+      .map_or(0, |fps| f.frame_rate().abs_diff(fps));
+    (
+      dx * dx + dy * dy,
+      dfps,
+      std::cmp::Reverse(f.width() as u64 * f.height() as u64),
+      std::cmp::Reverse(f.frame_rate()),
+    )
+  });
+  ranked
+}
+
+/// Picks the device format closest to the target set on `config`
+pub fn closest_format(
+  config: &RequestedFormatConfig,
+  formats: &[nokhwa::utils::CameraFormat],
+) -> Option<nokhwa::utils::CameraFormat> {
+  rank_formats(config, formats).into_iter().next()
+}
+
+/// Device formats listed when an `Exact` request has no match
+const NEAREST_FORMATS_LISTED: usize = 3;
+
+/// Picks a device format matching every target field set on `config`
+/// Fails listing the nearest formats the device does offer, so a mode the
+/// device lacks is never silently replaced by another one.
+pub fn exact_format(
+  config: &RequestedFormatConfig,
+  formats: &[nokhwa::utils::CameraFormat],
+) -> anyhow::Result<nokhwa::utils::CameraFormat> {
+  let ranked = rank_formats(config, formats);
+  let matches = |f: &nokhwa::utils::CameraFormat| {
+    config.width.is_none_or(|w| f.width() == w)
+      && config.height.is_none_or(|h| f.height() == h)
+      && config.frame_rate.is_none_or(|fps| f.frame_rate() == fps)
+  };
+  if let Some(format) = ranked.iter().find(|f| matches(f)) {
+    return Ok(*format);
+  }
+
+  let field = |value: Option<u32>| value.map_or("any".to_string(), |v| v.to_string());
+  let target = format!(
+    "{}x{} at {} fps{}",
+    field(config.width),
+    field(config.height),
+    field(config.frame_rate),
+    config
+      .format
+      .map_or(String::new(), |format| format!(" ({:?})", format))
+  );
+  if ranked.is_empty() {
+    return Err(coded_failure(
+      ErrorCode::FormatUnsupported,
+      format!("No format matches {}: camera lists no formats", target),
+    ));
+  }
+  let nearest: Vec<String> = ranked
+    .iter()
+    .take(NEAREST_FORMATS_LISTED)
+    .map(|f| {
+      format!(
+        "{}x{} at {} fps ({:?})",
+        f.width(),
+        f.height(),
+        f.frame_rate(),
+        convert_frame_format(f.format())
+      )
+    })
+    .collect();
+  Err(coded_failure(
+    ErrorCode::FormatUnsupported,
+    format!(
+      "No format matches {} exactly; nearest available: {}",
+      target,
+      nearest.join(", ")
+    ),
+  ))
+}
+
+/// Applies a format request to an open camera and returns the negotiated format
+/// Requests with a target are matched against the device's format list here:
+/// nokhwa's own `Closest` only searches the exact target resolution, and its
+/// `Exact` accepts modes the device doesn't list.
+pub fn apply_format_request(
+  camera: &mut nokhwa::Camera,
+  config: RequestedFormatConfig,
+) -> anyhow::Result<nokhwa::utils::CameraFormat> {
+  if !has_format_target(&config) {
+    let request = convert_requested_format(config)
+      .map_err(|e| coded_failure(ErrorCode::Napi(e.status), &e.reason))?;
+    return camera
+      .set_camera_requset(request)
+      .map_err(|e| nokhwa_failure("Failed to set camera format", e));
+  }
+
+  let formats = camera
+    .compatible_camera_formats()
+    .map_err(|e| nokhwa_failure("Failed to get compatible formats", e))?;
+  let chosen = match config.request_type {
+    RequestedFormatType::Exact => exact_format(&config, &formats)?,
+    _ => closest_format(&config, &formats).ok_or_else(|| match config.format {
+      Some(format) => coded_failure(
+        ErrorCode::FormatUnsupported,
+        format!("Camera lists no {:?} formats", format),
+      ),
+      None => coded_failure(ErrorCode::FormatUnsupported, "Camera lists no formats"),
+    })?,
+  };
+  set_listed_format(camera, chosen)
+}
+
+/// How far, relative to the request, the nearest frame rate may be for `set_closest_frame_rate`
+const FRAME_RATE_TOLERANCE: f64 = 0.25;
+
+/// Switches to the frame rate nearest `fps` at the current resolution and pixel format
+/// Fails with `FormatUnsupported` if the device lists no mode at the current
+/// resolution and pixel format, or if the nearest rate is more than 25% off
+/// `fps` (e.g. 30 when asking for 120).
+pub fn set_closest_frame_rate(
+  camera: &mut nokhwa::Camera,
+  fps: u32,
+) -> anyhow::Result<nokhwa::utils::CameraFormat> {
+  let current = camera.camera_format();
+  let config = RequestedFormatConfig {
+    request_type: RequestedFormatType::Closest,
+    format: Some(convert_frame_format(current.format())),
+    width: Some(current.width()),
+    height: Some(current.height()),
+    frame_rate: Some(fps),
+  };
+  let formats = camera
+    .compatible_camera_formats()
+    .map_err(|e| nokhwa_failure("Failed to get compatible formats", e))?;
+  let chosen = closest_format(&config, &formats)
+    .filter(|f| f.resolution() == current.resolution() && f.format() == current.format())
+    .ok_or_else(|| {
+      coded_failure(
+        ErrorCode::FormatUnsupported,
+        format!(
+          "Camera lists no {:?} mode at {}x{} to change the frame rate of",
+          current.format(),
+          current.width(),
+          current.height()
+        ),
+      )
+    })?;
+  if chosen.frame_rate().abs_diff(fps) as f64 > fps as f64 * FRAME_RATE_TOLERANCE {
+    return Err(coded_failure(
+      ErrorCode::FormatUnsupported,
+      format!(
+        "Camera offers no frame rate near {} fps at {}x{}; the nearest is {} fps",
+        fps,
+        current.width(),
+        current.height(),
+        chosen.frame_rate()
+      ),
+    ));
+  }
+  set_listed_format(camera, chosen)
+}
+
+/// Sets a format taken from the device's own format list
+fn set_listed_format(
+  camera: &mut nokhwa::Camera,
+  chosen: nokhwa::utils::CameraFormat,
+) -> anyhow::Result<nokhwa::utils::CameraFormat> {
+  use nokhwa::utils::RequestedFormat;
+
+  let source = [chosen.format()];
+  camera
+    .set_camera_requset(RequestedFormat::with_formats(
+      nokhwa::utils::RequestedFormatType::Exact(chosen),
+      &source,
+    ))
+    .map_err(|e| nokhwa_failure(&format!("Failed to set camera format {}", chosen), e))
+}
+
 /// Convert nokhwa camera control to N-API camera control
 pub fn convert_camera_control(control: nokhwa::utils::CameraControl) -> CameraControl {
+  use nokhwa::utils::ControlValueDescription;
+
+  let (value, minimum, maximum, step, default) = match *control.description() {
+    ControlValueDescription::IntegerRange {
+      min,
+      max,
+      value,
+      step,
+      default,
+    } => (Some(value), Some(min), Some(max), Some(step), Some(default)),
+    ControlValueDescription::Integer {
+      value,
+      default,
+      step,
+    } => (Some(value), None, None, Some(step), Some(default)),
+    ControlValueDescription::Enum { value, default, .. } => {
+      (Some(value), None, None, None, Some(default))
+    }
+    _ => (None, None, None, None, None),
+  };
+
   CameraControl {
     name: control.name().to_string(),
     control_type: format!("{:?}", control.control()),
+    other_id: other_control_id(control.control()),
+    value,
+    minimum,
+    maximum,
+    step,
+    default,
+    active: control.active(),
   }
 }
 
@@ -240,11 +1058,21 @@ pub fn convert_known_control(control: nokhwa::utils::KnownCameraControl) -> Know
   }
 }
 
+/// Id of a vendor-specific control, as a decimal string since it may not fit a JS number
+pub fn other_control_id(control: nokhwa::utils::KnownCameraControl) -> Option<String> {
+  match control {
+    nokhwa::utils::KnownCameraControl::Other(id) => Some(id.to_string()),
+    _ => None,
+  }
+}
+
 /// Convert N-API known control to nokhwa known control
+/// `Other` needs the vendor id reported as `otherId`; the other variants ignore it.
 pub fn convert_known_control_to_nokhwa(
   control: KnownCameraControl,
-) -> nokhwa::utils::KnownCameraControl {
-  match control {
+  other_id: Option<&str>,
+) -> Result<nokhwa::utils::KnownCameraControl> {
+  Ok(match control {
     KnownCameraControl::Brightness => nokhwa::utils::KnownCameraControl::Brightness,
     KnownCameraControl::Contrast => nokhwa::utils::KnownCameraControl::Contrast,
     KnownCameraControl::Saturation => nokhwa::utils::KnownCameraControl::Saturation,
@@ -260,8 +1088,22 @@ pub fn convert_known_control_to_nokhwa(
     KnownCameraControl::Exposure => nokhwa::utils::KnownCameraControl::Exposure,
     KnownCameraControl::Iris => nokhwa::utils::KnownCameraControl::Iris,
     KnownCameraControl::Focus => nokhwa::utils::KnownCameraControl::Focus,
-    KnownCameraControl::Other => nokhwa::utils::KnownCameraControl::Other(0), // Dummy ID
-  }
+    KnownCameraControl::Other => {
+      let id = other_id.ok_or_else(|| {
+        Error::new(
+          Status::InvalidArg,
+          "The Other control needs the otherId of the vendor control",
+        )
+      })?;
+      let id = id.parse::<u128>().map_err(|_| {
+        Error::new(
+          Status::InvalidArg,
+          format!("Invalid control id '{}': expected a decimal number", id),
+        )
+      })?;
+      nokhwa::utils::KnownCameraControl::Other(id)
+    }
+  })
 }
 
 /// Convert N-API control value to nokhwa control value
@@ -274,12 +1116,33 @@ pub fn convert_control_value(value: ControlValueSetter) -> nokhwa::utils::Contro
   }
 }
 
+/// Convert nokhwa control value to N-API control value
+/// Returns None for value kinds that have no N-API counterpart (bytes, points, ...)
+pub fn convert_control_value_to_napi(
+  value: nokhwa::utils::ControlValueSetter,
+) -> Option<ControlValueSetter> {
+  match value {
+    nokhwa::utils::ControlValueSetter::Integer(i) => Some(ControlValueSetter::Integer(i)),
+    nokhwa::utils::ControlValueSetter::EnumValue(i) => Some(ControlValueSetter::Integer(i)),
+    nokhwa::utils::ControlValueSetter::Float(f) => Some(ControlValueSetter::Float(f)),
+    nokhwa::utils::ControlValueSetter::Boolean(b) => Some(ControlValueSetter::Boolean(b)),
+    nokhwa::utils::ControlValueSetter::String(s) => Some(ControlValueSetter::String(s)),
+    _ => None,
+  }
+}
+
 /// Create camera with format fallback
 /// Uses automatic format selection with priority on high frame rates (30fps+)
 /// Falls back to different format types and request strategies
+/// Every attempt goes through `backend` (`ApiBackend::Auto` lets nokhwa choose)
+/// Permission errors stop the fallback early with a `PermissionDenied` code,
+/// since no other format can succeed either. If any attempt found the device
+/// busy, the failure is reported as `DeviceBusy` so callers can retry;
+/// otherwise the code classifies the last attempt's error.
 pub fn create_camera_with_fallback(
   index: nokhwa::utils::CameraIndex,
-) -> napi::Result<nokhwa::Camera> {
+  backend: nokhwa::utils::ApiBackend,
+) -> Result<nokhwa::Camera, ErrorCode> {
   use nokhwa::pixel_format::{LumaFormat, RgbAFormat, RgbFormat, YuyvFormat};
   use nokhwa::utils::{RequestedFormat, RequestedFormatType};
 
@@ -295,6 +1158,8 @@ pub fn create_camera_with_fallback(
     (RequestedFormatType::AbsoluteHighestFrameRate, "Luma"),
   ];
 
+  let mut busy = false;
+  let mut last_error = None;
   for (strategy, format_name) in strategies {
     let request = match format_name {
       "RgbA" => RequestedFormat::new::<RgbAFormat>(strategy),
@@ -304,14 +1169,100 @@ pub fn create_camera_with_fallback(
       _ => RequestedFormat::new::<RgbFormat>(strategy),
     };
 
-    if let Ok(mut cam) = nokhwa::Camera::new(index.clone(), request) {
-      if cam.open_stream().is_ok() {
-        return Ok(cam);
+    let opened =
+      nokhwa::Camera::with_backend(index.clone(), request, backend).and_then(|mut cam| {
+        cam.open_stream()?;
+        Ok(cam)
+      });
+    match opened {
+      Ok(cam) => return Ok(cam),
+      Err(e) if is_permission_error(&e) => return Err(permission_denied_error(&e)),
+      Err(e) => {
+        busy |= is_busy_error(&e);
+        last_error = Some(e);
       }
     }
   }
 
-  Err(Error::from_reason(
-    "Failed to create camera: No compatible format found or device is busy".to_string(),
-  ))
+  let code = match &last_error {
+    _ if busy => ErrorCode::DeviceBusy,
+    Some(e) => nokhwa_error_code(e),
+    None => ErrorCode::Napi(Status::GenericFailure),
+  };
+  let err = match code {
+    ErrorCode::DeviceBusy => coded_error(
+      code,
+      "Failed to create camera: device is busy (in use by another process?)",
+    ),
+    ErrorCode::DeviceNotFound => coded_error(code, "Failed to create camera: no such device"),
+    ErrorCode::FormatUnsupported => coded_error(
+      code,
+      "Failed to create camera: device offers none of the fallback formats",
+    ),
+    _ => coded_error(
+      code,
+      "Failed to create camera: No compatible format found or device is busy",
+    ),
+  };
+  // Keep the backend's own message of the last attempt for debugging
+  Err(match last_error {
+    Some(e) => with_cause(err, e),
+    None => err,
+  })
+}
+
+/// Runs `open` again while it fails with `DeviceBusy`, per `retry`
+/// A device that was just released by another process often needs a moment
+/// before it can be opened. Other errors (unsupported format, permissions)
+/// can't be fixed by waiting and are returned right away.
+pub fn retry_when_busy<T>(
+  retry: Option<&RetryConfig>,
+  mut open: impl FnMut() -> Result<T, ErrorCode>,
+) -> Result<T, ErrorCode> {
+  let (retries, delay_ms) = retry.map_or((0, 0), |r| (r.retries, r.delay_ms.unwrap_or(200)));
+  let mut attempt = 0;
+  loop {
+    match open() {
+      Err(e) if e.status == ErrorCode::DeviceBusy && attempt < retries => {
+        attempt += 1;
+        std::thread::sleep(std::time::Duration::from_millis(delay_ms as u64));
+      }
+      result => return result,
+    }
+  }
+}
+
+/// Create a camera that only accepts the given source frame format
+/// Skips the fallback chain entirely and fails fast when the device cannot
+/// deliver `format`. RGBA is a decoded output format, not something a device
+/// produces, so it cannot be requested here.
+pub fn create_camera_with_format(
+  index: nokhwa::utils::CameraIndex,
+  format: crate::types::FrameFormat,
+) -> Result<nokhwa::Camera, ErrorCode> {
+  use nokhwa::utils::{RequestedFormat, RequestedFormatType};
+
+  let Some(source_format) = to_source_format(format) else {
+    return Err(coded_error(
+      ErrorCode::Napi(Status::InvalidArg),
+      "RGBA is not a camera source format; use MJPEG, YUYV, NV12, RGB or GRAY",
+    ));
+  };
+
+  let formats = [source_format];
+  let request =
+    RequestedFormat::with_formats(RequestedFormatType::AbsoluteHighestFrameRate, &formats);
+  let mut cam = nokhwa::Camera::new(index, request).map_err(|e| {
+    nokhwa_error(
+      &format!("Failed to create camera with format {:?}", source_format),
+      &e,
+    )
+  })?;
+  cam.open_stream().map_err(|e| {
+    nokhwa_error(
+      &format!("Failed to open stream with format {:?}", source_format),
+      &e,
+    )
+  })?;
+  Ok(cam)
 }
@@ -0,0 +1,251 @@
+//! Encoding module for nokhwa-node
+//!
+//! This module provides the image encoders used to turn decoded frames into
+//! ready-to-send or ready-to-save byte streams.
+
+use std::io::Cursor;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+use anyhow::anyhow;
+use exif::experimental::Writer;
+use exif::{Field, In, Tag, Value};
+use image::codecs::png::PngEncoder;
+use image::{save_buffer, ExtendedColorType, ImageEncoder, ImageFormat};
+use img_parts::jpeg::{markers, Jpeg, JpegSegment};
+use mozjpeg::{ColorSpace, Compress};
+
+use crate::processing::rgba_to_rgb;
+use crate::types::JpegMetadata;
+
+/// Encodes an RGB buffer as a baseline JPEG
+/// `quality` is clamped to 1-100
+pub fn encode_jpeg(rgb: &[u8], width: u32, height: u32, quality: u32) -> anyhow::Result<Vec<u8>> {
+  compress_jpeg(rgb, width, height, quality, 3)
+}
+
+/// Encodes a single-channel gray buffer as a baseline JPEG
+/// `quality` is clamped to 1-100
+pub fn encode_jpeg_gray(
+  gray: &[u8],
+  width: u32,
+  height: u32,
+  quality: u32,
+) -> anyhow::Result<Vec<u8>> {
+  compress_jpeg(gray, width, height, quality, 1)
+}
+
+fn compress_jpeg(
+  pixels: &[u8],
+  width: u32,
+  height: u32,
+  quality: u32,
+  channels: usize,
+) -> anyhow::Result<Vec<u8>> {
+  let expected = width as usize * height as usize * channels;
+  if width == 0 || height == 0 || pixels.len() != expected {
+    return Err(anyhow!(
+      "Cannot encode JPEG: expected {} bytes for {}x{}, got {}",
+      expected,
+      width,
+      height,
+      pixels.len()
+    ));
+  }
+  let color_space = if channels == 1 {
+    ColorSpace::JCS_GRAYSCALE
+  } else {
+    ColorSpace::JCS_RGB
+  };
+
+  // mozjpeg reports encoder errors by unwinding, so keep them from crossing into N-API
+  let result = catch_unwind(AssertUnwindSafe(|| -> std::io::Result<Vec<u8>> {
+    let mut compress = Compress::new(color_space);
+    compress.set_size(width as usize, height as usize);
+    compress.set_quality(quality.clamp(1, 100) as f32);
+    let mut started = compress.start_compress(Vec::new())?;
+    started.write_scanlines(pixels)?;
+    started.finish()
+  }));
+
+  match result {
+    Ok(Ok(jpeg)) => Ok(jpeg),
+    Ok(Err(e)) => Err(anyhow!("Encoding JPEG: {}", e)),
+    Err(_) => Err(anyhow!("JPEG encoding panicked internally")),
+  }
+}
+
+// ============================================================================
+// PNG
+// ============================================================================
+
+/// Encodes an RGBA (4 channels), RGB (3 channels) or gray (1 channel) buffer as PNG
+pub fn encode_png(
+  pixels: &[u8],
+  width: u32,
+  height: u32,
+  channels: usize,
+) -> anyhow::Result<Vec<u8>> {
+  let color_type = match channels {
+    1 => ExtendedColorType::L8,
+    3 => ExtendedColorType::Rgb8,
+    4 => ExtendedColorType::Rgba8,
+    _ => return Err(anyhow!("Cannot encode PNG with {} channels", channels)),
+  };
+  let mut png = Vec::new();
+  PngEncoder::new(&mut png)
+    .write_image(pixels, width, height, color_type)
+    .map_err(|e| anyhow!("Cannot encode PNG: {}", e))?;
+  Ok(png)
+}
+
+/// JPEG quality used when saving to a file
+const SAVE_JPEG_QUALITY: u32 = 90;
+
+/// Picks the `save_image` format from the extension of `path`
+/// Only PNG, JPEG and BMP are accepted.
+pub fn save_format(path: &Path) -> anyhow::Result<ImageFormat> {
+  match ImageFormat::from_path(path) {
+    Ok(format @ (ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Bmp)) => Ok(format),
+    _ => Err(anyhow!(
+      "Cannot save {}: unknown image extension, use .png, .jpg or .bmp",
+      path.display()
+    )),
+  }
+}
+
+/// Writes an RGBA frame to `path` in a format returned by `save_format`
+/// JPEG goes through mozjpeg at quality 90 with `metadata` embedded as EXIF;
+/// BMP is written as 24-bit RGB.
+pub fn save_image(
+  path: &Path,
+  format: ImageFormat,
+  rgba: &[u8],
+  width: u32,
+  height: u32,
+  metadata: Option<&JpegMetadata>,
+) -> anyhow::Result<()> {
+  let result = match format {
+    ImageFormat::Jpeg => {
+      let mut jpeg = encode_jpeg(&rgba_to_rgb(rgba), width, height, SAVE_JPEG_QUALITY)?;
+      if let Some(metadata) = metadata {
+        jpeg = embed_exif(jpeg, metadata)?;
+      }
+      return std::fs::write(path, jpeg).map_err(|e| anyhow!("Writing {}: {}", path.display(), e));
+    }
+    ImageFormat::Bmp => save_buffer(
+      path,
+      &rgba_to_rgb(rgba),
+      width,
+      height,
+      ExtendedColorType::Rgb8,
+    ),
+    _ => save_buffer(path, rgba, width, height, ExtendedColorType::Rgba8),
+  };
+  result.map_err(|e| anyhow!("Writing {}: {}", path.display(), e))
+}
+
+// ============================================================================
+// EXIF Metadata
+// ============================================================================
+
+/// Embeds `metadata` as an EXIF APP1 segment into a JPEG
+/// The segment goes right after SOI, or after the JFIF APP0 segment if there is one.
+/// Metadata without any field set leaves the JPEG untouched.
+pub fn embed_exif(jpeg: Vec<u8>, metadata: &JpegMetadata) -> anyhow::Result<Vec<u8>> {
+  let fields = exif_fields(metadata)?;
+  if fields.is_empty() {
+    return Ok(jpeg);
+  }
+  let mut image = Jpeg::from_bytes(jpeg.into()).map_err(|e| anyhow!("Cannot embed EXIF: {}", e))?;
+
+  let mut writer = Writer::new();
+  for field in &fields {
+    writer.push_field(field);
+  }
+  let mut tiff = Cursor::new(Vec::new());
+  writer
+    .write(&mut tiff, false)
+    .map_err(|e| anyhow!("Cannot embed EXIF: {}", e))?;
+
+  let mut contents = b"Exif\0\0".to_vec();
+  contents.extend_from_slice(tiff.get_ref());
+  // The segment length counts its own two bytes
+  if contents.len() + 2 > u16::MAX as usize {
+    return Err(anyhow!("Cannot embed EXIF: metadata is too large"));
+  }
+
+  let segments = image.segments_mut();
+  let at = match segments.first() {
+    Some(first) if first.marker() == markers::APP0 => 1,
+    _ => 0,
+  };
+  segments.insert(
+    at,
+    JpegSegment::new_with_contents(markers::APP1, contents.into()),
+  );
+  Ok(image.encoder().bytes().to_vec())
+}
+
+/// The EXIF fields for the set parts of `metadata`, all in the primary IFD
+fn exif_fields(metadata: &JpegMetadata) -> anyhow::Result<Vec<Field>> {
+  let field = |tag, value| Field {
+    tag,
+    ifd_num: In::PRIMARY,
+    value,
+  };
+  let mut fields = Vec::new();
+  if let Some(name) = &metadata.camera_name {
+    fields.push(field(
+      Tag::Model,
+      Value::Ascii(vec![name.clone().into_bytes()]),
+    ));
+  }
+  if let Some(orientation) = metadata.orientation {
+    if !(1..=8).contains(&orientation) {
+      return Err(anyhow!(
+        "Invalid EXIF orientation {}: must be between 1 and 8",
+        orientation
+      ));
+    }
+    fields.push(field(
+      Tag::Orientation,
+      Value::Short(vec![orientation as u16]),
+    ));
+  }
+  if let Some(timestamp_ms) = metadata.timestamp_ms {
+    let date_time = exif_date_time(timestamp_ms)?.into_bytes();
+    fields.push(field(Tag::DateTime, Value::Ascii(vec![date_time])));
+  }
+  Ok(fields)
+}
+
+/// Formats Unix milliseconds as an EXIF "YYYY:MM:DD HH:MM:SS" date in UTC
+fn exif_date_time(timestamp_ms: f64) -> anyhow::Result<String> {
+  if !timestamp_ms.is_finite() || timestamp_ms < 0.0 {
+    return Err(anyhow!("Invalid EXIF timestamp: {}", timestamp_ms));
+  }
+  let secs = (timestamp_ms / 1000.0) as i64;
+  let (days, day_secs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+  // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  Ok(format!(
+    "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+    year,
+    month,
+    day,
+    day_secs / 3600,
+    day_secs % 3600 / 60,
+    day_secs % 60
+  ))
+}
@@ -0,0 +1,274 @@
+//! Error module for nokhwa-node
+//!
+//! This module defines the stable error codes surfaced to JavaScript as `err.code`.
+
+use std::fmt;
+
+use napi::bindgen_prelude::*;
+
+/// Stable error codes exposed to JavaScript as `err.code`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+  /// Camera access has not been initialized on a platform that requires it
+  NotInitialized,
+  /// The OS refused access to the camera device
+  PermissionDenied,
+  /// The frame data doesn't match the resolution reported by the camera
+  ResolutionMismatch,
+  /// The camera reported a zero width or height
+  InvalidResolution,
+  /// The device is held by another process, or another call still holds this camera after
+  /// two seconds; retrying later may succeed
+  DeviceBusy,
+  /// No camera exists at the given index or with the given name
+  DeviceNotFound,
+  /// The camera can't deliver the requested resolution, frame rate or pixel format
+  FormatUnsupported,
+  /// A frame couldn't be decoded or converted, e.g. a corrupt MJPEG frame
+  DecodeFailed,
+  /// The camera's stream isn't running, so no frame can be read
+  StreamClosed,
+  /// The camera was released with `close()`
+  CameraClosed,
+  /// The device stopped delivering frames, e.g. it was unplugged; see `Camera::reconnect`
+  Disconnected,
+  /// No frame arrived within the timeout given to a capture
+  Timeout,
+  /// Any other failure, carrying the plain napi status
+  Napi(Status),
+}
+
+impl AsRef<str> for ErrorCode {
+  fn as_ref(&self) -> &str {
+    match self {
+      ErrorCode::NotInitialized => "NotInitialized",
+      ErrorCode::PermissionDenied => "PermissionDenied",
+      ErrorCode::ResolutionMismatch => "ResolutionMismatch",
+      ErrorCode::InvalidResolution => "InvalidResolution",
+      ErrorCode::DeviceBusy => "DeviceBusy",
+      ErrorCode::DeviceNotFound => "DeviceNotFound",
+      ErrorCode::FormatUnsupported => "FormatUnsupported",
+      ErrorCode::DecodeFailed => "DecodeFailed",
+      ErrorCode::StreamClosed => "StreamClosed",
+      ErrorCode::CameraClosed => "CameraClosed",
+      ErrorCode::Disconnected => "Disconnected",
+      ErrorCode::Timeout => "Timeout",
+      ErrorCode::Napi(status) => status.as_ref(),
+    }
+  }
+}
+
+// Lets thread-safe callbacks carry coded errors
+impl From<Status> for ErrorCode {
+  fn from(status: Status) -> Self {
+    ErrorCode::Napi(status)
+  }
+}
+
+/// Create an error with a stable code
+pub fn coded_error(code: ErrorCode, reason: impl ToString) -> Error<ErrorCode> {
+  Error::new(code, reason.to_string())
+}
+
+/// Attach the original, platform-specific error text as `err.cause`
+/// The code is meant for handling errors in code, the cause for debugging them.
+pub fn with_cause(mut err: Error<ErrorCode>, detail: impl ToString) -> Error<ErrorCode> {
+  err.set_cause(Error::from_reason(detail.to_string()));
+  err
+}
+
+/// Carry a plain napi error through a coded result, keeping its status as the code
+pub fn into_coded(mut err: Error) -> Error<ErrorCode> {
+  let mut coded = Error::new(ErrorCode::Napi(err.status), err.reason.clone());
+  coded.cause = err.cause.take();
+  coded
+}
+
+/// Throw the coded error an async task's `compute` failed with, see `Task::reject`
+/// `Task` errors only carry a napi status, so the code and cause are put on the
+/// JS error here. Without a stored failure `err` is thrown as is.
+pub fn reject_coded<T>(env: Env, err: Error, failure: Option<Error<ErrorCode>>) -> Result<T> {
+  let Some(mut failure) = failure else {
+    return Err(err);
+  };
+  let mut error = env.create_error(err)?;
+  error.set_named_property("code", failure.status.as_ref())?;
+  if let Some(cause) = failure.cause.take() {
+    error.set_named_property("cause", env.create_error(*cause)?)?;
+  }
+  Err(Error::from(error.to_unknown()))
+}
+
+/// Failure that keeps its code while travelling through `anyhow`-based internals
+#[derive(Debug)]
+pub struct CodedFailure {
+  pub code: ErrorCode,
+  pub message: String,
+}
+
+impl fmt::Display for CodedFailure {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.message)
+  }
+}
+
+impl std::error::Error for CodedFailure {}
+
+/// Build an `anyhow` error that keeps `code` on its way to JavaScript
+pub fn coded_failure(code: ErrorCode, message: impl ToString) -> anyhow::Error {
+  CodedFailure {
+    code,
+    message: message.to_string(),
+  }
+  .into()
+}
+
+/// Convert an internal error, keeping the code of a `CodedFailure` if it carries one
+/// When the error wraps another one, the innermost error becomes `err.cause`.
+pub fn anyhow_to_coded(err: anyhow::Error) -> Error<ErrorCode> {
+  let coded = match err.downcast_ref::<CodedFailure>() {
+    Some(failure) => coded_error(failure.code, &failure.message),
+    None => coded_error(ErrorCode::Napi(Status::GenericFailure), &err),
+  };
+  match err.chain().nth(1) {
+    Some(_) => with_cause(coded, err.root_cause()),
+    None => coded,
+  }
+}
+
+/// Fail with `NotInitialized` when nokhwa still needs `nokhwa_initialize`
+/// Only macOS requires initialization; elsewhere the check always passes.
+pub fn ensure_initialized() -> Result<(), ErrorCode> {
+  if nokhwa::nokhwa_check() {
+    return Ok(());
+  }
+  Err(coded_error(
+    ErrorCode::NotInitialized,
+    "Camera access is not initialized: await nokhwaInitializeAsync() before opening a camera",
+  ))
+}
+
+/// Check whether a nokhwa error is the OS refusing access to the device
+/// nokhwa only reports backend errors as text, so this looks for the errno
+/// messages of EACCES/EPERM and the equivalent wording of other backends.
+pub fn is_permission_error(err: &nokhwa::NokhwaError) -> bool {
+  let message = err.to_string().to_lowercase();
+  [
+    "permission denied",
+    "operation not permitted",
+    "os error 13",
+    "access is denied",
+    "not authorized",
+  ]
+  .iter()
+  .any(|needle| message.contains(needle))
+}
+
+/// Check whether a nokhwa error means the device is in use elsewhere
+/// Like permission errors these only come as text: EBUSY on Linux, and the
+/// "in use" wording of the Windows and macOS backends.
+pub fn is_busy_error(err: &nokhwa::NokhwaError) -> bool {
+  let message = err.to_string().to_lowercase();
+  [
+    "device or resource busy",
+    "os error 16",
+    "in use",
+    "being used by another",
+  ]
+  .iter()
+  .any(|needle| message.contains(needle))
+}
+
+/// Build the `PermissionDenied` error for a camera, with a platform-specific hint
+pub fn permission_denied_error(err: &nokhwa::NokhwaError) -> Error<ErrorCode> {
+  #[cfg(target_os = "linux")]
+  let hint = "add your user to the 'video' group (sudo usermod -aG video $USER) and log in again";
+  #[cfg(target_os = "macos")]
+  let hint = "grant camera access to this application in System Settings > Privacy & Security";
+  #[cfg(target_os = "windows")]
+  let hint = "allow camera access for desktop apps in Settings > Privacy & security > Camera";
+  #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+  let hint = "check that the current user may access the video device";
+
+  with_cause(
+    coded_error(
+      ErrorCode::PermissionDenied,
+      format!("Permission denied opening camera: {}. Hint: {}", err, hint),
+    ),
+    err,
+  )
+}
+
+/// Check whether a nokhwa error means there is no such device
+/// ENOENT/ENODEV on Linux when the /dev node is missing, "not found" elsewhere.
+fn is_not_found_error(err: &nokhwa::NokhwaError) -> bool {
+  let message = err.to_string().to_lowercase();
+  [
+    "no such file",
+    "no such device",
+    "os error 2)",
+    "os error 19",
+    "not found",
+  ]
+  .iter()
+  .any(|needle| message.contains(needle))
+}
+
+/// Classify a nokhwa error into one of the stable codes
+/// This is the one place nokhwa's variants (and, where a backend only reports
+/// text, its wording) are mapped; anything unrecognized stays a `GenericFailure`.
+pub fn nokhwa_error_code(err: &nokhwa::NokhwaError) -> ErrorCode {
+  use nokhwa::NokhwaError;
+
+  if is_permission_error(err) {
+    return ErrorCode::PermissionDenied;
+  }
+  if is_busy_error(err) {
+    return ErrorCode::DeviceBusy;
+  }
+  match err {
+    NokhwaError::UnitializedError => ErrorCode::NotInitialized,
+    NokhwaError::OpenDeviceError(..) if is_not_found_error(err) => ErrorCode::DeviceNotFound,
+    NokhwaError::ProcessFrameError { .. } => ErrorCode::DecodeFailed,
+    NokhwaError::GetPropertyError { property, error }
+    | NokhwaError::SetPropertyError {
+      property, error, ..
+    } => {
+      let property = property.to_lowercase();
+      let unsupported = ["format", "frame rate", "framerate", "resolution"]
+        .iter()
+        .any(|needle| property.contains(needle))
+        || error.to_lowercase().contains("fufill");
+      if unsupported {
+        ErrorCode::FormatUnsupported
+      } else {
+        ErrorCode::Napi(Status::GenericFailure)
+      }
+    }
+    NokhwaError::ReadFrameError(message) => {
+      let message = message.to_lowercase();
+      if message.contains("not started") || message.contains("not open") {
+        ErrorCode::StreamClosed
+      } else {
+        ErrorCode::Napi(Status::GenericFailure)
+      }
+    }
+    _ => ErrorCode::Napi(Status::GenericFailure),
+  }
+}
+
+/// Wrap a nokhwa error as `"{context}: {err}"` with its classified code
+/// The backend's own message is kept as `err.cause`.
+pub fn nokhwa_error(context: &str, err: &nokhwa::NokhwaError) -> Error<ErrorCode> {
+  match nokhwa_error_code(err) {
+    ErrorCode::PermissionDenied => permission_denied_error(err),
+    code => with_cause(coded_error(code, format!("{}: {}", context, err)), err),
+  }
+}
+
+/// Like `nokhwa_error`, for the `anyhow`-based internals
+pub fn nokhwa_failure(context: &str, err: nokhwa::NokhwaError) -> anyhow::Error {
+  let code = nokhwa_error_code(&err);
+  let message = format!("{}: {}", context, err);
+  anyhow::Error::new(err).context(CodedFailure { code, message })
+}
@@ -0,0 +1,143 @@
+//! Camera group module for nokhwa-node
+//!
+//! This module captures from several cameras at once for stereo and multi-view
+//! setups. Every camera is read on its own thread so the frames are grabbed
+//! with as little skew as the devices allow.
+
+use std::thread;
+
+use anyhow::anyhow;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::conversions::{
+  convert_to_napi_frame, create_camera_with_fallback, decode_to_rgba, next_buffer,
+  parse_camera_index, Frame, RgbaFrame,
+};
+use crate::error::{anyhow_to_coded, ensure_initialized, into_coded, ErrorCode};
+use crate::shared::SharedCamera;
+use crate::types::ColorSpace;
+
+/// Result of one camera in `CameraGroup::capture_all`
+/// Exactly one of `frame` and `error` is set.
+#[napi(object)]
+pub struct GroupCapture {
+  /// Index the camera was opened with
+  pub index: String,
+  pub frame: Option<Frame>,
+  /// Stable error code, same as `err.code` on thrown errors
+  pub code: Option<String>,
+  pub error: Option<String>,
+}
+
+/// Several cameras captured together, see `CameraGroup::capture_all`
+#[napi]
+pub struct CameraGroup {
+  indices: Vec<String>,
+  cameras: Vec<SharedCamera>,
+}
+
+impl Drop for CameraGroup {
+  fn drop(&mut self) {
+    for camera in &self.cameras {
+      camera.release();
+    }
+  }
+}
+
+#[napi]
+impl CameraGroup {
+  /// Open every camera in `indices` with automatic format detection
+  /// Throws if any camera fails to open; the ones already opened are released.
+  #[napi(constructor)]
+  pub fn new(indices: Vec<String>) -> Result<Self, ErrorCode> {
+    ensure_initialized()?;
+    let mut group = Self {
+      indices: Vec::with_capacity(indices.len()),
+      cameras: Vec::with_capacity(indices.len()),
+    };
+    for index in indices {
+      let nokhwa_index = parse_camera_index(index.clone()).map_err(into_coded)?;
+      let camera = create_camera_with_fallback(nokhwa_index, nokhwa::utils::ApiBackend::Auto)?;
+      group.indices.push(index);
+      group.cameras.push(SharedCamera::new(camera));
+    }
+    Ok(group)
+  }
+
+  /// Get the indices of the cameras, in the order `capture_all` reports them
+  #[napi]
+  pub fn indices(&self) -> Vec<String> {
+    self.indices.clone()
+  }
+
+  /// Capture one RGBA frame from every camera at once
+  /// Each camera is read on its own thread and the call returns when all are
+  /// done. A camera that fails is reported in its entry instead of failing the
+  /// batch. Compare the frames' `timestampMs` to measure the skew between them.
+  #[napi]
+  pub fn capture_all(&self) -> Vec<GroupCapture> {
+    let results: Vec<anyhow::Result<RgbaFrame>> = thread::scope(|scope| {
+      let workers: Vec<_> = self
+        .cameras
+        .iter()
+        .map(|camera| scope.spawn(move || capture_one(camera)))
+        .collect();
+      workers
+        .into_iter()
+        .map(|worker| {
+          worker
+            .join()
+            .unwrap_or_else(|_| Err(anyhow!("Capture thread panicked")))
+        })
+        .collect()
+    });
+
+    self
+      .indices
+      .iter()
+      .zip(results)
+      .map(|(index, result)| {
+        let frame = result
+          .map_err(anyhow_to_coded)
+          .and_then(|frame| convert_to_napi_frame(frame).map_err(into_coded));
+        match frame {
+          Ok(frame) => GroupCapture {
+            index: index.clone(),
+            frame: Some(frame),
+            code: None,
+            error: None,
+          },
+          Err(e) => GroupCapture {
+            index: index.clone(),
+            frame: None,
+            code: Some(e.status.as_ref().to_string()),
+            error: Some(e.reason.clone()),
+          },
+        }
+      })
+      .collect()
+  }
+
+  /// Stop every camera's stream and release the devices
+  /// Later captures report code `CameraClosed` for each camera.
+  #[napi]
+  pub fn close(&mut self) {
+    for camera in &self.cameras {
+      camera.close();
+    }
+  }
+}
+
+/// Grab and decode one frame; runs on the camera's capture thread
+fn capture_one(camera: &SharedCamera) -> anyhow::Result<RgbaFrame> {
+  let mut cam = camera.lock()?;
+  let buffer = next_buffer(&mut cam)?;
+  let resolution = cam.resolution();
+  decode_to_rgba(
+    &buffer,
+    resolution.width(),
+    resolution.height(),
+    ColorSpace::Bt601,
+  )
+}
@@ -7,162 +7,827 @@
 //! This library provides access to webcams and other video capture devices
 //! with support for multiple backends and frame formats.
 
+mod analysis;
 mod buffer;
 mod camera;
+mod config;
+mod controls;
 mod conversions;
+mod encode;
+mod error;
+mod group;
+mod pipeline;
+mod pool;
+mod processing;
+mod record;
+mod shared;
+mod stream;
+mod timing;
 mod types;
+mod watch;
+mod yuv;
 
-use std::mem::ManuallyDrop;
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::ThreadsafeFunction;
 use napi_derive::napi;
 
 // Re-export public types from modules
 pub use buffer::CameraBuffer;
-pub use conversions::Frame;
+pub use conversions::{Frame, I420Frame, ImageDataFrame, StreamFrame};
+pub use group::{CameraGroup, GroupCapture};
+pub use pool::{FramePool, PooledFrame};
+pub use record::RecordingHandle;
+pub use stream::{FrameIterator, StreamBuilder, StreamHandle};
 pub use types::*;
+pub use watch::DeviceWatcher;
 
-use camera::list_cameras as list_cameras_internal;
+use camera::{
+  find_camera_by_name, list_cameras as list_cameras_internal,
+  list_cameras_with_status as list_cameras_with_status_internal, self_test,
+};
 use conversions::{
-  capture_frame, convert_backend, convert_backend_to_napi, convert_camera_control,
-  convert_control_value, convert_frame_format, convert_known_control,
-  convert_known_control_to_nokhwa, convert_requested_format, convert_to_napi_frame,
-  create_camera_with_fallback, parse_camera_index,
+  apply_format_request, capture_frame_into, convert_backend, convert_backend_to_napi,
+  convert_camera_control, convert_control_value, convert_control_value_to_napi,
+  convert_frame_format, convert_known_control, convert_known_control_to_nokhwa,
+  convert_requested_format, convert_to_i420, convert_to_image_data, convert_to_napi_frame,
+  create_camera_with_fallback, create_camera_with_format, decode_as, decode_to_rgba,
+  decode_to_rgba_cached, has_format_target, next_buffer_for, other_control_id, parse_camera_index,
+  retry_when_busy, DecodeCache, RgbaFrame,
+};
+use error::{
+  anyhow_to_coded, coded_error, ensure_initialized, into_coded, nokhwa_error, reject_coded,
+  CodedFailure, ErrorCode,
 };
+use shared::SharedCamera;
+use timing::FpsMeter;
 
 // ============================================================================
 // Camera Class
 // ============================================================================
 
 /// Camera instance for capturing frames with full nokhwa functionality
+///
+/// All methods are serialized through an internal lock on the device, so
+/// overlapping calls (e.g. from async tasks) run one after another. A call that
+/// cannot get the device within two seconds fails with a "busy" error.
 #[napi]
 pub struct Camera {
-  // Use ManuallyDrop to control when the camera is actually dropped
-  // This prevents double-free and ensures proper cleanup during GC
-  camera: Option<ManuallyDrop<nokhwa::Camera>>,
+  camera: SharedCamera,
+  origin: ImageOrigin,
+  color_space: ColorSpace,
+  decode_cache: Option<DecodeCache>,
+  fps_meter: FpsMeter,
+  pipeline: Vec<FrameOp>,
+  deinterlace: Option<DeinterlaceMode>,
+  latency_mode: LatencyMode,
+  adjust_lut: Option<[u8; 256]>,
+  overlay: Option<Arc<processing::Overlay>>,
+  transform: FrameTransform,
+  /// Region (x, y, width, height) the capture methods crop frames to
+  region_of_interest: Option<(u32, u32, u32, u32)>,
+  /// RGBA frame reused by `capture_frame_into` between captures
+  capture_scratch: Vec<u8>,
 }
 
 impl Drop for Camera {
   fn drop(&mut self) {
-    // First try to stop the stream if it exists
-    // This is critical on Windows with MediaFoundation backend
-    if let Some(cam) = self.camera.as_mut() {
-      let _ = catch_unwind(AssertUnwindSafe(|| cam.stop_stream()));
-    }
-    // Then drop the camera
-    let _ = self.camera.take();
+    self.camera.release();
   }
 }
 
 #[napi]
 impl Camera {
   /// Create a new camera instance with the given index
-  /// The camera stream is opened immediately with automatic format detection
+  /// The camera stream is opened immediately with automatic format detection.
+  /// Pass `frame_format` to skip detection and open the device with exactly that
+  /// source format, failing fast if it isn't supported.
+  /// Throws with code `NotInitialized` if camera access still has to be
+  /// initialized via nokhwa_initialize_async (macOS), and `PermissionDenied` if the OS
+  /// refuses access to the device (e.g. user not in the `video` group on Linux).
+  /// With `retry`, opening is retried while the device is busy (code
+  /// `DeviceBusy`); permanent failures are thrown right away.
   #[napi(constructor)]
-  pub fn new(camera_index: String) -> Result<Self> {
-    let nokhwa_index = parse_camera_index(camera_index)?;
-    let camera = create_camera_with_fallback(nokhwa_index)?;
-    Ok(Self {
-      camera: Some(ManuallyDrop::new(camera)),
-    })
+  pub fn new(
+    camera_index: String,
+    frame_format: Option<FrameFormat>,
+    retry: Option<RetryConfig>,
+  ) -> Result<Self, ErrorCode> {
+    ensure_initialized()?;
+    let nokhwa_index = parse_camera_index(camera_index).map_err(into_coded)?;
+    let camera = retry_when_busy(retry.as_ref(), || match frame_format {
+      Some(format) => create_camera_with_format(nokhwa_index.clone(), format),
+      None => create_camera_with_fallback(nokhwa_index.clone(), nokhwa::utils::ApiBackend::Auto),
+    })?;
+    Ok(Self::from_nokhwa(camera))
   }
 
   /// Create a new camera instance with the given index and format configuration
   /// The camera stream is opened immediately with the specified format
+  /// Request type `None` opens whatever format the device offers first, the
+  /// quickest way to open a camera; check camera_format() for the result.
   #[napi]
   pub fn new_with_format(
     camera_index: String,
     format_config: RequestedFormatConfig,
-  ) -> Result<Self> {
-    let nokhwa_index = parse_camera_index(camera_index)?;
-    let nokhwa_format = convert_requested_format(format_config)?;
-    let camera = nokhwa::Camera::new(nokhwa_index, nokhwa_format)
-      .map_err(|e| Error::from_reason(format!("Failed to create camera: {}", e)))?;
-    Ok(Self {
-      camera: Some(ManuallyDrop::new(camera)),
-    })
+  ) -> Result<Self, ErrorCode> {
+    ensure_initialized()?;
+    let nokhwa_index = parse_camera_index(camera_index).map_err(into_coded)?;
+    // A target is matched against the device's formats, which needs the device open first
+    let (nokhwa_format, target) = if has_format_target(&format_config) {
+      let any = nokhwa::utils::RequestedFormat::new::<nokhwa::pixel_format::RgbAFormat>(
+        nokhwa::utils::RequestedFormatType::None,
+      );
+      (any, Some(format_config))
+    } else {
+      let request = convert_requested_format(format_config).map_err(into_coded)?;
+      (request, None)
+    };
+    let mut camera = nokhwa::Camera::new(nokhwa_index, nokhwa_format)
+      .map_err(|e| nokhwa_error("Failed to create camera", &e))?;
+    if let Some(config) = target {
+      apply_format_request(&mut camera, config).map_err(anyhow_to_coded)?;
+    }
+    Ok(Self::from_nokhwa(camera))
+  }
+
+  /// Create a new camera instance pinned to a specific backend
+  /// Uses the same automatic format detection as the constructor, but never lets
+  /// nokhwa pick another backend (e.g. to force Video4Linux on Linux).
+  #[napi]
+  pub fn new_with_backend(camera_index: String, backend: ApiBackend) -> Result<Self, ErrorCode> {
+    ensure_initialized()?;
+    let nokhwa_index = parse_camera_index(camera_index).map_err(into_coded)?;
+    let camera = create_camera_with_fallback(nokhwa_index, convert_backend(backend))?;
+    Ok(Self::from_nokhwa(camera))
+  }
+
+  /// Open the first camera whose human-readable name matches `name`
+  /// With `exact`, the name must match exactly and be unique among connected devices.
+  /// Otherwise a case-insensitive substring match is used and the first match wins.
+  #[napi]
+  pub fn from_name(name: String, exact: bool) -> Result<Self, ErrorCode> {
+    ensure_initialized()?;
+    let index = find_camera_by_name(&name, exact).map_err(anyhow_to_coded)?;
+    let camera = create_camera_with_fallback(index, nokhwa::utils::ApiBackend::Auto)?;
+    Ok(Self::from_nokhwa(camera))
   }
 
   /// Capture a single frame from the camera
   /// Returns the frame as RGBA buffer with width and height
+  /// Frames whose size doesn't match the reported resolution are corrected when
+  /// the real size can be inferred, otherwise fail with code `ResolutionMismatch`.
+  /// With `timeout_ms`, waiting for the device gives up after that long with code
+  /// `Timeout`. The device stays locked until the late frame arrives, which is
+  /// then discarded, so other calls wait meanwhile (and fail as busy after two seconds).
+  #[napi]
+  pub fn capture_frame(&mut self, timeout_ms: Option<u32>) -> Result<Frame, ErrorCode> {
+    let (buffer, width, height) = self.grab(self.latency_mode, timeout_ms)?;
+    let rgba_frame = self.finish_rgba(&buffer, width, height)?;
+    convert_to_napi_frame(rgba_frame).map_err(into_coded)
+  }
+
+  /// Capture a frame and scale it to `width`x`height` with a triangle (bilinear) filter
+  /// With `keep_aspect_ratio` the frame is instead scaled to the largest size
+  /// that fits in `width`x`height` without distorting it, so one side may come
+  /// out smaller; check the returned frame's dimensions. A zero width or height
+  /// throws with code `InvalidArg`.
+  #[napi]
+  pub fn capture_frame_resized(
+    &mut self,
+    width: u32,
+    height: u32,
+    keep_aspect_ratio: Option<bool>,
+  ) -> Result<Frame, ErrorCode> {
+    if width == 0 || height == 0 {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!(
+          "Invalid target size {}x{}: both dimensions must be non-zero",
+          width, height
+        ),
+      ));
+    }
+    let mut frame = self.capture_rgba()?;
+    let (width, height) = if keep_aspect_ratio.unwrap_or(false) {
+      processing::fit_within(frame.width, frame.height, width, height)
+    } else {
+      (width, height)
+    };
+    if (width, height) != (frame.width, frame.height) {
+      frame.data = processing::resize(&frame.data, frame.width, frame.height, 4, width, height)
+        .map_err(anyhow_to_coded)?;
+      frame.width = width;
+      frame.height = height;
+    }
+    convert_to_napi_frame(frame).map_err(into_coded)
+  }
+
+  /// Capture a frame decoded to `format`: RGB (3 bytes per pixel), RGBA (4) or GRAY (1)
+  /// RGB and GRAY are decoded directly, skipping the RGBA conversion and its
+  /// bandwidth; GRAY takes YUYV/NV12 luma as is and Rec.601 luma otherwise.
+  /// Deinterlacing, the transform, the region of interest and the output origin
+  /// apply as for `capture_frame`. With a software adjustment or overlay set,
+  /// the frame goes through RGBA for those and is converted afterwards. Other
+  /// formats throw with code `InvalidArg`.
+  #[napi]
+  pub fn capture_frame_as(&mut self, format: FrameFormat) -> Result<Frame, ErrorCode> {
+    if format == FrameFormat::RGBA || self.adjust_lut.is_some() || self.overlay.is_some() {
+      let frame = self.capture_rgba()?;
+      let data = match format {
+        FrameFormat::RGBA => frame.data,
+        FrameFormat::RGB => processing::rgba_to_rgb(&frame.data),
+        FrameFormat::GRAY => processing::rgba_to_gray(&frame.data),
+        other => {
+          return Err(coded_error(
+            ErrorCode::Napi(Status::InvalidArg),
+            format!(
+              "{:?} is not a decoded output format; use RGB, RGBA or GRAY",
+              other
+            ),
+          ))
+        }
+      };
+      return Ok(Frame {
+        data: data.into(),
+        width: frame.width,
+        height: frame.height,
+        timestamp_ms: frame.timestamp_ms,
+      });
+    }
+
+    let (mut data, width, height, timestamp_ms) = {
+      let mut cam = self.camera.lock_coded()?;
+      let buffer = next_buffer_for(&mut cam, self.latency_mode).map_err(anyhow_to_coded)?;
+      let timestamp_ms = timing::unix_time_ms();
+      let resolution = cam.resolution();
+      decode_as(
+        &buffer,
+        resolution.width(),
+        resolution.height(),
+        self.color_space,
+        format,
+      )
+      .map(|(data, width, height)| (data, width, height, timestamp_ms))
+      .map_err(anyhow_to_coded)?
+    };
+    self.fps_meter.tick();
+    let channels = if format == FrameFormat::GRAY { 1 } else { 3 };
+    let (width, height) = self
+      .frame_finish()
+      .apply_channels(&mut data, width, height, channels);
+    Ok(Frame {
+      data: data.into(),
+      width,
+      height,
+      timestamp_ms,
+    })
+  }
+
+  /// Capture a single frame without blocking the event loop
+  /// Waiting for the device and decoding run on the libuv thread pool; the
+  /// promise resolves to the same RGBA `Frame` as `captureFrame()`, with the same
+  /// settings except the decode cache. Overlapping calls are serialized on the
+  /// device lock: each waits for the previous capture, and a call that can't
+  /// get the device within two seconds rejects with code `DeviceBusy`. Errors
+  /// carry the same codes as the synchronous capture.
+  #[napi(ts_return_type = "Promise<Frame>")]
+  pub fn capture_frame_async(&self) -> AsyncTask<CaptureFrameTask> {
+    AsyncTask::new(CaptureFrameTask {
+      camera: self.camera.clone(),
+      color_space: self.color_space,
+      latency_mode: self.latency_mode,
+      finish: self.frame_finish(),
+      failure: None,
+    })
+  }
+
+  /// Capture a frame into a buffer taken from `pool`
+  /// The frame's buffer is reused across captures once it is released back to
+  /// the pool via `release()` (or garbage collection).
+  #[napi]
+  pub fn capture_into_pooled(&mut self, pool: &FramePool) -> Result<PooledFrame, ErrorCode> {
+    let mut frame = pool.acquire();
+    // A freshly acquired frame is the only owner of its buffer, so this doesn't copy
+    let data = Arc::make_mut(&mut frame.data);
+    let (width, height) = {
+      let mut cam = self.camera.lock_coded()?;
+      capture_frame_into(&mut cam, self.color_space, self.latency_mode, data)
+        .map_err(anyhow_to_coded)?
+    };
+    self.fps_meter.tick();
+    let (width, height) = self.frame_finish().apply(data, width, height);
+    frame.width = width;
+    frame.height = height;
+    Ok(frame)
+  }
+
+  /// Capture an RGBA frame into `buffer`, e.g. one allocation reused by a render loop
+  /// Returns the frame's resolution; the frame fills the first `width * height * 4`
+  /// bytes and the rest of `buffer` is left untouched. A buffer that is too small
+  /// throws with code `InvalidArg` before anything is written. The same settings
+  /// as `capture_frame` apply, except that each frame is decoded afresh.
+  #[napi]
+  pub fn capture_frame_into(&mut self, mut buffer: Uint8Array) -> Result<Resolution, ErrorCode> {
+    let (width, height) = {
+      let mut cam = self.camera.lock_coded()?;
+      capture_frame_into(
+        &mut cam,
+        self.color_space,
+        self.latency_mode,
+        &mut self.capture_scratch,
+      )
+      .map_err(anyhow_to_coded)?
+    };
+    self.fps_meter.tick();
+    let (width, height) = self
+      .frame_finish()
+      .apply(&mut self.capture_scratch, width, height);
+
+    let needed = width as usize * height as usize * 4;
+    if buffer.len() < needed {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!(
+          "Buffer too small: a {}x{} RGBA frame needs {} bytes, got {}",
+          width,
+          height,
+          needed,
+          buffer.len()
+        ),
+      ));
+    }
+    // SAFETY: JS code can't run while this synchronous call writes to the buffer
+    let dest = unsafe { buffer.as_mut() };
+    dest[..needed].copy_from_slice(&self.capture_scratch[..needed]);
+    Ok(Resolution { width, height })
+  }
+
+  /// Capture a frame shaped for the browser `ImageData` constructor
+  /// The data is guaranteed to be `width * height * 4` bytes of tightly packed
+  /// RGBA with no padding, in the sRGB color space.
+  #[napi]
+  pub fn capture_image_data(&mut self) -> Result<ImageDataFrame, ErrorCode> {
+    let rgba_frame = self.capture_rgba()?;
+    convert_to_image_data(rgba_frame).map_err(into_coded)
+  }
+
+  /// Capture only the `width`x`height` region at (`x`, `y`), e.g. a QR code zone
+  /// For YUYV, NV12, RGB and GRAY sources only the region's pixels are decoded,
+  /// which is much cheaper than decoding the full frame on large sensors. MJPEG
+  /// frames are always decoded in full and then cropped. Coordinates are in
+  /// the camera image (top-left origin); the output origin setting still applies.
+  #[napi]
+  pub fn capture_region(
+    &mut self,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+  ) -> Result<Frame, ErrorCode> {
+    let mut frame = {
+      let mut cam = self.camera.lock_coded()?;
+      conversions::capture_region(
+        &mut cam,
+        self.color_space,
+        self.latency_mode,
+        (x, y, width, height),
+      )
+      .map_err(anyhow_to_coded)?
+    };
+    self.fps_meter.tick();
+    if self.origin == ImageOrigin::BottomLeft {
+      processing::flip_vertical(&mut frame.data, frame.width, frame.height, 4);
+    }
+    convert_to_napi_frame(frame).map_err(into_coded)
+  }
+
+  /// Configure the steps `capture_processed()` applies to every frame, in order
+  /// Steps are `Resize`, `Crop`, `Flip`, `Rotate`, `Grayscale`, and at the end
+  /// optionally `EncodeJpeg` or `EncodePng`. The pipeline is validated here, so
+  /// misplaced encoders or bad parameters throw with code `InvalidArg` before
+  /// any frame is captured.
+  #[napi]
+  pub fn set_output_pipeline(&mut self, ops: Vec<FrameOp>) -> Result<(), ErrorCode> {
+    pipeline::validate_pipeline(&ops)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+    self.pipeline = ops;
+    Ok(())
+  }
+
+  /// Capture a frame and run it through the output pipeline
+  /// Returns the encoded image when the pipeline ends with an encode step,
+  /// otherwise the raw pixels (RGBA, or one byte per pixel after `Grayscale`).
+  #[napi]
+  pub fn capture_processed(&mut self) -> Result<Buffer, ErrorCode> {
+    let frame = self.capture_rgba()?;
+    let data = pipeline::run_pipeline(frame, &self.pipeline).map_err(anyhow_to_coded)?;
+    Ok(data.into())
+  }
+
+  /// Capture a frame as planar I420 (YUV 4:2:0), the usual input of video encoders
+  /// Uses the camera's color space; `u` and `v` are `width / 2 * height / 2`
+  /// bytes each, so the frame dimensions must be even.
+  #[napi(js_name = "captureI420")]
+  pub fn capture_i420(&mut self) -> Result<I420Frame, ErrorCode> {
+    let rgba_frame = self.capture_rgba()?;
+    convert_to_i420(rgba_frame, self.color_space).map_err(anyhow_to_coded)
+  }
+
+  /// Capture a frame encoded as JPEG at `quality` (1-100)
+  /// Pass `metadata` to embed a timestamp, camera name and orientation as EXIF
+  /// tags; without it no EXIF segment is written, keeping the output smallest.
+  #[napi]
+  pub fn capture_jpeg(
+    &mut self,
+    quality: u32,
+    metadata: Option<JpegMetadata>,
+  ) -> Result<Buffer, ErrorCode> {
+    let frame = self.capture_rgba()?;
+    let rgb = processing::rgba_to_rgb(&frame.data);
+    let mut jpeg =
+      encode::encode_jpeg(&rgb, frame.width, frame.height, quality).map_err(anyhow_to_coded)?;
+    if let Some(metadata) = metadata {
+      jpeg = encode::embed_exif(jpeg, &metadata).map_err(anyhow_to_coded)?;
+    }
+    Ok(jpeg.into())
+  }
+
+  /// Capture a frame and write it to `path` as an image file
+  /// The format follows the extension: `.png`, `.jpg`/`.jpeg`
+  /// (quality 90) or `.bmp`; any other extension fails before capturing.
+  /// `metadata` is embedded as EXIF tags like in `capture_jpeg`; only JPEG files
+  /// carry it, so passing it for another format throws with code `InvalidArg`.
+  /// Missing parent directories are created.
+  #[napi]
+  pub fn save_frame(
+    &mut self,
+    path: String,
+    metadata: Option<JpegMetadata>,
+  ) -> Result<(), ErrorCode> {
+    let path = std::path::PathBuf::from(path);
+    let format = encode::save_format(&path)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+    if metadata.is_some() && format != image::ImageFormat::Jpeg {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!(
+          "Cannot save {}: metadata can only be embedded in .jpg files",
+          path.display()
+        ),
+      ));
+    }
+    let frame = self.capture_rgba()?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+      std::fs::create_dir_all(parent).map_err(|e| {
+        coded_error(
+          ErrorCode::Napi(Status::GenericFailure),
+          format!("Creating {}: {}", parent.display(), e),
+        )
+      })?;
+    }
+    encode::save_image(
+      &path,
+      format,
+      &frame.data,
+      frame.width,
+      frame.height,
+      metadata.as_ref(),
+    )
+    .map_err(anyhow_to_coded)
+  }
+
+  /// Capture a frame at a wall-clock time given as Unix milliseconds
+  /// Blocks until the target time using a precise sleep, then captures. Fails if
+  /// the target is more than 50ms in the past. With NTP-synced clocks this gives
+  /// rough synchronization of captures across machines.
+  #[napi]
+  pub fn capture_at(&mut self, unix_ms: f64) -> Result<Frame, ErrorCode> {
+    let deadline = timing::deadline_from_unix_ms(unix_ms).map_err(anyhow_to_coded)?;
+    timing::sleep_until(deadline);
+    let rgba_frame = self.capture_rgba()?;
+    convert_to_napi_frame(rgba_frame).map_err(into_coded)
+  }
+
+  /// Capture a frame and compute its average color
+  #[napi]
+  pub fn capture_average_color(&mut self) -> Result<RgbColor, ErrorCode> {
+    let frame = self.capture_rgba()?;
+    Ok(analysis::average_color(&frame.data))
+  }
+
+  /// Capture a frame and compute its sharpness (variance of the Laplacian)
+  /// Higher is sharper; compare values while adjusting the `Focus` control.
+  #[napi]
+  pub fn capture_sharpness(&mut self) -> Result<f64, ErrorCode> {
+    let frame = self.capture_rgba()?;
+    Ok(analysis::sharpness(&frame.data, frame.width, frame.height))
+  }
+
+  /// Capture a frame and compute the mean luma of each cell in a `cols`x`rows` grid
+  #[napi]
+  pub fn capture_zone_brightness(&mut self, cols: u32, rows: u32) -> Result<Vec<u8>, ErrorCode> {
+    let frame = self.capture_rgba()?;
+    analysis::zone_brightness(&frame.data, frame.width, frame.height, cols, rows)
+      .map_err(anyhow_to_coded)
+  }
+
+  /// Capture a frame and render it as ASCII art `cols` characters wide
+  /// Handy to check that a camera works on a headless machine
+  #[napi]
+  pub fn capture_ascii(&mut self, cols: u32) -> Result<String, ErrorCode> {
+    let frame = self.capture_rgba()?;
+    analysis::rgba_to_ascii(&frame.data, frame.width, frame.height, cols).map_err(anyhow_to_coded)
+  }
+
+  /// Set where row 0 of captured frames is placed
+  /// `BottomLeft` flips frames vertically, matching WebGL/canvas texture
+  /// orientation. Applies to the capture methods returning decoded frames.
+  #[napi]
+  pub fn set_output_origin(&mut self, origin: ImageOrigin) {
+    self.origin = origin;
+  }
+
+  /// Get the current output origin
+  #[napi]
+  pub fn output_origin(&self) -> ImageOrigin {
+    self.origin
+  }
+
+  /// Mirror and rotate captured frames, e.g. for a mirrored webcam or one mounted sideways
+  /// The flips are applied first, then the clockwise rotation, which must be
+  /// 0, 90, 180 or 270 degrees (other values throw with code `InvalidArg`); 90
+  /// and 270 swap the frame's width and height. Applies to the capture methods
+  /// returning full decoded frames, before any software adjustment or overlay.
+  #[napi]
+  pub fn set_transform(
+    &mut self,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    rotate: u32,
+  ) -> Result<(), ErrorCode> {
+    if !matches!(rotate, 0 | 90 | 180 | 270) {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!("Invalid rotation {}: must be 0, 90, 180 or 270", rotate),
+      ));
+    }
+    self.transform = FrameTransform {
+      flip_horizontal,
+      flip_vertical,
+      rotate,
+    };
+    Ok(())
+  }
+
+  /// Get the current mirroring and rotation
+  #[napi]
+  pub fn transform(&self) -> FrameTransform {
+    self.transform
+  }
+
+  /// Crop captured frames to the `width`x`height` region at (`x`, `y`)
+  /// Coordinates are in the frame after `set_transform`, and the region must fit
+  /// in the current resolution (throws with code `InvalidArg` otherwise). Unlike
+  /// `capture_region` the full frame is still decoded; only the cropped pixels
+  /// are copied to JavaScript. The adjust and overlay settings apply to the crop.
+  #[napi]
+  pub fn set_region_of_interest(
+    &mut self,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+  ) -> Result<(), ErrorCode> {
+    let resolution = self.camera.lock_coded()?.resolution();
+    let (frame_width, frame_height) = match self.transform.rotate {
+      90 | 270 => (resolution.height(), resolution.width()),
+      _ => (resolution.width(), resolution.height()),
+    };
+    let fits = width > 0
+      && height > 0
+      && x as u64 + width as u64 <= frame_width as u64
+      && y as u64 + height as u64 <= frame_height as u64;
+    if !fits {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!(
+          "Region {}x{} at ({}, {}) doesn't fit in the {}x{} frame",
+          width, height, x, y, frame_width, frame_height
+        ),
+      ));
+    }
+    self.region_of_interest = Some((x, y, width, height));
+    Ok(())
+  }
+
+  /// Stop cropping captured frames, see `set_region_of_interest`
+  #[napi]
+  pub fn clear_region_of_interest(&mut self) {
+    self.region_of_interest = None;
+  }
+
+  /// Deinterlace captured frames, e.g. from analog capture cards, or pass null to stop
+  /// Applies to the capture methods returning full decoded frames (not regions).
+  /// With `DiscardField` frames come back half as tall as the camera resolution.
+  #[napi]
+  pub fn set_deinterlace(&mut self, mode: Option<DeinterlaceMode>) {
+    self.deinterlace = mode;
+  }
+
+  /// Get the current deinterlace mode, null when frames are passed through
+  #[napi]
+  pub fn deinterlace(&self) -> Option<DeinterlaceMode> {
+    self.deinterlace
+  }
+
+  /// Adjust brightness (-1 to 1), contrast (0 and up) and gamma (above 0) in software
+  /// A fallback for cameras without those hardware controls, applied through a
+  /// 256-entry lookup table to every frame the capture methods decode. The
+  /// neutral values 0, 1, 1 turn the adjustment off; values out of range throw
+  /// with code `InvalidArg`.
+  #[napi]
+  pub fn set_software_adjust(
+    &mut self,
+    brightness: f64,
+    contrast: f64,
+    gamma: f64,
+  ) -> Result<(), ErrorCode> {
+    let lut = processing::adjust_lut(brightness, contrast, gamma)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+    let neutral = (brightness, contrast, gamma) == (0.0, 1.0, 1.0);
+    self.adjust_lut = if neutral { None } else { Some(lut) };
+    Ok(())
+  }
+
+  /// Composite an RGBA image (e.g. a logo) onto every frame the capture methods decode
+  /// The overlay's top-left corner goes to (`x`, `y`) in the frame, measured
+  /// from the top left before any `BottomLeft` flip; parts outside the frame are
+  /// clipped. Its alpha channel is respected and scaled by `opacity` (0 to 1).
+  /// A buffer that doesn't match `width`x`height` or an opacity out of range
+  /// throws with code `InvalidArg`.
+  #[napi]
+  pub fn set_overlay(
+    &mut self,
+    data: Buffer,
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    opacity: f64,
+  ) -> Result<(), ErrorCode> {
+    let overlay = processing::Overlay::new(data.to_vec(), width, height, x, y, opacity)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+    self.overlay = Some(Arc::new(overlay));
+    Ok(())
+  }
+
+  /// Stop compositing the overlay set with `set_overlay`
+  #[napi]
+  pub fn clear_overlay(&mut self) {
+    self.overlay = None;
+  }
+
+  /// Choose between in-order frames (`Balanced`, default) and always the newest one
+  /// `LowestLatency` drops frames that were already buffered by the driver
+  /// before each capture, for interactive previews where lag matters more than
+  /// throughput. Applies to the capture methods returning decoded frames.
+  #[napi]
+  pub fn set_latency_mode(&mut self, mode: LatencyMode) {
+    self.latency_mode = mode;
+  }
+
+  /// Get the current latency mode
+  #[napi]
+  pub fn latency_mode(&self) -> LatencyMode {
+    self.latency_mode
+  }
+
+  /// Set the YUV color matrix used when decoding YUYV and NV12 frames
+  /// Defaults to BT.601; HD cameras usually need BT.709 for accurate colors
+  #[napi]
+  pub fn set_color_space(&mut self, color_space: ColorSpace) {
+    self.color_space = color_space;
+    if let Some(cache) = self.decode_cache.as_mut() {
+      cache.clear();
+    }
+  }
+
+  /// Get the YUV color matrix used for decoding
   #[napi]
-  pub fn capture_frame(&mut self) -> Result<Frame> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
-    let rgba_frame = capture_frame(cam).map_err(|e| Error::from_reason(e.to_string()))?;
-    convert_to_napi_frame(rgba_frame)
+  pub fn color_space(&self) -> ColorSpace {
+    self.color_space
+  }
+
+  /// Enable or disable reuse of the last decoded frame when the camera repeats it
+  /// When enabled, identical source frames (static scene, paused device) skip
+  /// decoding. This keeps one extra decoded frame in memory; disabling drops it.
+  #[napi]
+  pub fn enable_decode_cache(&mut self, enabled: bool) {
+    self.decode_cache = if enabled {
+      Some(self.decode_cache.take().unwrap_or_default())
+    } else {
+      None
+    };
   }
 
   /// Get the camera index
   #[napi]
-  pub fn index(&self) -> String {
-    self
-      .camera
-      .as_ref()
-      .expect("Camera is closed or has been stopped")
-      .index()
-      .as_string()
+  pub fn index(&self) -> Result<String, ErrorCode> {
+    Ok(self.camera.lock_coded()?.index().as_string())
   }
 
   /// Get the backend being used
   #[napi]
-  pub fn backend(&self) -> ApiBackend {
-    convert_backend_to_napi(
-      self
-        .camera
-        .as_ref()
-        .expect("Camera is closed or has been stopped")
-        .backend(),
-    )
+  pub fn backend(&self) -> Result<ApiBackend, ErrorCode> {
+    Ok(convert_backend_to_napi(self.camera.lock_coded()?.backend()))
   }
 
   /// Get camera information
   #[napi]
-  pub fn info(&self) -> CameraDevice {
-    let cam = self
-      .camera
-      .as_ref()
-      .expect("Camera is closed or has been stopped");
+  pub fn info(&self) -> Result<CameraDevice, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
     let info = cam.info();
-    CameraDevice {
+    Ok(CameraDevice {
       index: info.index().as_string(),
       name: info.human_name(),
-    }
+    })
+  }
+
+  /// Get index, name, backend, stream state and format in a single call
+  /// `measuredFps` reflects captures made through this object (not background
+  /// streams) and is null until two frames have been captured close together.
+  #[napi]
+  pub fn status(&self) -> Result<CameraStatus, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
+    let info = cam.info();
+    let fmt = cam.camera_format();
+    Ok(CameraStatus {
+      index: info.index().as_string(),
+      name: info.human_name(),
+      backend: convert_backend_to_napi(cam.backend()),
+      is_stream_open: cam.is_stream_open(),
+      current_format: CameraFormat {
+        resolution: Resolution {
+          width: fmt.width(),
+          height: fmt.height(),
+        },
+        frame_rate: fmt.frame_rate(),
+        format: convert_frame_format(fmt.format()),
+      },
+      negotiated_pixel_format: convert_frame_format(cam.frame_format()),
+      measured_fps: self.fps_meter.fps(),
+    })
   }
 
   /// Get the current camera format
   /// Note: This returns the requested frame rate. Use refresh_camera_format()
   /// to get the actual active frame rate from the camera.
   #[napi]
-  pub fn camera_format(&self) -> CameraFormat {
-    let cam = self
-      .camera
-      .as_ref()
-      .expect("Camera is closed or has been stopped");
+  pub fn camera_format(&self) -> Result<CameraFormat, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
     let fmt = cam.camera_format();
-    CameraFormat {
+    Ok(CameraFormat {
       resolution: Resolution {
         width: fmt.width(),
         height: fmt.height(),
       },
       frame_rate: fmt.frame_rate(),
       format: convert_frame_format(fmt.format()),
-    }
+    })
+  }
+
+  /// Get the resolution of the negotiated format, e.g. to size buffers before capturing
+  /// Frames may still come out differently sized after deinterlacing, rotation
+  /// or a region of interest.
+  #[napi]
+  pub fn resolution(&self) -> Result<Resolution, ErrorCode> {
+    let resolution = self.camera.lock_coded()?.resolution();
+    Ok(Resolution {
+      width: resolution.width(),
+      height: resolution.height(),
+    })
+  }
+
+  /// Get the pixel format the device delivers in the negotiated format
+  #[napi]
+  pub fn frame_format(&self) -> Result<FrameFormat, ErrorCode> {
+    Ok(convert_frame_format(
+      self.camera.lock_coded()?.frame_format(),
+    ))
   }
 
   /// Refresh and get the camera format
   #[napi]
-  pub fn refresh_camera_format(&mut self) -> Result<CameraFormat> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
+  pub fn refresh_camera_format(&mut self) -> Result<CameraFormat, ErrorCode> {
+    let mut cam = self.camera.lock_coded()?;
     let fmt = cam
       .refresh_camera_format()
-      .map_err(|e| Error::from_reason(format!("Failed to refresh camera format: {}", e)))?;
+      .map_err(|e| nokhwa_error("Failed to refresh camera format", &e))?;
     Ok(CameraFormat {
       resolution: Resolution {
         width: fmt.width(),
@@ -174,16 +839,73 @@ impl Camera {
   }
 
   /// Set camera format with requested configuration
+  /// With `width`, `height` or `frameRate` set, the device format closest to
+  /// them is picked (e.g. 1280x720 at 30fps); otherwise formats are ranked by
+  /// the request type. Returns the format the camera actually negotiated.
   #[napi]
-  pub fn set_camera_request(&mut self, request: RequestedFormatConfig) -> Result<CameraFormat> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
-    let nokhwa_format = convert_requested_format(request)?;
-    let fmt = cam
-      .set_camera_requset(nokhwa_format)
-      .map_err(|e| Error::from_reason(format!("Failed to set camera format: {}", e)))?;
+  pub fn set_camera_request(
+    &mut self,
+    request: RequestedFormatConfig,
+  ) -> Result<CameraFormat, ErrorCode> {
+    let mut cam = self.camera.lock_coded()?;
+    let fmt = apply_format_request(&mut cam, request).map_err(anyhow_to_coded)?;
+    Ok(CameraFormat {
+      resolution: Resolution {
+        width: fmt.width(),
+        height: fmt.height(),
+      },
+      frame_rate: fmt.frame_rate(),
+      format: convert_frame_format(fmt.format()),
+    })
+  }
+
+  /// Change only the frame rate, keeping the current resolution and pixel format
+  /// The device mode with the rate nearest to `fps` is picked, e.g. 30 when
+  /// asking for 25 from a camera offering 15 and 30. Returns the negotiated
+  /// format; throws with code `FormatUnsupported` if the device lists no mode
+  /// at the current resolution and pixel format, or none within 25% of `fps`.
+  #[napi]
+  pub fn set_frame_rate(&mut self, fps: u32) -> Result<CameraFormat, ErrorCode> {
+    if fps == 0 {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        "Frame rate must be greater than 0",
+      ));
+    }
+    let mut cam = self.camera.lock_coded()?;
+    let fmt = conversions::set_closest_frame_rate(&mut cam, fps).map_err(anyhow_to_coded)?;
+    Ok(CameraFormat {
+      resolution: Resolution {
+        width: fmt.width(),
+        height: fmt.height(),
+      },
+      frame_rate: fmt.frame_rate(),
+      format: convert_frame_format(fmt.format()),
+    })
+  }
+
+  /// Change only the resolution, keeping the current pixel format and frame rate
+  /// The device mode nearest to `width`x`height` is picked, preferring the
+  /// current frame rate among modes of that size. Returns the negotiated format,
+  /// so check it when the device may not offer the exact resolution.
+  #[napi]
+  pub fn set_resolution(&mut self, width: u32, height: u32) -> Result<CameraFormat, ErrorCode> {
+    if width == 0 || height == 0 {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        "Resolution must be greater than 0x0",
+      ));
+    }
+    let mut cam = self.camera.lock_coded()?;
+    let current = cam.camera_format();
+    let request = RequestedFormatConfig {
+      request_type: RequestedFormatType::Closest,
+      format: Some(convert_frame_format(current.format())),
+      width: Some(width),
+      height: Some(height),
+      frame_rate: Some(current.frame_rate()),
+    };
+    let fmt = apply_format_request(&mut cam, request).map_err(anyhow_to_coded)?;
     Ok(CameraFormat {
       resolution: Resolution {
         width: fmt.width(),
@@ -196,14 +918,11 @@ impl Camera {
 
   /// Get compatible camera formats
   #[napi]
-  pub fn compatible_camera_formats(&mut self) -> Result<Vec<CameraFormat>> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
+  pub fn compatible_camera_formats(&mut self) -> Result<Vec<CameraFormat>, ErrorCode> {
+    let mut cam = self.camera.lock_coded()?;
     let formats = cam
       .compatible_camera_formats()
-      .map_err(|e| Error::from_reason(format!("Failed to get compatible formats: {}", e)))?;
+      .map_err(|e| nokhwa_error("Failed to get compatible formats", &e))?;
     Ok(
       formats
         .into_iter()
@@ -219,78 +938,445 @@ impl Camera {
     )
   }
 
+  /// Get compatible camera formats in a deterministic "best first" order
+  /// Sorted by resolution (largest first), then frame rate (highest first), then
+  /// by position in `prefer`; formats missing from `prefer` come last.
+  #[napi]
+  pub fn compatible_formats_sorted(
+    &mut self,
+    prefer: Vec<FrameFormat>,
+  ) -> Result<Vec<CameraFormat>, ErrorCode> {
+    let mut formats = self.compatible_camera_formats()?;
+    let rank = |format: FrameFormat| {
+      prefer
+        .iter()
+        .position(|p| *p == format)
+        .unwrap_or(prefer.len())
+    };
+    formats.sort_by(|a, b| {
+      let area = |f: &CameraFormat| f.resolution.width as u64 * f.resolution.height as u64;
+      area(b)
+        .cmp(&area(a))
+        .then(b.resolution.width.cmp(&a.resolution.width))
+        .then(b.frame_rate.cmp(&a.frame_rate))
+        .then(rank(a.format).cmp(&rank(b.format)))
+    });
+    Ok(formats)
+  }
+
+  /// Get compatible camera formats nested by pixel format, then resolution
+  /// Formats keep the order the device reports them in; within each, resolutions
+  /// are sorted highest pixel count first and list their distinct frame rates.
+  #[napi]
+  pub fn compatible_formats_grouped(&mut self) -> Result<Vec<FormatGroup>, ErrorCode> {
+    let mut groups: Vec<FormatGroup> = Vec::new();
+    for fmt in self.compatible_camera_formats()? {
+      let group = match groups.iter().position(|g| g.format == fmt.format) {
+        Some(i) => &mut groups[i],
+        None => {
+          groups.push(FormatGroup {
+            format: fmt.format,
+            resolutions: Vec::new(),
+          });
+          groups.last_mut().unwrap()
+        }
+      };
+      let same_size = |r: &ResolutionFrameRates| {
+        r.resolution.width == fmt.resolution.width && r.resolution.height == fmt.resolution.height
+      };
+      match group.resolutions.iter_mut().find(|r| same_size(r)) {
+        Some(entry) => entry.frame_rates.push(fmt.frame_rate),
+        None => group.resolutions.push(ResolutionFrameRates {
+          resolution: fmt.resolution,
+          frame_rates: vec![fmt.frame_rate],
+        }),
+      }
+    }
+
+    for group in &mut groups {
+      group.resolutions.sort_by(|a, b| {
+        let area = |r: &Resolution| r.width as u64 * r.height as u64;
+        area(&b.resolution)
+          .cmp(&area(&a.resolution))
+          .then(b.resolution.width.cmp(&a.resolution.width))
+      });
+      for entry in &mut group.resolutions {
+        entry.frame_rates.sort_unstable();
+        entry.frame_rates.dedup();
+      }
+    }
+    Ok(groups)
+  }
+
+  /// Get the exact frame rate as a fraction, e.g. 30000/1001 for 29.97 fps
+  /// `camera_format().frame_rate` is rounded to an integer. On Video4Linux the
+  /// driver's frame interval is read; other backends only report the integer
+  /// rate, which is returned as `fps/1`.
+  #[napi]
+  pub fn frame_interval(&self) -> Result<FrameInterval, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
+    Ok(timing::frame_interval(&cam))
+  }
+
+  /// Get the distinct frame rates the device supports at `width`x`height`, lowest first
+  /// Rates offered in several pixel formats are only listed once.
+  #[napi]
+  pub fn frame_rates_for_resolution(
+    &mut self,
+    width: u32,
+    height: u32,
+  ) -> Result<Vec<u32>, ErrorCode> {
+    let mut rates: Vec<u32> = self
+      .compatible_camera_formats()?
+      .into_iter()
+      .filter(|f| f.resolution.width == width && f.resolution.height == height)
+      .map(|f| f.frame_rate)
+      .collect();
+    rates.sort_unstable();
+    rates.dedup();
+    Ok(rates)
+  }
+
+  /// Get the distinct resolutions the device supports, highest pixel count first
+  #[napi]
+  pub fn supported_resolutions(&mut self) -> Result<Vec<Resolution>, ErrorCode> {
+    let mut resolutions: Vec<Resolution> = self
+      .compatible_camera_formats()?
+      .into_iter()
+      .map(|f| f.resolution)
+      .collect();
+    resolutions.sort_by(|a, b| {
+      let area = |r: &Resolution| r.width as u64 * r.height as u64;
+      area(b).cmp(&area(a)).then(b.width.cmp(&a.width))
+    });
+    resolutions.dedup_by(|a, b| a.width == b.width && a.height == b.height);
+    Ok(resolutions)
+  }
+
   /// Get supported camera controls
   #[napi]
-  pub fn supported_camera_controls(&self) -> Result<Vec<KnownCameraControl>> {
-    let cam = self
-      .camera
-      .as_ref()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
+  pub fn supported_camera_controls(&self) -> Result<Vec<KnownCameraControl>, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
     let controls = cam
       .supported_camera_controls()
-      .map_err(|e| Error::from_reason(format!("Failed to get supported controls: {}", e)))?;
+      .map_err(|e| nokhwa_error("Failed to get supported controls", &e))?;
     Ok(controls.into_iter().map(convert_known_control).collect())
   }
 
+  /// Get the ids of the supported vendor-specific controls, i.e. the `Other` entries
+  #[napi]
+  pub fn supported_other_controls(&self) -> Result<Vec<String>, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
+    let controls = cam
+      .supported_camera_controls()
+      .map_err(|e| nokhwa_error("Failed to get supported controls", &e))?;
+    Ok(controls.into_iter().filter_map(other_control_id).collect())
+  }
+
+  /// Check if the device supports a control, e.g. to enable or disable its slider
+  /// With `other_id`, `Other` matches only that vendor control, otherwise any of them.
+  #[napi]
+  pub fn is_control_supported(
+    &self,
+    control: KnownCameraControl,
+    other_id: Option<String>,
+  ) -> Result<bool, ErrorCode> {
+    let wanted = match other_id {
+      Some(id) => Some(convert_known_control_to_nokhwa(control, Some(&id)).map_err(into_coded)?),
+      None => None,
+    };
+    let cam = self.camera.lock_coded()?;
+    let controls = cam
+      .supported_camera_controls()
+      .map_err(|e| nokhwa_error("Failed to get supported controls", &e))?;
+    Ok(controls.into_iter().any(|supported| match wanted {
+      Some(wanted) => supported == wanted,
+      None => convert_known_control(supported) == control,
+    }))
+  }
+
   /// Get all camera controls
   #[napi]
-  pub fn camera_controls(&self) -> Result<Vec<CameraControl>> {
-    let cam = self
-      .camera
-      .as_ref()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
+  pub fn camera_controls(&self) -> Result<Vec<CameraControl>, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
     let controls = cam
       .camera_controls()
-      .map_err(|e| Error::from_reason(format!("Failed to get camera controls: {}", e)))?;
+      .map_err(|e| nokhwa_error("Failed to get camera controls", &e))?;
     Ok(controls.into_iter().map(convert_camera_control).collect())
   }
 
+  /// Get the descriptor of a single control, including its current value
+  /// Fails naming the control if the device doesn't support it.
+  #[napi]
+  pub fn get_camera_control(
+    &self,
+    control: KnownCameraControl,
+    other_id: Option<String>,
+  ) -> Result<CameraControl, ErrorCode> {
+    let nokhwa_control =
+      convert_known_control_to_nokhwa(control, other_id.as_deref()).map_err(into_coded)?;
+    let cam = self.camera.lock_coded()?;
+    let controls = cam
+      .camera_controls()
+      .map_err(|e| nokhwa_error("Failed to get camera controls", &e))?;
+    controls
+      .into_iter()
+      .find(|c| c.control() == nokhwa_control)
+      .map(convert_camera_control)
+      .ok_or_else(|| {
+        coded_error(
+          ErrorCode::Napi(Status::GenericFailure),
+          format!("Camera doesn't support the {} control", nokhwa_control),
+        )
+      })
+  }
+
+  /// Read the current value of every supported control
+  /// Controls whose value can't be read, or has no JS representation, are skipped
+  #[napi]
+  pub fn controls_snapshot(&self) -> Result<Vec<ControlSnapshot>, ErrorCode> {
+    let cam = self.camera.lock_coded()?;
+    let controls = cam
+      .supported_camera_controls()
+      .map_err(|e| nokhwa_error("Failed to get supported controls", &e))?;
+    Ok(
+      controls
+        .into_iter()
+        .filter_map(|known| {
+          let control = cam.camera_control(known).ok()?;
+          Some(ControlSnapshot {
+            name: control.name().to_string(),
+            control: convert_known_control(known),
+            other_id: other_control_id(known),
+            value: convert_control_value_to_napi(control.value())?,
+          })
+        })
+        .collect(),
+    )
+  }
+
   /// Set a camera control value
   #[napi]
   pub fn set_camera_control(
     &mut self,
     control: KnownCameraControl,
     value: ControlValueSetter,
-  ) -> Result<()> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
-    let nokhwa_control = convert_known_control_to_nokhwa(control);
+    other_id: Option<String>,
+  ) -> Result<(), ErrorCode> {
+    let nokhwa_control =
+      convert_known_control_to_nokhwa(control, other_id.as_deref()).map_err(into_coded)?;
+    let mut cam = self.camera.lock_coded()?;
     let nokhwa_value = convert_control_value(value);
     cam
       .set_camera_control(nokhwa_control, nokhwa_value)
-      .map_err(|e| Error::from_reason(format!("Failed to set camera control: {}", e)))?;
+      .map_err(|e| nokhwa_error("Failed to set camera control", &e))?;
     Ok(())
   }
 
+  /// Set several controls in one go, e.g. to apply a saved profile
+  /// The camera stays locked for the whole batch so no capture lands between the
+  /// writes. A control that fails is reported in its entry and the rest are still
+  /// applied; the results are in the order of `controls`.
+  #[napi]
+  pub fn set_camera_controls(
+    &mut self,
+    controls: Vec<ControlSetting>,
+  ) -> Result<Vec<ControlSetResult>, ErrorCode> {
+    let mut cam = self.camera.lock_coded()?;
+    Ok(
+      controls
+        .into_iter()
+        .map(|setting| {
+          let applied =
+            convert_known_control_to_nokhwa(setting.control, setting.other_id.as_deref())
+              .map_err(into_coded)
+              .and_then(|nokhwa_control| {
+                cam
+                  .set_camera_control(nokhwa_control, convert_control_value(setting.value))
+                  .map_err(|e| nokhwa_error("Failed to set camera control", &e))
+              });
+          ControlSetResult {
+            control: setting.control,
+            other_id: setting.other_id,
+            ok: applied.is_ok(),
+            error: applied.err().map(|e| e.reason.clone()),
+          }
+        })
+        .collect(),
+    )
+  }
+
+  /// Serialize the current value of every readable control as a JSON preset
+  /// Restore it later, or on another camera of the same model, with `import_controls`.
+  #[napi]
+  pub fn export_controls(&self) -> Result<String, ErrorCode> {
+    controls::export_preset(self.controls_snapshot()?)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::GenericFailure), e))
+  }
+
+  /// Apply a JSON preset written by `export_controls`
+  /// Entries this version can't read are skipped with a warning instead of
+  /// failing the import; the others are applied as by `set_camera_controls`.
+  #[napi]
+  pub fn import_controls(&mut self, json: String) -> Result<ControlsImport, ErrorCode> {
+    let (settings, warnings) = controls::parse_preset(&json)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+    Ok(ControlsImport {
+      applied: self.set_camera_controls(settings)?,
+      warnings,
+    })
+  }
+
+  /// Set a camera control value and return the value the camera actually holds afterwards
+  /// Cameras snap requests to their supported steps (e.g. 55 becomes 50), so the
+  /// control is read back after writing instead of echoing the request.
+  #[napi]
+  pub fn set_camera_control_checked(
+    &mut self,
+    control: KnownCameraControl,
+    value: ControlValueSetter,
+    other_id: Option<String>,
+  ) -> Result<ControlValueSetter, ErrorCode> {
+    let nokhwa_control =
+      convert_known_control_to_nokhwa(control, other_id.as_deref()).map_err(into_coded)?;
+    let mut cam = self.camera.lock_coded()?;
+    cam
+      .set_camera_control(nokhwa_control, convert_control_value(value))
+      .map_err(|e| nokhwa_error("Failed to set camera control", &e))?;
+    let applied = cam
+      .camera_control(nokhwa_control)
+      .map_err(|e| nokhwa_error("Failed to read back camera control", &e))?;
+    convert_control_value_to_napi(applied.value()).ok_or_else(|| {
+      coded_error(
+        ErrorCode::Napi(Status::GenericFailure),
+        format!(
+          "Control {} holds a value with no JS representation",
+          applied.name()
+        ),
+      )
+    })
+  }
+
+  /// Read a control's current value mapped linearly onto 0.0-1.0 of its native min/max
+  /// Lets one slider drive controls whose native units differ between cameras.
+  /// Boolean controls read as 0 or 1; controls without a range are an error.
+  #[napi]
+  pub fn get_control_normalized(
+    &self,
+    control: KnownCameraControl,
+    other_id: Option<String>,
+  ) -> Result<f64, ErrorCode> {
+    let nokhwa_control =
+      convert_known_control_to_nokhwa(control, other_id.as_deref()).map_err(into_coded)?;
+    let cam = self.camera.lock_coded()?;
+    let descriptor = cam
+      .camera_control(nokhwa_control)
+      .map_err(|e| nokhwa_error("Failed to get camera control", &e))?;
+    controls::normalized_value(&descriptor)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::GenericFailure), e))
+  }
+
+  /// Set a control from a 0.0-1.0 value mapped linearly onto its native min/max
+  /// The value is clamped to 0.0-1.0 and snapped to the control's step.
+  #[napi]
+  pub fn set_control_normalized(
+    &mut self,
+    control: KnownCameraControl,
+    value: f64,
+    other_id: Option<String>,
+  ) -> Result<(), ErrorCode> {
+    let nokhwa_control =
+      convert_known_control_to_nokhwa(control, other_id.as_deref()).map_err(into_coded)?;
+    let mut cam = self.camera.lock_coded()?;
+    let descriptor = cam
+      .camera_control(nokhwa_control)
+      .map_err(|e| nokhwa_error("Failed to get camera control", &e))?;
+    let native = controls::denormalize_value(&descriptor, value)
+      .map_err(|e| coded_error(ErrorCode::Napi(Status::GenericFailure), e))?;
+    cam
+      .set_camera_control(nokhwa_control, native)
+      .map_err(|e| nokhwa_error("Failed to set camera control", &e))?;
+    Ok(())
+  }
+
+  /// Let auto white balance settle over a few frames, then lock it at that value
+  /// Keeps colors consistent across a capture session. Throws if the camera
+  /// has no manual white balance control.
+  #[napi]
+  pub fn lock_white_balance(&mut self) -> Result<(), ErrorCode> {
+    let mut cam = self.camera.lock_coded()?;
+    controls::lock_white_balance(&mut cam, controls::WHITE_BALANCE_WARMUP_FRAMES)
+      .map_err(anyhow_to_coded)
+  }
+
+  /// Capture as fast as possible for `duration_ms` and compare the real frame rate with the advertised one
+  /// USB bandwidth or lighting often keep cameras below their nominal rate. The
+  /// device stays locked for the whole run, so other calls on this camera wait
+  /// (and fail as busy after two seconds).
+  #[napi]
+  pub fn benchmark_fps(&mut self, duration_ms: u32) -> Result<FpsBenchmark, ErrorCode> {
+    if duration_ms == 0 {
+      return Err(coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        "Duration must be greater than 0",
+      ));
+    }
+    let mut cam = self.camera.lock_coded()?;
+    timing::benchmark_fps(&mut cam, Duration::from_millis(duration_ms as u64))
+      .map_err(anyhow_to_coded)
+  }
+
+  /// Stop the stream and release the device right away instead of on garbage collection
+  /// Use this when another process or `Camera` needs the device. Background
+  /// streams end, and later calls on this object throw with code `CameraClosed`.
+  /// Closing twice is harmless.
+  #[napi]
+  pub fn close(&mut self) {
+    self.camera.close();
+  }
+
+  /// Reopen the device after it was unplugged or stopped responding
+  /// Opens the same index and backend again with automatic format detection, as
+  /// the constructor does, and starts its stream. Streams that ended with a
+  /// `Disconnected` error have to be started again afterwards. Throws like the
+  /// constructor while the device isn't back yet; the call can be retried.
+  #[napi]
+  pub fn reconnect(&mut self) -> Result<(), ErrorCode> {
+    self.camera.reopen(create_camera_with_fallback)?;
+    if let Some(cache) = self.decode_cache.as_mut() {
+      cache.clear();
+    }
+    self.fps_meter = FpsMeter::default();
+    Ok(())
+  }
+
+  /// Check if `close()` has released the camera
+  #[napi]
+  pub fn is_closed(&self) -> bool {
+    self.camera.is_closed()
+  }
+
   /// Check if stream is open
   #[napi]
-  pub fn is_stream_open(&self) -> bool {
-    self.camera.as_ref().is_some_and(|cam| cam.is_stream_open())
+  pub fn is_stream_open(&self) -> Result<bool, ErrorCode> {
+    Ok(self.camera.lock_coded()?.is_stream_open())
   }
 
   /// Open the camera stream
   #[napi]
-  pub fn open_stream(&mut self) -> Result<()> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
+  pub fn open_stream(&mut self) -> Result<(), ErrorCode> {
+    let mut cam = self.camera.lock_coded()?;
     cam
       .open_stream()
-      .map_err(|e| Error::from_reason(format!("Failed to open stream: {}", e)))?;
+      .map_err(|e| nokhwa_error("Failed to open stream", &e))?;
     Ok(())
   }
 
   /// Stop the camera stream
   #[napi]
-  pub fn stop_stream(&mut self) -> Result<()> {
+  pub fn stop_stream(&mut self) -> Result<(), ErrorCode> {
     // Get reference to camera without taking ownership
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is already stopped or closed"))?;
+    let mut cam = self.camera.lock_coded()?;
 
     // Close the stream
     // This is critical on Windows with MediaFoundation backend
@@ -306,29 +1392,386 @@ impl Camera {
     Ok(())
   }
 
+  /// Start streaming decoded RGBA frames to a callback
+  /// A native thread captures at the device's own rate and calls `callback` with
+  /// `{ data, width, height, fps, dropped, total }` for every frame, using this camera's color
+  /// space and output origin. Capture errors are passed as the callback's first
+  /// argument. Call `stop()` on the returned handle to end the stream; dropping
+  /// the handle stops and joins the thread as well.
+  #[napi]
+  pub fn start_stream(
+    &self,
+    callback: ThreadsafeFunction<StreamFrame, Unknown<'static>, StreamFrame, ErrorCode>,
+    config: Option<StreamConfig>,
+  ) -> Result<StreamHandle> {
+    let config = config.unwrap_or_default();
+    stream::check_stream_config(&config)?;
+    Ok(stream::spawn_frame_stream(
+      self.camera.clone(),
+      self.color_space,
+      self.origin,
+      callback,
+      config,
+    ))
+  }
+
+  /// Start streaming RGBA frames through a fixed ring of `capacity` reusable buffers
+  /// Like `start_stream`, but frames are decoded straight into the ring's slots
+  /// instead of a new buffer each. The callback gets `{ slot, data, width,
+  /// height, fps, timestampMs }` where `data` views the slot's memory: it is
+  /// detached once the callback returns, so copy out anything kept longer. A
+  /// frame arriving while its slot is still in a callback is dropped and
+  /// counted in `droppedFrames()`.
+  #[napi]
+  pub fn start_ring_stream(
+    &self,
+    capacity: u32,
+    callback: ThreadsafeFunction<RingFrame, Unknown<'static>, RingFrame, ErrorCode>,
+    config: Option<StreamConfig>,
+  ) -> Result<StreamHandle> {
+    if capacity == 0 {
+      return Err(Error::from_reason("Ring capacity must be greater than 0"));
+    }
+    let config = config.unwrap_or_default();
+    stream::check_stream_config(&config)?;
+    Ok(stream::spawn_ring_stream(
+      self.camera.clone(),
+      self.color_space,
+      self.origin,
+      capacity as usize,
+      callback,
+      config,
+    ))
+  }
+
+  /// Start streaming JPEG frames to a callback, e.g. for `multipart/x-mixed-replace` HTTP endpoints
+  /// Capture and encoding run on a native thread at up to `fps` frames per second.
+  /// MJPEG sources are passed through without re-encoding; other formats are
+  /// encoded at `quality` (1-100). Capture errors are passed as the callback's
+  /// first argument. Call `stop()` on the returned handle to end the stream.
+  /// `config` tunes the native capture thread (priority, CPU affinity) and how many
+  /// frames may queue up for a slow callback before `queuePolicy` drops them.
+  #[napi]
+  pub fn mjpeg_stream(
+    &self,
+    quality: u32,
+    fps: u32,
+    callback: ThreadsafeFunction<Buffer, Unknown<'static>, Buffer, ErrorCode>,
+    config: Option<StreamConfig>,
+  ) -> Result<StreamHandle> {
+    if fps == 0 {
+      return Err(Error::from_reason("Frame rate must be greater than 0"));
+    }
+    let config = config.unwrap_or_default();
+    stream::check_stream_config(&config)?;
+    Ok(stream::spawn_mjpeg_stream(
+      self.camera.clone(),
+      quality,
+      fps,
+      callback,
+      config,
+    ))
+  }
+
+  /// Prepare one capture thread at `fps` that feeds several outputs at once
+  /// Register sinks with `onFrame`, `recordTo` and `mjpegTo`, then `start()`.
+  /// Frames are captured and decoded once for all of them, with this camera's
+  /// color space; the output origin applies to `onFrame` only.
+  #[napi]
+  pub fn stream_builder(&self, fps: u32, config: Option<StreamConfig>) -> Result<StreamBuilder> {
+    if fps == 0 {
+      return Err(Error::from_reason("Frame rate must be greater than 0"));
+    }
+    let config = config.unwrap_or_default();
+    stream::check_stream_config(&config)?;
+    Ok(StreamBuilder::new(
+      self.camera.clone(),
+      fps,
+      self.color_space,
+      self.origin,
+      config,
+    ))
+  }
+
+  /// Start a pull-based frame stream
+  /// Each `next()` resolves to the next RGBA frame (with the current origin and
+  /// color space settings), or `null` once the iterator is stopped:
+  /// `let f; while ((f = await it.next()) !== null) { ... }`, or
+  /// `for await (const f of cam.frameIterator()) { ... }`
+  #[napi(ts_return_type = "FrameIterator & AsyncIterable<Frame>")]
+  pub fn frame_iterator<'env>(&self, env: &'env Env) -> Result<ClassInstance<'env, FrameIterator>> {
+    let iterator = stream::spawn_frame_iterator(self.camera.clone(), self.color_space, self.origin);
+    stream::into_async_iterable(env, iterator)
+  }
+
+  /// Record `duration_ms` of video at `fps` to a YUV4MPEG2 (.y4m) file
+  /// Frames are captured and written on a native thread; convert the result with
+  /// e.g. `ffmpeg -i out.y4m out.mp4`. Call `stop()` on the handle to end early.
+  #[napi]
+  pub fn record_to_y4m(&self, path: String, duration_ms: u32, fps: u32) -> Result<RecordingHandle> {
+    if fps == 0 {
+      return Err(Error::from_reason("Frame rate must be greater than 0"));
+    }
+    record::spawn_y4m_recording(
+      self.camera.clone(),
+      &path,
+      Duration::from_millis(duration_ms as u64),
+      fps,
+      self.color_space,
+    )
+    .map_err(|e| Error::from_reason(e.to_string()))
+  }
+
   /// Get raw frame data
+  /// Fails with code `InvalidResolution` if the camera keeps reporting a 0x0 size.
+  /// `timeout_ms` works as for `capture_frame`.
   #[napi]
-  pub fn frame_raw(&mut self) -> Result<CameraBuffer> {
-    let cam = self
-      .camera
-      .as_mut()
-      .ok_or_else(|| Error::from_reason("Camera is closed or has been stopped"))?;
-    let resolution = cam.resolution();
-    let frame_format = cam.frame_format();
-    let raw = cam
-      .frame_raw()
-      .map_err(|e| Error::from_reason(format!("Failed to get raw frame: {}", e)))?;
+  pub fn frame_raw(&mut self, timeout_ms: Option<u32>) -> Result<CameraBuffer, ErrorCode> {
+    let (raw, width, height) = self.grab(LatencyMode::Balanced, timeout_ms)?;
+    self.fps_meter.tick();
     Ok(CameraBuffer {
-      resolution: Resolution {
-        width: resolution.width(),
-        height: resolution.height(),
-      },
-      data: raw.to_vec(),
-      source_frame_format: convert_frame_format(frame_format),
+      resolution: Resolution { width, height },
+      source_frame_format: convert_frame_format(raw.source_frame_format()),
+      data: Arc::new(raw.buffer().to_vec()),
     })
   }
 }
 
+impl Camera {
+  /// Wrap an opened nokhwa camera with default output settings
+  fn from_nokhwa(camera: nokhwa::Camera) -> Self {
+    Self {
+      camera: SharedCamera::new(camera),
+      origin: ImageOrigin::TopLeft,
+      color_space: ColorSpace::Bt601,
+      decode_cache: None,
+      fps_meter: FpsMeter::default(),
+      pipeline: Vec::new(),
+      deinterlace: None,
+      latency_mode: LatencyMode::Balanced,
+      adjust_lut: None,
+      overlay: None,
+      transform: FrameTransform::default(),
+      region_of_interest: None,
+      capture_scratch: Vec::new(),
+    }
+  }
+
+  /// Capture a frame as RGBA for the methods that post-process it in Rust
+  fn capture_rgba(&mut self) -> Result<RgbaFrame, ErrorCode> {
+    let (buffer, width, height) = self.grab(self.latency_mode, None)?;
+    self.finish_rgba(&buffer, width, height)
+  }
+
+  /// Take the next raw frame with the camera's reported resolution
+  /// With a timeout the wait happens on a helper thread, see `capture_frame`.
+  fn grab(
+    &self,
+    latency_mode: LatencyMode,
+    timeout_ms: Option<u32>,
+  ) -> Result<(nokhwa::Buffer, u32, u32), ErrorCode> {
+    let camera = self.camera.clone();
+    let grab = move || -> anyhow::Result<_> {
+      let mut cam = camera.lock_coded().map_err(|e| CodedFailure {
+        code: e.status,
+        message: e.reason.clone(),
+      })?;
+      let buffer = next_buffer_for(&mut cam, latency_mode)?;
+      let resolution = cam.resolution();
+      Ok((buffer, resolution.width(), resolution.height()))
+    };
+
+    let Some(timeout_ms) = timeout_ms else {
+      return grab().map_err(anyhow_to_coded);
+    };
+    match timing::run_with_timeout(Duration::from_millis(timeout_ms as u64), grab) {
+      Ok(grabbed) => grabbed.map_err(anyhow_to_coded),
+      Err(RecvTimeoutError::Timeout) => Err(coded_error(
+        ErrorCode::Timeout,
+        format!("No frame arrived from the camera within {} ms", timeout_ms),
+      )),
+      Err(RecvTimeoutError::Disconnected) => Err(coded_error(
+        ErrorCode::Napi(Status::GenericFailure),
+        "Capturing frame: the capture thread panicked",
+      )),
+    }
+  }
+
+  /// Decode a grabbed frame to RGBA and apply the per-camera transforms
+  fn finish_rgba(
+    &mut self,
+    buffer: &nokhwa::Buffer,
+    width: u32,
+    height: u32,
+  ) -> Result<RgbaFrame, ErrorCode> {
+    let mut frame = match self.decode_cache.as_mut() {
+      Some(cache) => decode_to_rgba_cached(buffer, width, height, self.color_space, cache),
+      None => decode_to_rgba(buffer, width, height, self.color_space),
+    }
+    .map_err(anyhow_to_coded)?;
+    self.fps_meter.tick();
+    (frame.width, frame.height) =
+      self
+        .frame_finish()
+        .apply(&mut frame.data, frame.width, frame.height);
+    Ok(frame)
+  }
+
+  /// Snapshot of the transforms the capture methods apply after decoding
+  fn frame_finish(&self) -> FrameFinish {
+    FrameFinish {
+      deinterlace: self.deinterlace,
+      adjust_lut: self.adjust_lut,
+      overlay: self.overlay.clone(),
+      transform: self.transform,
+      region_of_interest: self.region_of_interest,
+      origin: self.origin,
+    }
+  }
+}
+
+/// Per-camera transforms applied to every decoded RGBA frame, in this order
+#[derive(Clone)]
+struct FrameFinish {
+  deinterlace: Option<DeinterlaceMode>,
+  transform: FrameTransform,
+  region_of_interest: Option<(u32, u32, u32, u32)>,
+  adjust_lut: Option<[u8; 256]>,
+  overlay: Option<Arc<processing::Overlay>>,
+  origin: ImageOrigin,
+}
+
+impl FrameFinish {
+  /// Transforms RGBA `data` and returns the new width and height
+  /// Deinterlacing may halve the height, a 90 or 270 degree rotation swaps them
+  /// and the region of interest crops them.
+  fn apply(&self, data: &mut Vec<u8>, width: u32, height: u32) -> (u32, u32) {
+    self.apply_channels(data, width, height, 4)
+  }
+
+  /// Same as `apply` for frames with `channels` bytes per pixel
+  /// The software adjustment and overlay work on RGBA only and are skipped for
+  /// other layouts, so callers with those set must decode to RGBA.
+  fn apply_channels(
+    &self,
+    data: &mut Vec<u8>,
+    width: u32,
+    height: u32,
+    channels: usize,
+  ) -> (u32, u32) {
+    let height = match self.deinterlace {
+      Some(mode) => processing::deinterlace(data, width, height, channels, mode),
+      None => height,
+    };
+    let (mut width, mut height) =
+      processing::transform(data, width, height, channels, self.transform);
+    if let Some((x, y, w, h)) = self.region_of_interest {
+      // The region was checked against the resolution when it was set; clip it
+      // in case the camera has switched to a smaller format since
+      let (w, h) = (
+        w.min(width.saturating_sub(x)),
+        h.min(height.saturating_sub(y)),
+      );
+      if w > 0 && h > 0 {
+        *data = processing::crop(data, width, channels, x, y, w, h);
+        (width, height) = (w, h);
+      }
+    }
+    if channels == 4 {
+      if let Some(lut) = &self.adjust_lut {
+        processing::apply_lut(data, lut);
+      }
+      if let Some(overlay) = &self.overlay {
+        processing::overlay(data, width, height, overlay);
+      }
+    }
+    if self.origin == ImageOrigin::BottomLeft {
+      processing::flip_vertical(data, width, height, channels);
+    }
+    (width, height)
+  }
+}
+
+// ============================================================================
+// Async Capture
+// ============================================================================
+
+/// Capture and decode running on the libuv thread pool, see `Camera::capture_frame_async`
+/// The task holds a clone of the camera's shared handle and a snapshot of its
+/// settings, so the JS object may change or go away while the task runs.
+pub struct CaptureFrameTask {
+  camera: SharedCamera,
+  color_space: ColorSpace,
+  latency_mode: LatencyMode,
+  finish: FrameFinish,
+  /// Coded error of a failed `compute`, thrown with its code by `reject`
+  failure: Option<Error<ErrorCode>>,
+}
+
+impl CaptureFrameTask {
+  fn capture(&self) -> Result<RgbaFrame, ErrorCode> {
+    let mut frame = {
+      let mut cam = self.camera.lock_coded()?;
+      let buffer = next_buffer_for(&mut cam, self.latency_mode).map_err(anyhow_to_coded)?;
+      let resolution = cam.resolution();
+      decode_to_rgba(
+        &buffer,
+        resolution.width(),
+        resolution.height(),
+        self.color_space,
+      )
+      .map_err(anyhow_to_coded)?
+    };
+    (frame.width, frame.height) = self
+      .finish
+      .apply(&mut frame.data, frame.width, frame.height);
+    Ok(frame)
+  }
+}
+
+impl Task for CaptureFrameTask {
+  type Output = RgbaFrame;
+  type JsValue = Frame;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.capture().map_err(|e| {
+      let plain = Error::from_reason(e.reason.clone());
+      self.failure = Some(e);
+      plain
+    })
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    convert_to_napi_frame(output)
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_coded(env, err, self.failure.take())
+  }
+}
+
+/// Waits on the libuv thread pool for the OS permission prompt, see `nokhwa_initialize_async`
+pub struct InitializeTask;
+
+impl Task for InitializeTask {
+  type Output = bool;
+  type JsValue = bool;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let (sender, receiver) = mpsc::channel();
+    nokhwa::nokhwa_initialize(move |granted| {
+      let _ = sender.send(granted);
+    });
+    // nokhwa drops the callback without calling it only if the request never
+    // started; the current authorization status is the best answer then
+    Ok(receiver.recv().unwrap_or_else(|_| nokhwa::nokhwa_check()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
 // ============================================================================
 // Utility Functions - Camera Discovery
 // ============================================================================
@@ -348,12 +1791,85 @@ pub fn list_cameras() -> Result<Vec<CameraDevice>> {
   )
 }
 
+/// List cameras that can be opened right now
+/// Each device is briefly opened and closed, so devices already in use by
+/// another process are left out
+#[napi]
+pub fn list_available_cameras() -> Result<Vec<CameraDevice>> {
+  let cameras =
+    list_cameras_with_status_internal().map_err(|e| Error::from_reason(e.to_string()))?;
+  Ok(
+    cameras
+      .into_iter()
+      .filter(|cam| cam.available)
+      .map(|cam| CameraDevice {
+        index: cam.index,
+        name: cam.name,
+      })
+      .collect(),
+  )
+}
+
+/// List all detected cameras with an `available` flag per device
+/// Availability is checked the same way as in list_available_cameras()
+#[napi]
+pub fn list_cameras_with_status() -> Result<Vec<CameraDeviceStatus>> {
+  list_cameras_with_status_internal().map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// List all detected cameras with a JPEG thumbnail of what each one sees
+/// Every device is opened in turn, a few warmup frames are dropped and the next
+/// frame is scaled to `thumb_width` (keeping the aspect ratio) and encoded at
+/// `quality` (1-100). Busy or failing devices are listed with a null thumbnail.
+#[napi]
+pub fn list_cameras_with_thumbnails(
+  thumb_width: u32,
+  quality: u32,
+) -> Result<Vec<CameraThumbnail>> {
+  camera::list_cameras_with_thumbnails(thumb_width, quality)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Open the camera `prefer` ranks highest among all connected devices
+/// Each device is opened briefly to read its formats; the winner is opened in
+/// the format it was ranked by, or with automatic detection if the device
+/// refuses that format. ExternalFirst guesses from device names and paths, so
+/// an unusual name may rank a built-in camera as external.
+#[napi]
+pub fn open_best_camera(prefer: CameraPreference) -> Result<Camera, ErrorCode> {
+  use nokhwa::utils::{RequestedFormat, RequestedFormatType};
+
+  ensure_initialized()?;
+  let (index, format) = camera::find_best_camera(prefer).map_err(anyhow_to_coded)?;
+  let formats = [format.format()];
+  let request = RequestedFormat::with_formats(RequestedFormatType::Exact(format), &formats);
+  let exact = nokhwa::Camera::new(index.clone(), request).and_then(|mut cam| {
+    cam.open_stream()?;
+    Ok(cam)
+  });
+  let camera = match exact {
+    Ok(cam) => cam,
+    Err(_) => create_camera_with_fallback(index, nokhwa::utils::ApiBackend::Auto)?,
+  };
+  Ok(Camera::from_nokhwa(camera))
+}
+
+/// Check that a camera works: open it, capture 3 frames and report the outcome
+/// Checks that frames arrive, match the reported size and aren't black. Problems
+/// end up in `warnings` rather than being thrown; only an invalid index throws.
+/// The camera is always stopped and released before this returns.
+#[napi]
+pub fn camera_self_test(index: String) -> Result<SelfTestReport> {
+  let index = parse_camera_index(index)?;
+  Ok(self_test(index))
+}
+
 /// Query available cameras with specific backend
 #[napi]
-pub fn query(backend: ApiBackend) -> Result<Vec<CameraDevice>> {
+pub fn query(backend: ApiBackend) -> Result<Vec<CameraDevice>, ErrorCode> {
   let nokhwa_backend = convert_backend(backend);
-  let cameras = nokhwa::query(nokhwa_backend)
-    .map_err(|e| Error::from_reason(format!("Failed to query cameras: {}", e)))?;
+  let cameras =
+    nokhwa::query(nokhwa_backend).map_err(|e| nokhwa_error("Failed to query cameras", &e))?;
   Ok(
     cameras
       .into_iter()
@@ -365,6 +1881,50 @@ pub fn query(backend: ApiBackend) -> Result<Vec<CameraDevice>> {
   )
 }
 
+/// Call `callback` with the cameras plugged in and unplugged, until the watcher is stopped
+/// The camera list is polled every `interval_ms` (default 1000) on a background
+/// thread. A burst of changes, e.g. a device re-enumerating, is reported as one
+/// event once the list has settled. Throws if the initial list can't be read.
+#[napi]
+pub fn watch_devices(
+  callback: ThreadsafeFunction<DeviceChange, Unknown<'static>, DeviceChange, ErrorCode>,
+  interval_ms: Option<u32>,
+) -> Result<DeviceWatcher, ErrorCode> {
+  let interval_ms = interval_ms.unwrap_or(1000);
+  if interval_ms == 0 {
+    return Err(coded_error(
+      ErrorCode::Napi(Status::InvalidArg),
+      "Interval must be greater than 0",
+    ));
+  }
+  let initial = watch::query_devices().map_err(|e| nokhwa_error("Failed to query cameras", &e))?;
+  Ok(watch::spawn_device_watcher(
+    initial,
+    Duration::from_millis(interval_ms as u64),
+    callback,
+  ))
+}
+
+// ============================================================================
+// Utility Functions - Config Storage
+// ============================================================================
+
+/// Save a camera profile (any JSON text) under `stable_id` in the JSON file at `path`
+/// The file is created if missing and profiles of other cameras are kept. Access
+/// is guarded by a file lock, so several processes may share one file.
+#[napi]
+pub fn save_camera_config(stable_id: String, profile_json: String, path: String) -> Result<()> {
+  config::save_profile(&path, &stable_id, &profile_json)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Load the profile saved under `stable_id` as JSON text
+/// Returns null if the file doesn't exist or has no profile for this id.
+#[napi]
+pub fn load_camera_config(stable_id: String, path: String) -> Result<Option<String>> {
+  config::load_profile(&path, &stable_id).map_err(|e| Error::from_reason(e.to_string()))
+}
+
 // ============================================================================
 // Utility Functions - System Information
 // ============================================================================
@@ -375,6 +1935,15 @@ pub fn nokhwa_check() -> bool {
   nokhwa::nokhwa_check()
 }
 
+/// Ask the OS for camera access and resolve with whether it was granted
+/// On macOS this shows the permission prompt the first time and must be awaited
+/// before `listCameras()`, `query()` or opening a camera, which otherwise find
+/// no devices. Other platforms need no initialization and resolve with `true`.
+#[napi(ts_return_type = "Promise<boolean>")]
+pub fn nokhwa_initialize_async() -> AsyncTask<InitializeTask> {
+  AsyncTask::new(InitializeTask)
+}
+
 /// Get native API backend
 #[napi]
 pub fn native_api_backend() -> Option<ApiBackend> {
@@ -423,9 +1992,12 @@ pub fn buf_bgr_to_rgb(width: u32, height: u32, bgr: Buffer) -> Result<Buffer> {
 
 /// Convert MJPEG buffer to RGB
 #[napi]
-pub fn buf_mjpeg_to_rgb(width: u32, height: u32, mjpeg: Buffer) -> Result<Buffer> {
+pub fn buf_mjpeg_to_rgb(width: u32, height: u32, mjpeg: Buffer) -> Result<Buffer, ErrorCode> {
   if mjpeg.is_empty() {
-    return Err(Error::from_reason("Empty MJPEG buffer".to_string()));
+    return Err(coded_error(
+      ErrorCode::DecodeFailed,
+      "Empty MJPEG buffer".to_string(),
+    ));
   }
 
   let mut dest = vec![0u8; width as usize * height as usize * 3];
@@ -441,19 +2013,37 @@ pub fn buf_mjpeg_to_rgb(width: u32, height: u32, mjpeg: Buffer) -> Result<Buffer
 
   match result {
     Ok(Ok(_)) => Ok(Buffer::from(dest)),
-    Ok(Err(e)) => Err(Error::from_reason(format!(
-      "Failed to convert MJPEG: {}",
-      e
-    ))),
-    Err(_) => Err(Error::from_reason(
+    Ok(Err(e)) => Err(coded_error(
+      ErrorCode::DecodeFailed,
+      format!("Failed to convert MJPEG: {}", e),
+    )),
+    Err(_) => Err(coded_error(
+      ErrorCode::DecodeFailed,
       "MJPEG conversion panicked internally (likely due to invalid JPEG data)".to_string(),
     )),
   }
 }
 
 /// Convert NV12 buffer to RGB
+/// `color_space` selects the YUV matrix (default BT.601)
 #[napi]
-pub fn buf_nv12_to_rgb(width: u32, height: u32, nv12: Buffer) -> Result<Buffer> {
+pub fn buf_nv12_to_rgb(
+  width: u32,
+  height: u32,
+  nv12: Buffer,
+  color_space: Option<ColorSpace>,
+) -> Result<Buffer, ErrorCode> {
+  if let Some(color_space @ ColorSpace::Bt709) = color_space {
+    return yuv::nv12_to_rgb(&nv12, width, height, color_space, false)
+      .map(Buffer::from)
+      .map_err(|e| {
+        coded_error(
+          ErrorCode::DecodeFailed,
+          format!("Failed to convert NV12: {}", e),
+        )
+      });
+  }
+
   let resolution = nokhwa::utils::Resolution::new(width, height);
   let mut dest = vec![0u8; width as usize * height as usize * 3];
 
@@ -466,16 +2056,37 @@ pub fn buf_nv12_to_rgb(width: u32, height: u32, nv12: Buffer) -> Result<Buffer>
 
   match result {
     Ok(Ok(_)) => Ok(Buffer::from(dest)),
-    Ok(Err(e)) => Err(Error::from_reason(format!("Failed to convert NV12: {}", e))),
-    Err(_) => Err(Error::from_reason(
+    Ok(Err(e)) => Err(coded_error(
+      ErrorCode::DecodeFailed,
+      format!("Failed to convert NV12: {}", e),
+    )),
+    Err(_) => Err(coded_error(
+      ErrorCode::DecodeFailed,
       "NV12 conversion panicked internally".to_string(),
     )),
   }
 }
 
 /// Convert YUYV422 buffer to RGB
+/// `color_space` selects the YUV matrix (default BT.601)
 #[napi]
-pub fn buf_yuyv422_to_rgb(width: u32, height: u32, yuyv: Buffer) -> Result<Buffer> {
+pub fn buf_yuyv422_to_rgb(
+  width: u32,
+  height: u32,
+  yuyv: Buffer,
+  color_space: Option<ColorSpace>,
+) -> Result<Buffer, ErrorCode> {
+  if let Some(color_space @ ColorSpace::Bt709) = color_space {
+    return yuv::yuyv_to_rgb(&yuyv, color_space, false)
+      .map(Buffer::from)
+      .map_err(|e| {
+        coded_error(
+          ErrorCode::DecodeFailed,
+          format!("Failed to convert YUYV: {}", e),
+        )
+      });
+  }
+
   let mut dest = vec![0u8; width as usize * height as usize * 3];
 
   let yuyv_ref = &yuyv;
@@ -487,18 +2098,55 @@ pub fn buf_yuyv422_to_rgb(width: u32, height: u32, yuyv: Buffer) -> Result<Buffe
 
   match result {
     Ok(Ok(_)) => Ok(Buffer::from(dest)),
-    Ok(Err(e)) => Err(Error::from_reason(format!("Failed to convert YUYV: {}", e))),
-    Err(_) => Err(Error::from_reason(
+    Ok(Err(e)) => Err(coded_error(
+      ErrorCode::DecodeFailed,
+      format!("Failed to convert YUYV: {}", e),
+    )),
+    Err(_) => Err(coded_error(
+      ErrorCode::DecodeFailed,
       "YUYV conversion panicked internally".to_string(),
     )),
   }
 }
 
+/// Convert a YUYV422 buffer to NV12 directly in YUV space
+/// Chroma is averaged over each pair of rows; width and height must be even.
+/// A wrong buffer size or odd dimensions throw with code `InvalidArg`.
+#[napi]
+pub fn buf_yuyv422_to_nv12(width: u32, height: u32, yuyv: Buffer) -> Result<Buffer, ErrorCode> {
+  yuv::yuyv_to_nv12(&yuyv, width, height)
+    .map(Buffer::from)
+    .map_err(|e| {
+      coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!("Failed to convert YUYV: {}", e),
+      )
+    })
+}
+
+/// Convert an NV12 buffer to YUYV422 directly in YUV space
+/// Width and height must be even.
+/// A wrong buffer size or odd dimensions throw with code `InvalidArg`.
+#[napi]
+pub fn buf_nv12_to_yuyv422(width: u32, height: u32, nv12: Buffer) -> Result<Buffer, ErrorCode> {
+  yuv::nv12_to_yuyv(&nv12, width, height)
+    .map(Buffer::from)
+    .map_err(|e| {
+      coded_error(
+        ErrorCode::Napi(Status::InvalidArg),
+        format!("Failed to convert NV12: {}", e),
+      )
+    })
+}
+
 /// Convert MJPEG to RGB (convenience function)
 #[napi]
-pub fn mjpeg_to_rgb(mjpeg: Buffer, _width: u32, _height: u32) -> Result<Buffer> {
+pub fn mjpeg_to_rgb(mjpeg: Buffer, _width: u32, _height: u32) -> Result<Buffer, ErrorCode> {
   if mjpeg.is_empty() {
-    return Err(Error::from_reason("Empty MJPEG buffer".to_string()));
+    return Err(coded_error(
+      ErrorCode::DecodeFailed,
+      "Empty MJPEG buffer".to_string(),
+    ));
   }
 
   let mjpeg_ref = &mjpeg;
@@ -508,19 +2156,37 @@ pub fn mjpeg_to_rgb(mjpeg: Buffer, _width: u32, _height: u32) -> Result<Buffer>
 
   match result {
     Ok(Ok(rgb)) => Ok(Buffer::from(rgb)),
-    Ok(Err(e)) => Err(Error::from_reason(format!(
-      "Failed to convert MJPEG: {}",
-      e
-    ))),
-    Err(_) => Err(Error::from_reason(
+    Ok(Err(e)) => Err(coded_error(
+      ErrorCode::DecodeFailed,
+      format!("Failed to convert MJPEG: {}", e),
+    )),
+    Err(_) => Err(coded_error(
+      ErrorCode::DecodeFailed,
       "MJPEG conversion panicked internally (likely due to invalid JPEG data)".to_string(),
     )),
   }
 }
 
 /// Convert NV12 to RGB (convenience function)
+/// `color_space` selects the YUV matrix (default BT.601)
 #[napi]
-pub fn nv12_to_rgb(nv12: Buffer, width: u32, height: u32) -> Result<Buffer> {
+pub fn nv12_to_rgb(
+  nv12: Buffer,
+  width: u32,
+  height: u32,
+  color_space: Option<ColorSpace>,
+) -> Result<Buffer, ErrorCode> {
+  if let Some(color_space @ ColorSpace::Bt709) = color_space {
+    return yuv::nv12_to_rgb(&nv12, width, height, color_space, false)
+      .map(Buffer::from)
+      .map_err(|e| {
+        coded_error(
+          ErrorCode::DecodeFailed,
+          format!("Failed to convert NV12: {}", e),
+        )
+      });
+  }
+
   let resolution = nokhwa::utils::Resolution::new(width, height);
 
   let nv12_ref = &nv12;
@@ -530,22 +2196,51 @@ pub fn nv12_to_rgb(nv12: Buffer, width: u32, height: u32) -> Result<Buffer> {
 
   match result {
     Ok(Ok(rgb)) => Ok(Buffer::from(rgb)),
-    Ok(Err(e)) => Err(Error::from_reason(format!("Failed to convert NV12: {}", e))),
-    Err(_) => Err(Error::from_reason(
+    Ok(Err(e)) => Err(coded_error(
+      ErrorCode::DecodeFailed,
+      format!("Failed to convert NV12: {}", e),
+    )),
+    Err(_) => Err(coded_error(
+      ErrorCode::DecodeFailed,
       "NV12 conversion panicked internally".to_string(),
     )),
   }
 }
 
-/// Get predicted size for YUYV422 format
+/// Get the size of the RGB output nokhwa predicts for a YUYV422 buffer of `data_len` bytes
+/// Pass the length of a captured buffer, which may include row padding.
 #[napi]
-pub fn yuyv422_predicted_size(width: u32, height: u32) -> u32 {
-  nokhwa::utils::yuyv422_predicted_size(width as usize * height as usize * 2, false) as u32
+pub fn yuyv422_predicted_size(data_len: u32) -> u32 {
+  nokhwa::utils::yuyv422_predicted_size(data_len as usize, false) as u32
+}
+
+/// Get the predicted RGB output size for an unpadded `width`x`height` YUYV422 frame
+/// This was `yuyv422PredictedSize(width, height)` before it took a buffer length.
+#[napi]
+pub fn yuyv422_predicted_size_for_resolution(width: u32, height: u32) -> u32 {
+  yuyv422_predicted_size(width.saturating_mul(height).saturating_mul(2))
 }
 
 /// Convert YUYV422 to RGB (convenience function)
+/// `color_space` selects the YUV matrix (default BT.601)
 #[napi]
-pub fn yuyv422_to_rgb(yuyv: Buffer, _width: u32, _height: u32) -> Result<Buffer> {
+pub fn yuyv422_to_rgb(
+  yuyv: Buffer,
+  _width: u32,
+  _height: u32,
+  color_space: Option<ColorSpace>,
+) -> Result<Buffer, ErrorCode> {
+  if let Some(color_space @ ColorSpace::Bt709) = color_space {
+    return yuv::yuyv_to_rgb(&yuyv, color_space, false)
+      .map(Buffer::from)
+      .map_err(|e| {
+        coded_error(
+          ErrorCode::DecodeFailed,
+          format!("Failed to convert YUYV: {}", e),
+        )
+      });
+  }
+
   let yuyv_ref = &yuyv;
   let result = catch_unwind(AssertUnwindSafe(move || {
     nokhwa::utils::yuyv422_to_rgb(yuyv_ref, false)
@@ -553,9 +2248,232 @@ pub fn yuyv422_to_rgb(yuyv: Buffer, _width: u32, _height: u32) -> Result<Buffer>
 
   match result {
     Ok(Ok(rgb)) => Ok(Buffer::from(rgb)),
-    Ok(Err(e)) => Err(Error::from_reason(format!("Failed to convert YUYV: {}", e))),
-    Err(_) => Err(Error::from_reason(
+    Ok(Err(e)) => Err(coded_error(
+      ErrorCode::DecodeFailed,
+      format!("Failed to convert YUYV: {}", e),
+    )),
+    Err(_) => Err(coded_error(
+      ErrorCode::DecodeFailed,
       "YUYV conversion panicked internally".to_string(),
     )),
   }
 }
+
+/// Pack an RGBA buffer as RGB by dropping the alpha byte of every pixel
+#[napi]
+pub fn rgba_to_rgb(data: Buffer) -> Result<Buffer, ErrorCode> {
+  if !data.len().is_multiple_of(4) {
+    return Err(coded_error(
+      ErrorCode::Napi(Status::InvalidArg),
+      format!(
+        "Invalid RGBA buffer: length {} is not a multiple of 4",
+        data.len()
+      ),
+    ));
+  }
+  Ok(processing::rgba_to_rgb(&data).into())
+}
+
+/// Convert an RGB buffer to single-channel grayscale with Rec.601 luma weights
+#[napi]
+pub fn rgb_to_gray(width: u32, height: u32, rgb: Buffer) -> Result<Buffer, ErrorCode> {
+  check_gray_input(&rgb, width, height, 3, "RGB")?;
+  Ok(processing::rgb_to_gray(&rgb).into())
+}
+
+/// Convert an RGBA buffer to single-channel grayscale with Rec.601 luma weights
+/// Alpha is ignored.
+#[napi]
+pub fn rgba_to_gray(width: u32, height: u32, rgba: Buffer) -> Result<Buffer, ErrorCode> {
+  check_gray_input(&rgba, width, height, 4, "RGBA")?;
+  Ok(processing::rgba_to_gray(&rgba).into())
+}
+
+fn check_gray_input(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  channels: usize,
+  name: &str,
+) -> Result<(), ErrorCode> {
+  let expected = width as usize * height as usize * channels;
+  if data.len() != expected {
+    return Err(coded_error(
+      ErrorCode::Napi(Status::InvalidArg),
+      format!(
+        "Invalid {} buffer: expected {} bytes for {}x{}, got {}",
+        name,
+        expected,
+        width,
+        height,
+        data.len()
+      ),
+    ));
+  }
+  Ok(())
+}
+
+// ============================================================================
+// Utility Functions - Frame Analysis
+// ============================================================================
+
+/// Apply a software brightness/contrast/gamma adjustment to an RGBA buffer
+/// Same parameters as `Camera.setSoftwareAdjust`; alpha is left untouched.
+#[napi]
+pub fn rgba_adjust(
+  data: Buffer,
+  width: u32,
+  height: u32,
+  brightness: f64,
+  contrast: f64,
+  gamma: f64,
+) -> Result<Frame> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  let lut = processing::adjust_lut(brightness, contrast, gamma)
+    .map_err(|e| Error::from_reason(e.to_string()))?;
+  let mut pixels = data.to_vec();
+  processing::apply_lut(&mut pixels, &lut);
+  convert_to_napi_frame(RgbaFrame {
+    data: pixels,
+    width,
+    height,
+    timestamp_ms: timing::unix_time_ms(),
+  })
+}
+
+/// Alpha-blend an RGBA overlay onto an RGBA frame at (`x`, `y`)
+/// Same compositing as `Camera.setOverlay`: clipped to the frame, weighted by
+/// the overlay's alpha times `opacity` (0 to 1).
+#[napi]
+#[allow(clippy::too_many_arguments)]
+pub fn rgba_overlay(
+  base: Buffer,
+  base_width: u32,
+  base_height: u32,
+  overlay: Buffer,
+  overlay_width: u32,
+  overlay_height: u32,
+  x: i32,
+  y: i32,
+  opacity: f64,
+) -> Result<Frame> {
+  analysis::check_rgba_len(&base, base_width, base_height)
+    .map_err(|e| Error::from_reason(e.to_string()))?;
+  let overlay = processing::Overlay::new(
+    overlay.to_vec(),
+    overlay_width,
+    overlay_height,
+    x,
+    y,
+    opacity,
+  )
+  .map_err(|e| Error::from_reason(e.to_string()))?;
+  let mut pixels = base.to_vec();
+  processing::overlay(&mut pixels, base_width, base_height, &overlay);
+  convert_to_napi_frame(RgbaFrame {
+    data: pixels,
+    width: base_width,
+    height: base_height,
+    timestamp_ms: timing::unix_time_ms(),
+  })
+}
+
+/// Deinterlace an RGBA buffer, see `DeinterlaceMode`
+#[napi]
+pub fn rgba_deinterlace(
+  data: Buffer,
+  width: u32,
+  height: u32,
+  mode: DeinterlaceMode,
+) -> Result<Frame> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  let mut pixels = data.to_vec();
+  let height = processing::deinterlace(&mut pixels, width, height, 4, mode);
+  convert_to_napi_frame(RgbaFrame {
+    data: pixels,
+    width,
+    height,
+    timestamp_ms: timing::unix_time_ms(),
+  })
+}
+
+/// Compute the average color of an RGBA buffer
+#[napi]
+pub fn rgba_average_color(data: Buffer, width: u32, height: u32) -> Result<RgbColor> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  Ok(analysis::average_color(&data))
+}
+
+/// Find the `k` dominant colors of an RGBA buffer, most common first
+#[napi]
+pub fn rgba_dominant_colors(
+  data: Buffer,
+  width: u32,
+  height: u32,
+  k: u32,
+) -> Result<Vec<ColorWeight>> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  Ok(analysis::dominant_colors(&data, k))
+}
+
+/// Compute the sharpness of an RGBA buffer as the variance of its luma Laplacian
+/// Higher values mean a sharper (better focused) image; only relative values
+/// of the same scene are meaningful.
+#[napi]
+pub fn rgba_sharpness(data: Buffer, width: u32, height: u32) -> Result<f64> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  Ok(analysis::sharpness(&data, width, height))
+}
+
+/// Compute the mean luma of each cell in a `cols`x`rows` grid over an RGBA buffer
+/// Values are returned in row-major order
+#[napi]
+pub fn rgba_zone_brightness(
+  data: Buffer,
+  width: u32,
+  height: u32,
+  cols: u32,
+  rows: u32,
+) -> Result<Vec<u8>> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  analysis::zone_brightness(&data, width, height, cols, rows)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Guess the pixel format of a raw frame from its size
+/// Returns every format whose frame size at `width`x`height` equals the buffer
+/// length, most common first (YUYV, NV12, RGB, RGBA, GRAY), or `[MJPEG]` for
+/// data starting with a JPEG marker. An empty result means nothing fits.
+#[napi]
+pub fn guess_frame_format(data: Buffer, width: u32, height: u32) -> Vec<FrameFormat> {
+  buffer::guess_frame_formats(&data, width, height)
+}
+
+/// Render an RGBA buffer as ASCII art `out_cols` characters wide
+/// Returns one line per character row, each terminated by a newline
+#[napi]
+pub fn rgba_to_ascii(data: Buffer, width: u32, height: u32, out_cols: u32) -> Result<String> {
+  analysis::check_rgba_len(&data, width, height).map_err(|e| Error::from_reason(e.to_string()))?;
+  analysis::rgba_to_ascii(&data, width, height, out_cols)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Encode an RGBA buffer as JPEG, same as `Camera::capture_jpeg` does for a captured frame
+/// `metadata` is embedded as EXIF tags; without it no EXIF is written.
+#[napi]
+pub fn rgba_to_jpeg(
+  data: Buffer,
+  width: u32,
+  height: u32,
+  quality: u32,
+  metadata: Option<JpegMetadata>,
+) -> Result<Buffer, ErrorCode> {
+  analysis::check_rgba_len(&data, width, height)
+    .map_err(|e| coded_error(ErrorCode::Napi(Status::InvalidArg), e))?;
+  let rgb = processing::rgba_to_rgb(&data);
+  let mut jpeg = encode::encode_jpeg(&rgb, width, height, quality).map_err(anyhow_to_coded)?;
+  if let Some(metadata) = metadata {
+    jpeg = encode::embed_exif(jpeg, &metadata).map_err(anyhow_to_coded)?;
+  }
+  Ok(jpeg.into())
+}
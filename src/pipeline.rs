@@ -0,0 +1,122 @@
+//! Output pipeline module for nokhwa-node
+//!
+//! This module validates and runs the chain of `FrameOp`s a camera applies to
+//! each frame in `Camera::capture_processed`.
+
+use anyhow::anyhow;
+
+use crate::conversions::RgbaFrame;
+use crate::encode;
+use crate::processing;
+use crate::types::FrameOp;
+
+/// Checks that a pipeline can run: encoders only as the last step, valid parameters
+pub fn validate_pipeline(ops: &[FrameOp]) -> anyhow::Result<()> {
+  for (i, op) in ops.iter().enumerate() {
+    let is_last = i + 1 == ops.len();
+    match op {
+      FrameOp::EncodeJpeg { .. } | FrameOp::EncodePng if !is_last => {
+        return Err(anyhow!(
+          "Invalid pipeline: step {} encodes the frame, so it must be the last step",
+          i + 1
+        ));
+      }
+      FrameOp::Resize { width, height } if *width == 0 || *height == 0 => {
+        return Err(anyhow!(
+          "Invalid pipeline: step {} resizes to {}x{}",
+          i + 1,
+          width,
+          height
+        ));
+      }
+      FrameOp::Crop { width, height, .. } if *width == 0 || *height == 0 => {
+        return Err(anyhow!(
+          "Invalid pipeline: step {} crops to {}x{}",
+          i + 1,
+          width,
+          height
+        ));
+      }
+      FrameOp::Rotate { degrees } if !matches!(degrees, 0 | 90 | 180 | 270) => {
+        return Err(anyhow!(
+          "Invalid pipeline: step {} rotates by {} degrees, use 0, 90, 180 or 270",
+          i + 1,
+          degrees
+        ));
+      }
+      _ => {}
+    }
+  }
+  Ok(())
+}
+
+/// Applies `ops` to a frame in order and returns the resulting bytes
+/// Without an encode step these are raw pixels: RGBA, or one byte per pixel
+/// after `Grayscale`.
+pub fn run_pipeline(frame: RgbaFrame, ops: &[FrameOp]) -> anyhow::Result<Vec<u8>> {
+  let (mut data, mut width, mut height) = (frame.data, frame.width, frame.height);
+  let mut channels = 4;
+
+  for op in ops {
+    match op {
+      FrameOp::Resize {
+        width: new_width,
+        height: new_height,
+      } => {
+        data = processing::resize(&data, width, height, channels, *new_width, *new_height)?;
+        (width, height) = (*new_width, *new_height);
+      }
+      FrameOp::Crop {
+        x,
+        y,
+        width: crop_width,
+        height: crop_height,
+      } => {
+        if *x as u64 + *crop_width as u64 > width as u64
+          || *y as u64 + *crop_height as u64 > height as u64
+        {
+          return Err(anyhow!(
+            "Crop {}x{} at ({}, {}) is outside the {}x{} frame",
+            crop_width,
+            crop_height,
+            x,
+            y,
+            width,
+            height
+          ));
+        }
+        data = processing::crop(&data, width, channels, *x, *y, *crop_width, *crop_height);
+        (width, height) = (*crop_width, *crop_height);
+      }
+      FrameOp::Flip {
+        horizontal,
+        vertical,
+      } => {
+        if *horizontal {
+          processing::flip_horizontal(&mut data, width, height, channels);
+        }
+        if *vertical {
+          processing::flip_vertical(&mut data, width, height, channels);
+        }
+      }
+      FrameOp::Rotate { degrees } => {
+        (data, width, height) = processing::rotate(&data, width, height, channels, *degrees)?;
+      }
+      FrameOp::Grayscale => {
+        if channels == 4 {
+          data = processing::rgba_to_gray(&data);
+          channels = 1;
+        }
+      }
+      FrameOp::EncodeJpeg { quality } => {
+        return if channels == 1 {
+          encode::encode_jpeg_gray(&data, width, height, *quality)
+        } else {
+          encode::encode_jpeg(&processing::rgba_to_rgb(&data), width, height, *quality)
+        };
+      }
+      FrameOp::EncodePng => return encode::encode_png(&data, width, height, channels),
+    }
+  }
+  Ok(data)
+}
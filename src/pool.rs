@@ -0,0 +1,133 @@
+//! Frame pool module for nokhwa-node
+//!
+//! This module provides recycled RGBA frame buffers so repeated captures don't
+//! allocate a new frame each time.
+
+use std::sync::{Arc, Mutex};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Number of idle buffers a pool keeps by default
+const DEFAULT_POOL_CAPACITY: u32 = 4;
+
+struct PoolInner {
+  idle: Vec<Vec<u8>>,
+  capacity: usize,
+}
+
+type SharedPool = Arc<Mutex<PoolInner>>;
+
+/// Pool of reusable frame buffers for `Camera.captureIntoPooled()`
+#[napi]
+pub struct FramePool {
+  inner: SharedPool,
+}
+
+#[napi]
+impl FramePool {
+  /// Create a pool that keeps up to `capacity` idle buffers (default 4)
+  #[napi(constructor)]
+  pub fn new(capacity: Option<u32>) -> Self {
+    Self {
+      inner: Arc::new(Mutex::new(PoolInner {
+        idle: Vec::new(),
+        capacity: capacity.unwrap_or(DEFAULT_POOL_CAPACITY) as usize,
+      })),
+    }
+  }
+
+  /// Take an empty frame from the pool, reusing an idle buffer when one is available
+  #[napi]
+  pub fn acquire(&self) -> PooledFrame {
+    let data = self
+      .inner
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .idle
+      .pop()
+      .unwrap_or_default();
+    PooledFrame {
+      data: Arc::new(data),
+      width: 0,
+      height: 0,
+      pool: Some(self.inner.clone()),
+    }
+  }
+
+  /// Number of idle buffers currently held by the pool
+  #[napi]
+  pub fn idle_count(&self) -> u32 {
+    self
+      .inner
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .idle
+      .len() as u32
+  }
+}
+
+/// RGBA frame whose buffer goes back to its pool on `release()` or garbage collection
+#[napi]
+pub struct PooledFrame {
+  pub(crate) data: Arc<Vec<u8>>,
+  pub(crate) width: u32,
+  pub(crate) height: u32,
+  pool: Option<SharedPool>,
+}
+
+#[napi]
+impl PooledFrame {
+  /// Get a `Uint8Array` viewing the RGBA data in place, without copying it
+  /// The view holds on to the bytes, so it stays valid after `release()`; a
+  /// buffer that is still viewed then isn't put back into the pool. The view is
+  /// read-only by contract. Fails once the frame has been released.
+  #[napi]
+  pub fn data(&self) -> Result<Uint8Array> {
+    if self.pool.is_none() {
+      return Err(Error::from_reason("Frame has been released to its pool"));
+    }
+    if self.data.is_empty() {
+      return Ok(Uint8Array::new(Vec::new()));
+    }
+    let data = Arc::clone(&self.data);
+    let (ptr, len) = (data.as_ptr().cast_mut(), data.len());
+    // SAFETY: the closure owns a reference to the bytes until the view is finalized
+    Ok(unsafe { Uint8Array::with_external_data(ptr, len, move |_, _| drop(data)) })
+  }
+
+  /// Get the frame width
+  #[napi]
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  /// Get the frame height
+  #[napi]
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+
+  /// Return the buffer to the pool; the frame is unusable afterwards
+  #[napi]
+  pub fn release(&mut self) {
+    if let Some(pool) = self.pool.take() {
+      // Bytes still viewed from JS stay with their views instead of being reused
+      if let Ok(data) = Arc::try_unwrap(std::mem::take(&mut self.data)) {
+        let mut pool = pool.lock().unwrap_or_else(|e| e.into_inner());
+        if pool.idle.len() < pool.capacity {
+          pool.idle.push(data);
+        }
+      }
+    }
+    self.data = Arc::default();
+    self.width = 0;
+    self.height = 0;
+  }
+}
+
+impl Drop for PooledFrame {
+  fn drop(&mut self) {
+    self.release();
+  }
+}
@@ -0,0 +1,370 @@
+//! Pixel processing module for nokhwa-node
+//!
+//! This module provides the pixel transforms applied to decoded frames before
+//! they are handed to JavaScript. Interleaved images take a `channels` count,
+//! so the same transforms work on RGBA and single-channel gray frames.
+
+use anyhow::anyhow;
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Luma, Rgba};
+
+use crate::analysis::luma;
+use crate::types::{DeinterlaceMode, FrameTransform};
+
+/// Flips an interleaved image upside down in place
+/// `channels` is the number of bytes per pixel (e.g. 4 for RGBA).
+pub fn flip_vertical(data: &mut [u8], width: u32, height: u32, channels: usize) {
+  let stride = width as usize * channels;
+  let height = height as usize;
+  if stride == 0 || data.len() < stride * height {
+    return;
+  }
+
+  let (mut top, mut bottom) = (0, height);
+  while top + 1 < bottom {
+    bottom -= 1;
+    let (upper, lower) = data.split_at_mut(bottom * stride);
+    upper[top * stride..(top + 1) * stride].swap_with_slice(&mut lower[..stride]);
+    top += 1;
+  }
+}
+
+/// Drops the alpha byte of every RGBA pixel
+pub fn rgba_to_rgb(rgba: &[u8]) -> Vec<u8> {
+  let mut rgb = Vec::with_capacity(rgba.len() / 4 * 3);
+  for px in rgba.chunks_exact(4) {
+    rgb.extend_from_slice(&px[..3]);
+  }
+  rgb
+}
+
+/// Copies the `w`x`h` region at (`x`, `y`) out of an interleaved image
+/// The caller validates that the region lies inside the image.
+pub fn crop(data: &[u8], width: u32, channels: usize, x: u32, y: u32, w: u32, h: u32) -> Vec<u8> {
+  let stride = width as usize * channels;
+  let row_len = w as usize * channels;
+  let mut out = Vec::with_capacity(row_len * h as usize);
+  for row in y as usize..(y + h) as usize {
+    let start = row * stride + x as usize * channels;
+    out.extend_from_slice(&data[start..start + row_len]);
+  }
+  out
+}
+
+/// Mirrors an interleaved image left to right in place
+pub fn flip_horizontal(data: &mut [u8], width: u32, height: u32, channels: usize) {
+  let stride = width as usize * channels;
+  if stride == 0 || data.len() < stride * height as usize {
+    return;
+  }
+  for line in data.chunks_exact_mut(stride).take(height as usize) {
+    let (mut left, mut right) = (0, width as usize);
+    while left + 1 < right {
+      right -= 1;
+      let (head, tail) = line.split_at_mut(right * channels);
+      head[left * channels..(left + 1) * channels].swap_with_slice(&mut tail[..channels]);
+      left += 1;
+    }
+  }
+}
+
+/// Rotates an interleaved image clockwise by 0, 90, 180 or 270 degrees
+/// Returns the rotated pixels with their new width and height.
+pub fn rotate(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  channels: usize,
+  degrees: u32,
+) -> anyhow::Result<(Vec<u8>, u32, u32)> {
+  let (w, h) = (width as usize, height as usize);
+  if data.len() != w * h * channels {
+    return Err(anyhow!(
+      "Cannot rotate: expected {} bytes for {}x{}, got {}",
+      w * h * channels,
+      width,
+      height,
+      data.len()
+    ));
+  }
+
+  let (out_w, out_h) = match degrees {
+    0 | 180 => (w, h),
+    90 | 270 => (h, w),
+    _ => {
+      return Err(anyhow!(
+        "Invalid rotation {}: must be 0, 90, 180 or 270",
+        degrees
+      ))
+    }
+  };
+  if degrees == 0 {
+    return Ok((data.to_vec(), width, height));
+  }
+
+  let mut out = vec![0u8; data.len()];
+  for y in 0..h {
+    for x in 0..w {
+      let (dx, dy) = match degrees {
+        90 => (h - 1 - y, x),
+        180 => (w - 1 - x, h - 1 - y),
+        _ => (y, w - 1 - x),
+      };
+      let src = (y * w + x) * channels;
+      let dst = (dy * out_w + dx) * channels;
+      out[dst..dst + channels].copy_from_slice(&data[src..src + channels]);
+    }
+  }
+  Ok((out, out_w as u32, out_h as u32))
+}
+
+/// Applies `transform` to an interleaved image, returning its new width and height
+/// Flips and 180 degree turns work in place; 90 and 270 degree turns swap the
+/// dimensions and need a second buffer, which then replaces `data`.
+pub fn transform(
+  data: &mut Vec<u8>,
+  width: u32,
+  height: u32,
+  channels: usize,
+  transform: FrameTransform,
+) -> (u32, u32) {
+  // A half turn is the same as mirroring both ways
+  let half_turn = transform.rotate == 180;
+  if transform.flip_horizontal != half_turn {
+    flip_horizontal(data, width, height, channels);
+  }
+  if transform.flip_vertical != half_turn {
+    flip_vertical(data, width, height, channels);
+  }
+  if !matches!(transform.rotate, 90 | 270) {
+    return (width, height);
+  }
+  match rotate(data, width, height, channels, transform.rotate) {
+    Ok((rotated, width, height)) => {
+      *data = rotated;
+      (width, height)
+    }
+    // Only a buffer that doesn't match its dimensions fails; leave it as it is
+    Err(_) => (width, height),
+  }
+}
+
+/// Removes interlacing combs from an interleaved image in place
+/// The top field (even rows) is kept. Bob repeats each of its rows over the
+/// odd row below, Blend averages every row with the next one, and DiscardField
+/// drops the odd rows. Returns the new height, which only DiscardField changes.
+pub fn deinterlace(
+  data: &mut Vec<u8>,
+  width: u32,
+  height: u32,
+  channels: usize,
+  mode: DeinterlaceMode,
+) -> u32 {
+  let stride = width as usize * channels;
+  let rows = height as usize;
+  if stride == 0 || data.len() < stride * rows {
+    return height;
+  }
+
+  match mode {
+    DeinterlaceMode::Bob => {
+      for row in (1..rows).step_by(2) {
+        data.copy_within((row - 1) * stride..row * stride, row * stride);
+      }
+      height
+    }
+    DeinterlaceMode::Blend => {
+      // Row `row + 1` is still untouched when `row` is blended, so this works top-down
+      for row in 0..rows.saturating_sub(1) {
+        let (upper, lower) = data.split_at_mut((row + 1) * stride);
+        for (a, b) in upper[row * stride..].iter_mut().zip(&lower[..stride]) {
+          *a = (*a as u16 + *b as u16).div_ceil(2) as u8;
+        }
+      }
+      height
+    }
+    DeinterlaceMode::DiscardField => {
+      let kept = rows.div_ceil(2);
+      for row in 1..kept {
+        data.copy_within(2 * row * stride..(2 * row + 1) * stride, row * stride);
+      }
+      data.truncate(kept * stride);
+      kept as u32
+    }
+  }
+}
+
+/// Builds the lookup table for a software brightness/contrast/gamma adjustment
+/// Per channel value `v` in 0-1 this computes `((v - 0.5) * contrast + 0.5 + brightness)`
+/// clamped to 0-1, raised to `1 / gamma`. Neutral values are brightness 0,
+/// contrast 1 and gamma 1; gamma above 1 brightens the mid tones.
+pub fn adjust_lut(brightness: f64, contrast: f64, gamma: f64) -> anyhow::Result<[u8; 256]> {
+  if !brightness.is_finite() || !(-1.0..=1.0).contains(&brightness) {
+    return Err(anyhow!(
+      "Invalid brightness {}: must be between -1 and 1",
+      brightness
+    ));
+  }
+  if !contrast.is_finite() || contrast < 0.0 {
+    return Err(anyhow!(
+      "Invalid contrast {}: must be 0 or greater",
+      contrast
+    ));
+  }
+  if !gamma.is_finite() || gamma <= 0.0 {
+    return Err(anyhow!("Invalid gamma {}: must be greater than 0", gamma));
+  }
+
+  let mut lut = [0u8; 256];
+  for (i, entry) in lut.iter_mut().enumerate() {
+    let v = ((i as f64 / 255.0 - 0.5) * contrast + 0.5 + brightness).clamp(0.0, 1.0);
+    *entry = (v.powf(1.0 / gamma) * 255.0).round() as u8;
+  }
+  Ok(lut)
+}
+
+/// Maps the R, G and B bytes of every RGBA pixel through `lut`, leaving alpha as is
+pub fn apply_lut(rgba: &mut [u8], lut: &[u8; 256]) {
+  for px in rgba.chunks_exact_mut(4) {
+    px[0] = lut[px[0] as usize];
+    px[1] = lut[px[1] as usize];
+    px[2] = lut[px[2] as usize];
+  }
+}
+
+/// RGBA image composited onto frames, see `overlay`
+pub struct Overlay {
+  pub data: Vec<u8>,
+  pub width: u32,
+  pub height: u32,
+  /// Position of the overlay's top-left pixel; may be negative or past the frame
+  pub x: i32,
+  pub y: i32,
+  /// Global opacity, 0 (invisible) to 1 (as opaque as the overlay's own alpha)
+  pub opacity: f64,
+}
+
+impl Overlay {
+  pub fn new(
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    opacity: f64,
+  ) -> anyhow::Result<Self> {
+    crate::analysis::check_rgba_len(&data, width, height)?;
+    if !opacity.is_finite() || !(0.0..=1.0).contains(&opacity) {
+      return Err(anyhow!(
+        "Invalid opacity {}: must be between 0 and 1",
+        opacity
+      ));
+    }
+    Ok(Self {
+      data,
+      width,
+      height,
+      x,
+      y,
+      opacity,
+    })
+  }
+}
+
+/// Alpha-blends `overlay` onto an RGBA frame in place ("source over")
+/// The overlay is clipped to the frame; each pixel's weight is its own alpha
+/// times the global opacity, and the frame's alpha grows accordingly.
+pub fn overlay(rgba: &mut [u8], width: u32, height: u32, overlay: &Overlay) {
+  let opacity = (overlay.opacity * 255.0).round() as u32;
+  if opacity == 0 {
+    return;
+  }
+  // Visible part of the overlay in overlay coordinates
+  let left = (-(overlay.x as i64)).clamp(0, overlay.width as i64) as usize;
+  let top = (-(overlay.y as i64)).clamp(0, overlay.height as i64) as usize;
+  let right = (width as i64 - overlay.x as i64).clamp(0, overlay.width as i64) as usize;
+  let bottom = (height as i64 - overlay.y as i64).clamp(0, overlay.height as i64) as usize;
+  if left >= right || top >= bottom {
+    return;
+  }
+
+  let span = (right - left) * 4;
+  for oy in top..bottom {
+    let fy = (overlay.y as i64 + oy as i64) as usize;
+    let fx = (overlay.x as i64 + left as i64) as usize;
+    let src_start = (oy * overlay.width as usize + left) * 4;
+    let dst_start = (fy * width as usize + fx) * 4;
+    let src = &overlay.data[src_start..src_start + span];
+    let dst = &mut rgba[dst_start..dst_start + span];
+    for (d, s) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+      let a = (s[3] as u32 * opacity + 127) / 255;
+      if a == 0 {
+        continue;
+      }
+      let inv = 255 - a;
+      for (dc, sc) in d[..3].iter_mut().zip(&s[..3]) {
+        *dc = ((*sc as u32 * a + *dc as u32 * inv + 127) / 255) as u8;
+      }
+      d[3] = (a + (d[3] as u32 * inv + 127) / 255) as u8;
+    }
+  }
+}
+
+/// Converts RGBA to single-channel luma with Rec.601 weights
+pub fn rgba_to_gray(rgba: &[u8]) -> Vec<u8> {
+  rgba
+    .chunks_exact(4)
+    .map(|px| luma(px[0], px[1], px[2]))
+    .collect()
+}
+
+/// Converts RGB to single-channel luma with Rec.601 weights
+pub fn rgb_to_gray(rgb: &[u8]) -> Vec<u8> {
+  rgb
+    .chunks_exact(3)
+    .map(|px| luma(px[0], px[1], px[2]))
+    .collect()
+}
+
+/// Resizes an RGBA (4 channels) or gray (1 channel) image with a triangle (bilinear) filter
+pub fn resize(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  channels: usize,
+  new_width: u32,
+  new_height: u32,
+) -> anyhow::Result<Vec<u8>> {
+  if new_width == 0 || new_height == 0 {
+    return Err(anyhow!(
+      "Invalid target size {}x{}: both dimensions must be non-zero",
+      new_width,
+      new_height
+    ));
+  }
+  let invalid = || anyhow!("Cannot resize: buffer doesn't match {}x{}", width, height);
+  match channels {
+    4 => {
+      let image = ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, data).ok_or_else(invalid)?;
+      Ok(imageops::resize(&image, new_width, new_height, FilterType::Triangle).into_raw())
+    }
+    1 => {
+      let image = ImageBuffer::<Luma<u8>, _>::from_raw(width, height, data).ok_or_else(invalid)?;
+      Ok(imageops::resize(&image, new_width, new_height, FilterType::Triangle).into_raw())
+    }
+    _ => Err(anyhow!("Cannot resize images with {} channels", channels)),
+  }
+}
+
+/// Largest size with the aspect ratio of `width`x`height` that fits in `max_width`x`max_height`
+/// Neither dimension drops below 1 pixel.
+pub fn fit_within(width: u32, height: u32, max_width: u32, max_height: u32) -> (u32, u32) {
+  // Compare max_width / width with max_height / height without floating point
+  if max_width as u64 * height as u64 <= max_height as u64 * width as u64 {
+    let fitted = (height as u64 * max_width as u64 / width.max(1) as u64).max(1);
+    (max_width, fitted as u32)
+  } else {
+    let fitted = (width as u64 * max_height as u64 / height.max(1) as u64).max(1);
+    (fitted as u32, max_height)
+  }
+}
@@ -0,0 +1,192 @@
+//! Recording module for nokhwa-node
+//!
+//! This module writes captured frames straight to disk from a native thread,
+//! so persisting footage doesn't require shipping every frame through JavaScript.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use napi_derive::napi;
+
+use crate::conversions::capture_frame;
+use crate::shared::SharedCamera;
+use crate::timing::sleep_until;
+use crate::types::ColorSpace;
+
+// ============================================================================
+// Recording Handle
+// ============================================================================
+
+/// Handle to a running recording
+/// Dropping the handle stops the recording as well
+#[napi]
+pub struct RecordingHandle {
+  running: Arc<AtomicBool>,
+  frames_written: Arc<AtomicU32>,
+  error: Arc<Mutex<Option<String>>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+#[napi]
+impl RecordingHandle {
+  /// Stop recording early and wait for the file to be flushed
+  #[napi]
+  pub fn stop(&mut self) {
+    self.running.store(false, Ordering::SeqCst);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+
+  /// Check if the recording is still in progress
+  #[napi]
+  pub fn is_running(&self) -> bool {
+    self.running.load(Ordering::SeqCst)
+  }
+
+  /// Number of frames written so far
+  #[napi]
+  pub fn frames_written(&self) -> u32 {
+    self.frames_written.load(Ordering::SeqCst)
+  }
+
+  /// Get the error that ended the recording, if any
+  #[napi]
+  pub fn error(&self) -> Option<String> {
+    self.error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+  }
+}
+
+impl Drop for RecordingHandle {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+// ============================================================================
+// Y4M Recording
+// ============================================================================
+
+/// Spawns a thread that records `duration` of frames at `fps` into a YUV4MPEG2 file
+/// The file is created before returning so path errors surface immediately.
+pub fn spawn_y4m_recording(
+  camera: SharedCamera,
+  path: &str,
+  duration: Duration,
+  fps: u32,
+  color_space: ColorSpace,
+) -> anyhow::Result<RecordingHandle> {
+  let file = File::create(path).map_err(|e| anyhow!("Creating {}: {}", path, e))?;
+
+  let running = Arc::new(AtomicBool::new(true));
+  let frames_written = Arc::new(AtomicU32::new(0));
+  let error = Arc::new(Mutex::new(None));
+  let (thread_running, thread_frames, thread_error) =
+    (running.clone(), frames_written.clone(), error.clone());
+
+  let thread = thread::spawn(move || {
+    let mut writer = Y4mWriter::new(BufWriter::new(file), fps);
+    let total_frames = (duration.as_secs_f64() * fps as f64).round() as u32;
+    let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+    let started = Instant::now();
+
+    let result = (|| -> anyhow::Result<()> {
+      for n in 0..total_frames {
+        if !thread_running.load(Ordering::SeqCst) {
+          break;
+        }
+        sleep_until(started + frame_interval * n);
+
+        let frame = {
+          let mut cam = camera.lock()?;
+          capture_frame(&mut cam, color_space)?
+        };
+        writer.write_frame(&frame.data, frame.width, frame.height)?;
+        thread_frames.fetch_add(1, Ordering::SeqCst);
+      }
+      writer.finish()
+    })();
+
+    if let Err(e) = result {
+      *thread_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(e.to_string());
+    }
+    thread_running.store(false, Ordering::SeqCst);
+  });
+
+  Ok(RecordingHandle {
+    running,
+    frames_written,
+    error,
+    thread: Some(thread),
+  })
+}
+
+/// Minimal YUV4MPEG2 writer producing 4:4:4 planar frames
+/// The stream header is written with the first frame, whose size every later
+/// frame must match.
+pub struct Y4mWriter<W: Write> {
+  out: W,
+  fps: u32,
+  size: Option<(u32, u32)>,
+  planes: Vec<u8>,
+}
+
+impl<W: Write> Y4mWriter<W> {
+  pub fn new(out: W, fps: u32) -> Self {
+    Self {
+      out,
+      fps,
+      size: None,
+      planes: Vec::new(),
+    }
+  }
+
+  pub fn write_frame(&mut self, rgba: &[u8], width: u32, height: u32) -> anyhow::Result<()> {
+    match self.size {
+      None => {
+        writeln!(
+          self.out,
+          "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444",
+          width, height, self.fps
+        )?;
+        self.size = Some((width, height));
+      }
+      Some(size) if size != (width, height) => {
+        return Err(anyhow!(
+          "Frame size changed from {}x{} to {}x{} during recording",
+          size.0,
+          size.1,
+          width,
+          height
+        ))
+      }
+      Some(_) => {}
+    }
+
+    // Y, U and V planes, BT.601 limited range as expected by most players
+    let pixels = width as usize * height as usize;
+    self.planes.resize(pixels * 3, 0);
+    let (y_plane, chroma) = self.planes.split_at_mut(pixels);
+    let (u_plane, v_plane) = chroma.split_at_mut(pixels);
+    for (i, px) in rgba.chunks_exact(4).take(pixels).enumerate() {
+      let (r, g, b) = (px[0] as i32, px[1] as i32, px[2] as i32);
+      y_plane[i] = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+      u_plane[i] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+      v_plane[i] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+    }
+
+    self.out.write_all(b"FRAME\n")?;
+    self.out.write_all(&self.planes)?;
+    Ok(())
+  }
+
+  pub fn finish(mut self) -> anyhow::Result<()> {
+    self.out.flush()?;
+    Ok(())
+  }
+}
@@ -0,0 +1,164 @@
+//! Shared camera handle for nokhwa-node
+//!
+//! This module provides the mutex-guarded wrapper that serializes access to the
+//! underlying nokhwa camera across JS calls and native worker threads.
+
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use napi::bindgen_prelude::*;
+use nokhwa::utils::{ApiBackend, CameraIndex};
+
+use crate::error::{coded_error, coded_failure, ErrorCode};
+
+/// How long a call waits for the camera before giving up with a "busy" error
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Poll interval while waiting for the camera lock
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+// Use ManuallyDrop to control when the camera is actually dropped
+// This prevents double-free and ensures proper cleanup during GC
+type CameraSlot = Option<ManuallyDrop<nokhwa::Camera>>;
+
+/// Thread-safe handle to a nokhwa camera
+///
+/// Concurrency model: every operation on the device goes through a single mutex,
+/// so overlapping calls (sync methods, async tasks, background threads) never
+/// touch the `nokhwa::Camera` at the same time. A call waits up to two seconds
+/// for the current holder to finish and then fails with `DeviceBusy`; on the JS
+/// thread that wait blocks the event loop, so it is kept short.
+#[derive(Clone)]
+pub struct SharedCamera {
+  inner: Arc<Mutex<CameraSlot>>,
+}
+
+/// Exclusive access to the camera for as long as the guard is alive
+pub struct CameraGuard<'a> {
+  guard: MutexGuard<'a, CameraSlot>,
+}
+
+impl SharedCamera {
+  /// Wrap an opened nokhwa camera
+  pub fn new(camera: nokhwa::Camera) -> Self {
+    Self {
+      inner: Arc::new(Mutex::new(Some(ManuallyDrop::new(camera)))),
+    }
+  }
+
+  /// Like `lock_coded`, for the `anyhow`-based internals
+  pub fn lock(&self) -> anyhow::Result<CameraGuard<'_>> {
+    self
+      .lock_coded()
+      .map_err(|e| coded_failure(e.status, &e.reason))
+  }
+
+  /// Wait for exclusive access to the camera
+  /// Fails with code `CameraClosed` once the camera was closed.
+  pub fn lock_coded(&self) -> Result<CameraGuard<'_>, ErrorCode> {
+    let deadline = Instant::now() + LOCK_TIMEOUT;
+    loop {
+      match self.inner.try_lock() {
+        Ok(guard) => return CameraGuard::new(guard),
+        // A panic while holding the lock is already caught and reported to the
+        // caller that caused it; the camera itself is still usable.
+        Err(TryLockError::Poisoned(poisoned)) => return CameraGuard::new(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+          thread::sleep(LOCK_POLL_INTERVAL)
+        }
+        Err(TryLockError::WouldBlock) => {
+          return Err(coded_error(
+            ErrorCode::DeviceBusy,
+            "Camera is busy: another operation is still using the device",
+          ))
+        }
+      }
+    }
+  }
+
+  /// Check if the camera has been released
+  pub fn is_closed(&self) -> bool {
+    self
+      .inner
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .is_none()
+  }
+
+  /// Stop the stream and release the camera
+  pub fn release(&self) {
+    let mut slot = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+    // First try to stop the stream if it exists
+    // This is critical on Windows with MediaFoundation backend
+    if let Some(cam) = slot.as_mut() {
+      let _ = catch_unwind(AssertUnwindSafe(|| cam.stop_stream()));
+    }
+    // Then drop the camera
+    let _ = slot.take();
+  }
+
+  /// Replace the camera with a newly opened one for the same index and backend
+  /// The old stream is stopped first so the device can be opened again; the old
+  /// camera is only dropped once `open` succeeded, so a failed attempt (e.g. the
+  /// device isn't plugged back in yet) can simply be retried.
+  pub fn reopen(
+    &self,
+    open: impl FnOnce(CameraIndex, ApiBackend) -> Result<nokhwa::Camera, ErrorCode>,
+  ) -> Result<(), ErrorCode> {
+    let mut camera = self.lock_coded()?;
+    let (index, backend) = (camera.index().clone(), camera.backend());
+    let _ = catch_unwind(AssertUnwindSafe(|| camera.stop_stream()));
+    let opened = open(index, backend)?;
+
+    if let Some(old) = camera.guard.replace(ManuallyDrop::new(opened)) {
+      let old = ManuallyDrop::into_inner(old);
+      let _ = catch_unwind(AssertUnwindSafe(move || drop(old)));
+    }
+    Ok(())
+  }
+
+  /// Stop the stream and drop the camera, freeing the device immediately
+  /// `release` runs during garbage collection and deliberately never drops the
+  /// camera; this is called explicitly from JS, where dropping is safe.
+  pub fn close(&self) {
+    let mut slot = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cam) = slot.take() {
+      let mut cam = ManuallyDrop::into_inner(cam);
+      let _ = catch_unwind(AssertUnwindSafe(move || {
+        let _ = cam.stop_stream();
+        drop(cam);
+      }));
+    }
+  }
+}
+
+impl<'a> CameraGuard<'a> {
+  fn new(guard: MutexGuard<'a, CameraSlot>) -> Result<Self, ErrorCode> {
+    if guard.is_none() {
+      return Err(coded_error(
+        ErrorCode::CameraClosed,
+        "Camera is closed or has been stopped",
+      ));
+    }
+    Ok(Self { guard })
+  }
+}
+
+impl Deref for CameraGuard<'_> {
+  type Target = nokhwa::Camera;
+
+  fn deref(&self) -> &Self::Target {
+    // Checked in CameraGuard::new and the slot is only emptied under the lock
+    self.guard.as_ref().expect("camera slot checked on lock")
+  }
+}
+
+impl DerefMut for CameraGuard<'_> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.guard.as_mut().expect("camera slot checked on lock")
+  }
+}
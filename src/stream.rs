@@ -0,0 +1,1438 @@
+//! Streaming module for nokhwa-node
+//!
+//! This module runs capture loops on dedicated native threads and delivers
+//! their output to JavaScript through thread-safe functions.
+
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use nokhwa::pixel_format::RgbFormat;
+use nokhwa::utils::FrameFormat;
+
+use crate::conversions::{
+  capture_frame, convert_to_napi_frame, decode_frame_into, decode_to_rgba, next_buffer, Frame,
+  RgbaFrame, StreamFrame,
+};
+use crate::encode::encode_jpeg;
+use crate::error::{
+  anyhow_to_coded, coded_error, reject_coded, with_cause, CodedFailure, ErrorCode,
+};
+use crate::processing::{flip_vertical, rgba_to_rgb};
+use crate::record::Y4mWriter;
+use crate::shared::SharedCamera;
+use crate::timing::{unix_time_ms, FpsMeter};
+use crate::types::{
+  ColorSpace, ImageOrigin, QueuePolicy, RingFrame, StreamConfig, StreamErrorInfo, ThreadPriority,
+};
+
+// ============================================================================
+// Stream Handle
+// ============================================================================
+
+/// Consecutive failed captures after which a stream gives the device up as disconnected
+const DISCONNECT_AFTER_FAILURES: u32 = 10;
+
+/// JS callback of a stream; errors passed to it keep their `err.code`
+pub type StreamCallback<T> = ThreadsafeFunction<T, Unknown<'static>, T, ErrorCode>;
+
+/// Handle to a running background stream
+/// Dropping the handle stops the stream as well
+#[napi]
+pub struct StreamHandle {
+  running: Arc<AtomicBool>,
+  paused: Arc<AtomicBool>,
+  disconnected: Arc<AtomicBool>,
+  last_error: Arc<Mutex<Option<StreamErrorInfo>>>,
+  dropped: Arc<AtomicU32>,
+  thread: Option<JoinHandle<()>>,
+}
+
+#[napi]
+impl StreamHandle {
+  /// Stop the stream and wait for its capture thread to exit
+  #[napi]
+  pub fn stop(&mut self) {
+    self.running.store(false, Ordering::SeqCst);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+
+  /// Check if the stream is still running
+  #[napi]
+  pub fn is_running(&self) -> bool {
+    self.running.load(Ordering::SeqCst)
+  }
+
+  /// Stop capturing and delivering frames while keeping the device streaming
+  /// The capture thread sleeps without reading from or locking the camera, so
+  /// other calls can use it meanwhile. The first frames after resume() may be
+  /// ones the driver buffered before the pause.
+  #[napi]
+  pub fn pause(&self) {
+    self.paused.store(true, Ordering::SeqCst);
+  }
+
+  /// Resume delivering frames after pause()
+  #[napi]
+  pub fn resume(&self) {
+    self.paused.store(false, Ordering::SeqCst);
+  }
+
+  /// Check if frame delivery is paused
+  #[napi]
+  pub fn is_paused(&self) -> bool {
+    self.paused.load(Ordering::SeqCst)
+  }
+
+  /// Check if the stream ended because the device stopped delivering frames
+  /// The callback got a final error with code `Disconnected`; call
+  /// `camera.reconnect()` and start a new stream to recover.
+  #[napi]
+  pub fn is_disconnected(&self) -> bool {
+    self.disconnected.load(Ordering::SeqCst)
+  }
+
+  /// Get the message of the most recent capture error, if any
+  #[napi]
+  pub fn last_error(&self) -> Option<String> {
+    self.last_error_info().map(|info| info.message)
+  }
+
+  /// Get the most recent capture error with its code and timestamp, if any
+  /// The error is kept after later frames succeed, so check `timestampMs` to see how old it is.
+  #[napi]
+  pub fn last_error_info(&self) -> Option<StreamErrorInfo> {
+    self
+      .last_error
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .clone()
+  }
+
+  /// Number of frames discarded because the callback couldn't keep up
+  #[napi]
+  pub fn dropped_frames(&self) -> u32 {
+    self.dropped.load(Ordering::SeqCst)
+  }
+}
+
+impl Drop for StreamHandle {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+// ============================================================================
+// MJPEG Stream
+// ============================================================================
+
+/// Spawns a thread that captures frames and delivers them as JPEG images
+/// MJPEG sources are passed through untouched, other formats are encoded at `quality`
+pub fn spawn_mjpeg_stream(
+  camera: SharedCamera,
+  quality: u32,
+  fps: u32,
+  callback: StreamCallback<Buffer>,
+  config: StreamConfig,
+) -> StreamHandle {
+  let running = Arc::new(AtomicBool::new(true));
+  let thread_running = running.clone();
+  let paused = Arc::new(AtomicBool::new(false));
+  let thread_paused = paused.clone();
+  let disconnected = Arc::new(AtomicBool::new(false));
+  let thread_disconnected = disconnected.clone();
+  let last_error = Arc::new(Mutex::new(None));
+  let thread_last_error = last_error.clone();
+  let dropped = Arc::new(AtomicU32::new(0));
+  let mut queue = DeliveryQueue::new(&config, dropped.clone());
+  let frame_interval = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+
+  let thread = thread::spawn(move || {
+    apply_thread_config(&config);
+    let deliver_every = config.deliver_every_nth.unwrap_or(1).max(1) as u64;
+    let mut captured: u64 = 0;
+    let mut failures = FailureCount::default();
+
+    while thread_running.load(Ordering::SeqCst) {
+      if thread_paused.load(Ordering::SeqCst) {
+        wait_while_paused(&thread_paused, &thread_running);
+        continue;
+      }
+      if queue.policy == QueuePolicy::Block {
+        // Hold off capturing until the callback has made room
+        while queue.is_full() && thread_running.load(Ordering::SeqCst) {
+          if queue.flush(&callback) == Status::Closing {
+            break;
+          }
+          thread::sleep(Duration::from_millis(1));
+        }
+      }
+
+      let started = Instant::now();
+
+      let decimated = !captured.is_multiple_of(deliver_every);
+      captured += 1;
+      if decimated {
+        // Pull the frame without decoding it: frames dropped by deliver_every_nth
+        // cost no decode
+        let pulled = (|| -> anyhow::Result<_> { Ok(camera.lock()?.frame()?) })();
+        if camera.is_closed() {
+          break;
+        }
+        if let Some(error) = failures.record(&pulled) {
+          record_error(&thread_last_error, &error);
+          thread_disconnected.store(true, Ordering::SeqCst);
+          callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking);
+          break;
+        }
+        thread::sleep(frame_interval.saturating_sub(started.elapsed()));
+        continue;
+      }
+
+      let jpeg = next_jpeg(&camera, quality);
+      if let Some(error) = failures.record(&jpeg) {
+        record_error(&thread_last_error, &error);
+        thread_disconnected.store(true, Ordering::SeqCst);
+        callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking);
+        break;
+      }
+      let mut status = match jpeg {
+        Ok(jpeg) => {
+          queue.push(Buffer::from(jpeg));
+          Status::Ok
+        }
+        Err(e) => {
+          // Errors are small and rare, so they skip the queue
+          let error = anyhow_to_coded(e);
+          record_error(&thread_last_error, &error);
+          callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking)
+        }
+      };
+      if queue.flush(&callback) == Status::Closing {
+        status = Status::Closing;
+      }
+
+      // Stop once the camera has been released or the JS side is shutting down
+      if camera.is_closed() || status == Status::Closing {
+        break;
+      }
+
+      thread::sleep(frame_interval.saturating_sub(started.elapsed()));
+    }
+    thread_running.store(false, Ordering::SeqCst);
+  });
+
+  StreamHandle {
+    running,
+    paused,
+    disconnected,
+    last_error,
+    dropped,
+    thread: Some(thread),
+  }
+}
+
+// ============================================================================
+// Frame Stream
+// ============================================================================
+
+/// Spawns a thread that delivers every decoded frame to `callback` as RGBA
+/// The loop runs at the device's own rate: each iteration blocks on the next
+/// frame the driver delivers. Capture and decode errors are passed as the
+/// callback's first argument and recorded as the stream's last error. After
+/// `DISCONNECT_AFTER_FAILURES` failed captures in a row the stream ends with a
+/// final `Disconnected` error.
+pub fn spawn_frame_stream(
+  camera: SharedCamera,
+  color_space: ColorSpace,
+  origin: ImageOrigin,
+  callback: StreamCallback<StreamFrame>,
+  config: StreamConfig,
+) -> StreamHandle {
+  let running = Arc::new(AtomicBool::new(true));
+  let thread_running = running.clone();
+  let paused = Arc::new(AtomicBool::new(false));
+  let thread_paused = paused.clone();
+  let disconnected = Arc::new(AtomicBool::new(false));
+  let thread_disconnected = disconnected.clone();
+  let last_error = Arc::new(Mutex::new(None));
+  let thread_last_error = last_error.clone();
+  let dropped = Arc::new(AtomicU32::new(0));
+  let thread_dropped = dropped.clone();
+  let mut queue = DeliveryQueue::new(&config, dropped.clone());
+
+  let thread = thread::spawn(move || {
+    apply_thread_config(&config);
+    let deliver_every = config.deliver_every_nth.unwrap_or(1).max(1) as u64;
+    let mut captured: u64 = 0;
+    let mut limiter = FrameLimiter::new(&config);
+    let mut fps_meter = FpsMeter::default();
+    let mut failures = FailureCount::default();
+    let (mut total, mut failed) = (0u32, 0u32);
+
+    while thread_running.load(Ordering::SeqCst) {
+      if thread_paused.load(Ordering::SeqCst) {
+        wait_while_paused(&thread_paused, &thread_running);
+        continue;
+      }
+      if queue.policy == QueuePolicy::Block {
+        // Hold off capturing until the callback has made room
+        while queue.is_full() && thread_running.load(Ordering::SeqCst) {
+          if queue.flush(&callback) == Status::Closing {
+            break;
+          }
+          thread::sleep(Duration::from_millis(1));
+        }
+      }
+
+      let decimated = !captured.is_multiple_of(deliver_every);
+      captured += 1;
+      // Pulled even when decimated, to keep the device drained
+      let buffer = (|| -> anyhow::Result<_> {
+        let mut cam = camera.lock()?;
+        let buffer = next_buffer(&mut cam)?;
+        let resolution = cam.resolution();
+        Ok((
+          buffer,
+          resolution.width(),
+          resolution.height(),
+          cam.frame_rate(),
+        ))
+      })();
+      if buffer.is_ok() {
+        fps_meter.tick();
+      }
+      if let Some(error) = failures.record(&buffer) {
+        record_error(&thread_last_error, &error);
+        thread_disconnected.store(true, Ordering::SeqCst);
+        callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking);
+        break;
+      }
+      let throttled = !decimated && buffer.is_ok() && !limiter.admit();
+      if decimated || throttled {
+        if camera.is_closed() {
+          break;
+        }
+        if buffer.is_err() {
+          // Don't spin on a failing device while nobody sees the errors
+          thread::sleep(Duration::from_millis(10));
+        }
+        continue;
+      }
+
+      total = total.saturating_add(1);
+      let frame = buffer.and_then(|(buffer, width, height, advertised_fps)| {
+        let mut frame = decode_to_rgba(&buffer, width, height, color_space)?;
+        if origin == ImageOrigin::BottomLeft {
+          flip_vertical(&mut frame.data, frame.width, frame.height, 4);
+        }
+        Ok(StreamFrame {
+          data: Buffer::from(frame.data),
+          width: frame.width,
+          height: frame.height,
+          fps: fps_meter.fps().unwrap_or(advertised_fps as f64),
+          timestamp_ms: frame.timestamp_ms,
+          dropped: failed.saturating_add(thread_dropped.load(Ordering::SeqCst)),
+          total,
+        })
+      });
+      let mut status = match frame {
+        Ok(frame) => {
+          queue.push(frame);
+          Status::Ok
+        }
+        Err(e) => {
+          failed = failed.saturating_add(1);
+          // Errors are small and rare, so they skip the queue
+          let error = anyhow_to_coded(e);
+          record_error(&thread_last_error, &error);
+          callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking)
+        }
+      };
+      if queue.flush(&callback) == Status::Closing {
+        status = Status::Closing;
+      }
+
+      // Stop once the camera has been released or the JS side is shutting down
+      if camera.is_closed() || status == Status::Closing {
+        break;
+      }
+    }
+    thread_running.store(false, Ordering::SeqCst);
+  });
+
+  StreamHandle {
+    running,
+    paused,
+    disconnected,
+    last_error,
+    dropped,
+    thread: Some(thread),
+  }
+}
+
+// ============================================================================
+// Ring Stream
+// ============================================================================
+
+/// Memory of one ring slot, lent to JS as a view while a callback runs
+/// The capture thread only writes a slot while it isn't lent out, and every
+/// view of it is detached before it is handed back, so the thread and JS never
+/// touch the bytes at the same time.
+struct RingSlot(UnsafeCell<Vec<u8>>);
+
+// SAFETY: the slot's `lent` flag hands the bytes over between the capture
+// thread and the JS thread, see above
+unsafe impl Send for RingSlot {}
+unsafe impl Sync for RingSlot {}
+
+impl RingSlot {
+  fn new() -> Arc<Self> {
+    Arc::new(Self(UnsafeCell::new(Vec::new())))
+  }
+
+  /// Gives the capture thread the slot's buffer to decode into
+  /// # Safety
+  /// The slot must not be lent out.
+  #[allow(clippy::mut_from_ref)]
+  unsafe fn buffer(&self) -> &mut Vec<u8> {
+    unsafe { &mut *self.0.get() }
+  }
+}
+
+/// First `len` bytes of a ring slot, converted to a `Uint8Array` viewing them in place
+/// The view's `ArrayBuffer` is kept in `lent` so that `detach` can cut JS off
+/// from the memory once the callback has returned.
+pub struct SlotView {
+  slot: Arc<RingSlot>,
+  len: usize,
+  lent: Arc<Mutex<Option<ObjectRef<false>>>>,
+}
+
+impl SlotView {
+  /// Detaches the view handed to JS, if it was converted; call on the JS thread
+  fn detach(lent: &Mutex<Option<ObjectRef<false>>>, env: &Env) {
+    let Some(reference) = lent.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+      return;
+    };
+    if let Ok(buffer) = reference.get_value(env) {
+      // SAFETY: the reference was created from an `ArrayBuffer` in `to_napi_value`
+      if let Ok(buffer) = unsafe { ArrayBuffer::from_napi_value(env.raw(), buffer.raw()) } {
+        // Fails harmlessly when the runtime made a copy, which needs no detaching
+        let _ = buffer.detach();
+      }
+    }
+    let _ = reference.unref(env);
+  }
+}
+
+impl ToNapiValue for SlotView {
+  unsafe fn to_napi_value(raw_env: sys::napi_env, view: Self) -> Result<sys::napi_value> {
+    let env = Env::from_raw(raw_env);
+    if view.len == 0 {
+      return Ok(Uint8ArraySlice::from_data(&env, Vec::new())?.raw());
+    }
+    // SAFETY: the slot is lent while its view exists; the finalizer keeps the
+    // memory alive for views that are garbage collected late
+    let buffer = unsafe {
+      let ptr = view.slot.buffer().as_mut_ptr();
+      ArrayBuffer::from_external(&env, ptr, view.len, view.slot, |_, slot| drop(slot))
+    }?;
+    let reference = Object::from_raw(raw_env, buffer.raw()).create_ref::<false>()?;
+    *view.lent.lock().unwrap_or_else(|e| e.into_inner()) = Some(reference);
+    Ok(Uint8ArraySlice::from_arraybuffer(&buffer, 0, view.len)?.raw())
+  }
+}
+
+/// Spawns a thread that decodes frames into a ring of `capacity` reusable slots
+/// Each frame is handed to `callback` as a view of its slot; the slot is lent
+/// until the callback returns, when the view is detached and the slot is free
+/// for a later frame. When the
+/// next slot in the ring is still lent, the frame is dropped and counted, so
+/// the queue settings of `config` don't apply. Errors are delivered and end the
+/// stream as in `spawn_frame_stream`.
+pub fn spawn_ring_stream(
+  camera: SharedCamera,
+  color_space: ColorSpace,
+  origin: ImageOrigin,
+  capacity: usize,
+  callback: StreamCallback<RingFrame>,
+  config: StreamConfig,
+) -> StreamHandle {
+  let running = Arc::new(AtomicBool::new(true));
+  let thread_running = running.clone();
+  let paused = Arc::new(AtomicBool::new(false));
+  let thread_paused = paused.clone();
+  let disconnected = Arc::new(AtomicBool::new(false));
+  let thread_disconnected = disconnected.clone();
+  let last_error = Arc::new(Mutex::new(None));
+  let thread_last_error = last_error.clone();
+  let dropped = Arc::new(AtomicU32::new(0));
+  let thread_dropped = dropped.clone();
+
+  let thread = thread::spawn(move || {
+    apply_thread_config(&config);
+    let deliver_every = config.deliver_every_nth.unwrap_or(1).max(1) as u64;
+    let mut captured: u64 = 0;
+    let mut limiter = FrameLimiter::new(&config);
+    let mut fps_meter = FpsMeter::default();
+    let mut failures = FailureCount::default();
+    let (mut total, mut failed) = (0u32, 0u32);
+    let slots: Vec<Arc<RingSlot>> = (0..capacity).map(|_| RingSlot::new()).collect();
+    let lent: Vec<Arc<AtomicBool>> = (0..capacity)
+      .map(|_| Arc::new(AtomicBool::new(false)))
+      .collect();
+    let mut next_slot = 0;
+
+    while thread_running.load(Ordering::SeqCst) {
+      if thread_paused.load(Ordering::SeqCst) {
+        wait_while_paused(&thread_paused, &thread_running);
+        continue;
+      }
+      let decimated = !captured.is_multiple_of(deliver_every);
+      captured += 1;
+      // Pulled even when decimated or throttled, to keep the device drained
+      let buffer = (|| -> anyhow::Result<_> {
+        let mut cam = camera.lock()?;
+        let buffer = next_buffer(&mut cam)?;
+        // Stamped when dequeued, before decoding and waiting for a free slot
+        let timestamp_ms = unix_time_ms();
+        let resolution = cam.resolution();
+        Ok((
+          buffer,
+          resolution.width(),
+          resolution.height(),
+          cam.frame_rate(),
+          timestamp_ms,
+        ))
+      })();
+      if buffer.is_ok() {
+        fps_meter.tick();
+      }
+      if let Some(error) = failures.record(&buffer) {
+        record_error(&thread_last_error, &error);
+        thread_disconnected.store(true, Ordering::SeqCst);
+        callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking);
+        break;
+      }
+      let throttled = !decimated && buffer.is_ok() && !limiter.admit();
+      if decimated || throttled {
+        if camera.is_closed() {
+          break;
+        }
+        if buffer.is_err() {
+          // Don't spin on a failing device while nobody sees the errors
+          thread::sleep(Duration::from_millis(10));
+        }
+        continue;
+      }
+
+      total = total.saturating_add(1);
+      if buffer.is_ok() && lent[next_slot].load(Ordering::SeqCst) {
+        // The callback is behind: drop the frame before paying for its decode
+        thread_dropped.fetch_add(1, Ordering::SeqCst);
+        continue;
+      }
+
+      let index = next_slot;
+      let frame = buffer.and_then(|(buffer, width, height, advertised_fps, timestamp_ms)| {
+        // SAFETY: the slot isn't lent, checked above; only this thread lends slots
+        let data = unsafe { slots[index].buffer() };
+        let (width, height) = decode_frame_into(&buffer, width, height, color_space, data)?;
+        if origin == ImageOrigin::BottomLeft {
+          flip_vertical(data, width, height, 4);
+        }
+        Ok((width, height, advertised_fps, timestamp_ms, data.len()))
+      });
+
+      let status = match frame {
+        Ok((width, height, advertised_fps, timestamp_ms, len)) => {
+          next_slot = (next_slot + 1) % capacity;
+          lent[index].store(true, Ordering::SeqCst);
+          let returned = lent[index].clone();
+          let view = Arc::new(Mutex::new(None));
+          let detach = view.clone();
+          let status = callback.call_with_return_value(
+            Ok(RingFrame {
+              slot: index as u32,
+              data: SlotView {
+                slot: slots[index].clone(),
+                len,
+                lent: view,
+              },
+              width,
+              height,
+              fps: fps_meter.fps().unwrap_or(advertised_fps as f64),
+              timestamp_ms,
+              dropped: failed.saturating_add(thread_dropped.load(Ordering::SeqCst)),
+              total,
+            }),
+            ThreadsafeFunctionCallMode::NonBlocking,
+            move |_, env| {
+              SlotView::detach(&detach, &env);
+              returned.store(false, Ordering::SeqCst);
+              Ok(())
+            },
+          );
+          // The completion callback only runs for calls that were actually queued
+          if status != Status::Ok {
+            lent[index].store(false, Ordering::SeqCst);
+          }
+          status
+        }
+        Err(e) => {
+          failed = failed.saturating_add(1);
+          let error = anyhow_to_coded(e);
+          record_error(&thread_last_error, &error);
+          callback.call(Err(error), ThreadsafeFunctionCallMode::NonBlocking)
+        }
+      };
+
+      // Stop once the camera has been released or the JS side is shutting down
+      if camera.is_closed() || status == Status::Closing {
+        break;
+      }
+    }
+    thread_running.store(false, Ordering::SeqCst);
+  });
+
+  StreamHandle {
+    running,
+    paused,
+    disconnected,
+    last_error,
+    dropped,
+    thread: Some(thread),
+  }
+}
+
+/// Bounded queue between a capture thread and its JS callback
+/// At most one frame is handed to the thread-safe function at a time; the rest
+/// wait here, so a slow callback drops frames according to the policy instead
+/// of growing the unbounded N-API queue.
+struct DeliveryQueue<T> {
+  policy: QueuePolicy,
+  depth: usize,
+  frames: VecDeque<T>,
+  in_flight: Arc<AtomicBool>,
+  dropped: Arc<AtomicU32>,
+}
+
+impl<T: ToNapiValue + 'static> DeliveryQueue<T> {
+  fn new(config: &StreamConfig, dropped: Arc<AtomicU32>) -> Self {
+    Self {
+      policy: config.queue_policy.unwrap_or(QueuePolicy::DropOldest),
+      depth: config.queue_depth.unwrap_or(2).max(1) as usize,
+      frames: VecDeque::new(),
+      in_flight: Arc::new(AtomicBool::new(false)),
+      dropped,
+    }
+  }
+
+  fn is_full(&self) -> bool {
+    self.frames.len() >= self.depth
+  }
+
+  /// Queues a frame, dropping one if the queue is full
+  fn push(&mut self, frame: T) {
+    if self.is_full() {
+      self.dropped.fetch_add(1, Ordering::SeqCst);
+      match self.policy {
+        QueuePolicy::DropNewest => return,
+        // Block only gets here if the callback never caught up, drop the oldest then
+        QueuePolicy::DropOldest | QueuePolicy::Block => {
+          self.frames.pop_front();
+        }
+      }
+    }
+    self.frames.push_back(frame);
+  }
+
+  /// Hands the oldest frame to the callback if the previous one has been delivered
+  fn flush(&mut self, callback: &StreamCallback<T>) -> Status {
+    if self.in_flight.load(Ordering::SeqCst) {
+      return Status::Ok;
+    }
+    let Some(frame) = self.frames.pop_front() else {
+      return Status::Ok;
+    };
+
+    self.in_flight.store(true, Ordering::SeqCst);
+    let in_flight = self.in_flight.clone();
+    let status = callback.call_with_return_value(
+      Ok(frame),
+      ThreadsafeFunctionCallMode::NonBlocking,
+      move |_, _| {
+        in_flight.store(false, Ordering::SeqCst);
+        Ok(())
+      },
+    );
+    // The completion callback only runs for calls that were actually queued
+    if status != Status::Ok {
+      self.in_flight.store(false, Ordering::SeqCst);
+    }
+    status
+  }
+}
+
+/// How often a paused stream checks whether it was resumed or stopped
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Sleeps while a stream is paused, without touching the camera
+/// Returns as soon as the stream is resumed or stopped.
+fn wait_while_paused(paused: &AtomicBool, running: &AtomicBool) {
+  while paused.load(Ordering::SeqCst) && running.load(Ordering::SeqCst) {
+    thread::sleep(PAUSE_POLL_INTERVAL);
+  }
+}
+
+/// Counts consecutive capture failures to tell an unplugged device from a glitch
+#[derive(Default)]
+struct FailureCount(u32);
+
+impl FailureCount {
+  /// Records the outcome of one capture, returning the `Disconnected` error once
+  /// `DISCONNECT_AFTER_FAILURES` captures in a row have failed
+  /// Waiting too long for the camera lock (`DeviceBusy`) means another call holds
+  /// the device, not that it is gone, so it leaves the count unchanged.
+  fn record<T>(&mut self, capture: &anyhow::Result<T>) -> Option<Error<ErrorCode>> {
+    let Err(e) = capture else {
+      self.0 = 0;
+      return None;
+    };
+    let busy = e
+      .downcast_ref::<CodedFailure>()
+      .is_some_and(|failure| failure.code == ErrorCode::DeviceBusy);
+    if busy {
+      return None;
+    }
+    self.0 += 1;
+    (self.0 >= DISCONNECT_AFTER_FAILURES).then(|| {
+      with_cause(
+        coded_error(
+          ErrorCode::Disconnected,
+          format!(
+            "Camera disconnected: {} captures in a row failed, the last with: {}",
+            self.0, e
+          ),
+        ),
+        e,
+      )
+    })
+  }
+}
+
+/// Spaces out the frames a device-paced stream delivers, see `StreamConfig::target_fps`
+struct FrameLimiter {
+  interval: Option<Duration>,
+  next_due: Instant,
+}
+
+impl FrameLimiter {
+  fn new(config: &StreamConfig) -> Self {
+    Self {
+      interval: config
+        .target_fps
+        .filter(|&fps| fps > 0)
+        .map(|fps| Duration::from_secs_f64(1.0 / fps as f64)),
+      next_due: Instant::now(),
+    }
+  }
+
+  /// Whether a frame that just arrived should be delivered
+  /// Frames are admitted up to a quarter interval early, since device frames
+  /// arrive with jitter. The next due time advances from the previous one, not
+  /// from now, so the average rate stays at the target without catching up
+  /// after a stall.
+  fn admit(&mut self) -> bool {
+    let Some(interval) = self.interval else {
+      return true;
+    };
+    let now = Instant::now();
+    if now + interval / 4 < self.next_due {
+      return false;
+    }
+    self.next_due = (self.next_due + interval).max(now);
+    true
+  }
+}
+
+/// Stores `error` as the stream's most recent error
+fn record_error(slot: &Mutex<Option<StreamErrorInfo>>, error: &Error<ErrorCode>) {
+  let timestamp_ms = unix_time_ms();
+  *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(StreamErrorInfo {
+    code: error.status.as_ref().to_string(),
+    message: error.reason.clone(),
+    timestamp_ms,
+  });
+}
+
+/// Captures one frame and returns it as JPEG bytes
+fn next_jpeg(camera: &SharedCamera, quality: u32) -> anyhow::Result<Vec<u8>> {
+  // Only hold the device for the capture itself, encoding happens unlocked
+  let buffer = camera.lock()?.frame()?;
+
+  if buffer.source_frame_format() == FrameFormat::MJPEG {
+    return Ok(buffer.buffer().to_vec());
+  }
+
+  let resolution = buffer.resolution();
+  let rgb = buffer.decode_image::<RgbFormat>()?;
+  encode_jpeg(&rgb, resolution.width(), resolution.height(), quality)
+}
+
+// ============================================================================
+// Stream Builder
+// ============================================================================
+
+/// Collects several outputs for one shared capture thread
+/// Every frame is pulled from the device once and decoded at most once, then
+/// handed to all registered sinks. Register sinks, then call `start()`.
+#[napi]
+pub struct StreamBuilder {
+  camera: SharedCamera,
+  fps: u32,
+  color_space: ColorSpace,
+  origin: ImageOrigin,
+  config: StreamConfig,
+  frame_sinks: Vec<StreamCallback<Frame>>,
+  mjpeg_sinks: Vec<(StreamCallback<Buffer>, u32)>,
+  recorders: Vec<Y4mWriter<BufWriter<File>>>,
+}
+
+#[napi]
+impl StreamBuilder {
+  /// Deliver every frame as RGBA (with the camera's origin setting) to `callback`
+  #[napi]
+  pub fn on_frame(
+    &mut self,
+    callback: ThreadsafeFunction<Frame, Unknown<'static>, Frame, ErrorCode>,
+  ) {
+    self.frame_sinks.push(callback);
+  }
+
+  /// Append every frame to a YUV4MPEG2 (.y4m) file at the stream's frame rate
+  /// The file is created right away, so path errors throw here.
+  #[napi]
+  pub fn record_to(&mut self, path: String) -> Result<()> {
+    let file =
+      File::create(&path).map_err(|e| Error::from_reason(format!("Creating {}: {}", path, e)))?;
+    self
+      .recorders
+      .push(Y4mWriter::new(BufWriter::new(file), self.fps));
+    Ok(())
+  }
+
+  /// Deliver every frame as JPEG to `callback`, encoded at `quality` unless the source is MJPEG
+  #[napi]
+  pub fn mjpeg_to(
+    &mut self,
+    callback: ThreadsafeFunction<Buffer, Unknown<'static>, Buffer, ErrorCode>,
+    quality: u32,
+  ) {
+    self.mjpeg_sinks.push((callback, quality));
+  }
+
+  /// Start the capture thread driving all registered sinks
+  /// The sinks move to the returned handle; the builder is empty afterwards.
+  #[napi]
+  pub fn start(&mut self) -> Result<StreamHandle> {
+    if self.frame_sinks.is_empty() && self.mjpeg_sinks.is_empty() && self.recorders.is_empty() {
+      return Err(Error::from_reason(
+        "No sinks registered: add onFrame, recordTo or mjpegTo first",
+      ));
+    }
+    let sinks = Sinks {
+      frames: std::mem::take(&mut self.frame_sinks),
+      mjpeg: std::mem::take(&mut self.mjpeg_sinks),
+      recorders: std::mem::take(&mut self.recorders),
+    };
+    Ok(spawn_multi_stream(
+      self.camera.clone(),
+      self.fps,
+      self.color_space,
+      self.origin,
+      self.config.clone(),
+      sinks,
+    ))
+  }
+}
+
+impl StreamBuilder {
+  pub fn new(
+    camera: SharedCamera,
+    fps: u32,
+    color_space: ColorSpace,
+    origin: ImageOrigin,
+    config: StreamConfig,
+  ) -> Self {
+    Self {
+      camera,
+      fps,
+      color_space,
+      origin,
+      config,
+      frame_sinks: Vec::new(),
+      mjpeg_sinks: Vec::new(),
+      recorders: Vec::new(),
+    }
+  }
+}
+
+/// Outputs registered on a `StreamBuilder`
+struct Sinks {
+  frames: Vec<StreamCallback<Frame>>,
+  mjpeg: Vec<(StreamCallback<Buffer>, u32)>,
+  recorders: Vec<Y4mWriter<BufWriter<File>>>,
+}
+
+/// Spawns the capture thread behind `StreamBuilder::start`
+/// Each callback sink gets its own delivery queue, so one slow callback drops
+/// its own frames without holding back the others or the recording.
+fn spawn_multi_stream(
+  camera: SharedCamera,
+  fps: u32,
+  color_space: ColorSpace,
+  origin: ImageOrigin,
+  config: StreamConfig,
+  sinks: Sinks,
+) -> StreamHandle {
+  let running = Arc::new(AtomicBool::new(true));
+  let thread_running = running.clone();
+  let paused = Arc::new(AtomicBool::new(false));
+  let thread_paused = paused.clone();
+  let disconnected = Arc::new(AtomicBool::new(false));
+  let thread_disconnected = disconnected.clone();
+  let last_error = Arc::new(Mutex::new(None));
+  let thread_last_error = last_error.clone();
+  let dropped = Arc::new(AtomicU32::new(0));
+  let frame_interval = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+
+  let Sinks {
+    frames: frame_sinks,
+    mjpeg: mjpeg_sinks,
+    mut recorders,
+  } = sinks;
+  let mut frame_queues: Vec<_> = frame_sinks
+    .into_iter()
+    .map(|callback| (DeliveryQueue::new(&config, dropped.clone()), callback))
+    .collect();
+  let mut mjpeg_queues: Vec<_> = mjpeg_sinks
+    .into_iter()
+    .map(|(callback, quality)| {
+      (
+        DeliveryQueue::new(&config, dropped.clone()),
+        callback,
+        quality,
+      )
+    })
+    .collect();
+
+  let thread = thread::spawn(move || {
+    apply_thread_config(&config);
+    let block = config.queue_policy == Some(QueuePolicy::Block);
+    let deliver_every = config.deliver_every_nth.unwrap_or(1).max(1) as u64;
+    let mut captured: u64 = 0;
+    let mut failures = FailureCount::default();
+
+    while thread_running.load(Ordering::SeqCst) {
+      if thread_paused.load(Ordering::SeqCst) {
+        wait_while_paused(&thread_paused, &thread_running);
+        continue;
+      }
+      let mut status = Status::Ok;
+      if block {
+        // Hold off capturing until every callback has made room
+        while thread_running.load(Ordering::SeqCst)
+          && (frame_queues.iter().any(|(queue, _)| queue.is_full())
+            || mjpeg_queues.iter().any(|(queue, _, _)| queue.is_full()))
+        {
+          status = flush_all(&mut frame_queues, &mut mjpeg_queues);
+          if status == Status::Closing {
+            break;
+          }
+          thread::sleep(Duration::from_millis(1));
+        }
+      }
+
+      let started = Instant::now();
+
+      let decimated = !captured.is_multiple_of(deliver_every);
+      captured += 1;
+      // Pulled even when decimated, to keep the device drained
+      let buffer = (|| -> anyhow::Result<_> {
+        let mut cam = camera.lock()?;
+        let buffer = next_buffer(&mut cam)?;
+        let resolution = cam.resolution();
+        Ok((buffer, resolution.width(), resolution.height()))
+      })();
+      if let Some(error) = failures.record(&buffer) {
+        record_error(&thread_last_error, &error);
+        thread_disconnected.store(true, Ordering::SeqCst);
+        for_each_callback(&frame_queues, &mjpeg_queues, &error);
+        break;
+      }
+      if decimated {
+        if camera.is_closed() {
+          break;
+        }
+        thread::sleep(frame_interval.saturating_sub(started.elapsed()));
+        continue;
+      }
+
+      let delivered = buffer.and_then(|(buffer, width, height)| {
+        deliver_to_sinks(
+          &buffer,
+          (width, height),
+          color_space,
+          origin,
+          &mut frame_queues,
+          &mut mjpeg_queues,
+          &mut recorders,
+        )
+      });
+      if let Err(e) = delivered {
+        // Errors are small and rare, so they skip the queues
+        let error = anyhow_to_coded(e);
+        record_error(&thread_last_error, &error);
+        for_each_callback(&frame_queues, &mjpeg_queues, &error);
+      }
+      if flush_all(&mut frame_queues, &mut mjpeg_queues) == Status::Closing {
+        status = Status::Closing;
+      }
+
+      // Stop once the camera has been released or the JS side is shutting down
+      if camera.is_closed() || status == Status::Closing {
+        break;
+      }
+
+      thread::sleep(frame_interval.saturating_sub(started.elapsed()));
+    }
+
+    for recorder in recorders {
+      if let Err(e) = recorder.finish() {
+        record_error(&thread_last_error, &anyhow_to_coded(e));
+      }
+    }
+    thread_running.store(false, Ordering::SeqCst);
+  });
+
+  StreamHandle {
+    running,
+    paused,
+    disconnected,
+    last_error,
+    dropped,
+    thread: Some(thread),
+  }
+}
+
+/// Decodes one captured buffer (only if some sink needs pixels) and queues it for every sink
+fn deliver_to_sinks(
+  buffer: &nokhwa::Buffer,
+  (width, height): (u32, u32),
+  color_space: ColorSpace,
+  origin: ImageOrigin,
+  frame_queues: &mut [(DeliveryQueue<Frame>, StreamCallback<Frame>)],
+  mjpeg_queues: &mut [(DeliveryQueue<Buffer>, StreamCallback<Buffer>, u32)],
+  recorders: &mut [Y4mWriter<BufWriter<File>>],
+) -> anyhow::Result<()> {
+  let passthrough = buffer.source_frame_format() == FrameFormat::MJPEG;
+  let needs_pixels =
+    !frame_queues.is_empty() || !recorders.is_empty() || (!passthrough && !mjpeg_queues.is_empty());
+  let mut frame = if needs_pixels {
+    Some(decode_to_rgba(buffer, width, height, color_space)?)
+  } else {
+    None
+  };
+
+  for (queue, _, quality) in mjpeg_queues.iter_mut() {
+    let jpeg = match &frame {
+      Some(frame) if !passthrough => encode_jpeg(
+        &rgba_to_rgb(&frame.data),
+        frame.width,
+        frame.height,
+        *quality,
+      )?,
+      _ => buffer.buffer().to_vec(),
+    };
+    queue.push(Buffer::from(jpeg));
+  }
+
+  if let Some(frame) = frame.as_mut() {
+    for recorder in recorders.iter_mut() {
+      recorder.write_frame(&frame.data, frame.width, frame.height)?;
+    }
+    if origin == ImageOrigin::BottomLeft {
+      flip_vertical(&mut frame.data, frame.width, frame.height, 4);
+    }
+  }
+
+  if let Some(frame) = frame {
+    let (last, rest) = match frame_queues.split_last_mut() {
+      Some(split) => split,
+      None => return Ok(()),
+    };
+    for (queue, _) in rest {
+      queue.push(convert_to_napi_frame(RgbaFrame {
+        data: frame.data.clone(),
+        width: frame.width,
+        height: frame.height,
+        timestamp_ms: frame.timestamp_ms,
+      })?);
+    }
+    // The last sink takes the decoded pixels without another copy
+    last.0.push(convert_to_napi_frame(frame)?);
+  }
+  Ok(())
+}
+
+/// Passes a copy of `error` to every callback sink
+fn for_each_callback(
+  frame_queues: &[(DeliveryQueue<Frame>, StreamCallback<Frame>)],
+  mjpeg_queues: &[(DeliveryQueue<Buffer>, StreamCallback<Buffer>, u32)],
+  error: &Error<ErrorCode>,
+) {
+  let copy = || coded_error(error.status, &error.reason);
+  for (_, callback) in frame_queues {
+    callback.call(Err(copy()), ThreadsafeFunctionCallMode::NonBlocking);
+  }
+  for (_, callback, _) in mjpeg_queues {
+    callback.call(Err(copy()), ThreadsafeFunctionCallMode::NonBlocking);
+  }
+}
+
+/// Hands the next queued frame to every callback sink that is ready for one
+fn flush_all(
+  frame_queues: &mut [(DeliveryQueue<Frame>, StreamCallback<Frame>)],
+  mjpeg_queues: &mut [(DeliveryQueue<Buffer>, StreamCallback<Buffer>, u32)],
+) -> Status {
+  let mut status = Status::Ok;
+  for (queue, callback) in frame_queues.iter_mut() {
+    if queue.flush(callback) == Status::Closing {
+      status = Status::Closing;
+    }
+  }
+  for (queue, callback, _) in mjpeg_queues.iter_mut() {
+    if queue.flush(callback) == Status::Closing {
+      status = Status::Closing;
+    }
+  }
+  status
+}
+
+// ============================================================================
+// Frame Iterator
+// ============================================================================
+
+/// Captured frame or the capture error, as sent from the capture thread
+type FrameMessage = std::result::Result<RgbaFrame, Error<ErrorCode>>;
+
+/// How often a pending `next()` checks whether its iterator was stopped
+const ITERATOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Pull-based frame stream backed by a native capture thread
+/// `next()` resolves to the next frame, or `null` once the iterator is stopped.
+#[napi]
+pub struct FrameIterator {
+  running: Arc<AtomicBool>,
+  receiver: Arc<Mutex<Receiver<FrameMessage>>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+#[napi]
+impl FrameIterator {
+  /// Wait for the next frame without blocking the event loop
+  /// Resolves to `null` after `stop()` or once the camera has been released;
+  /// capture errors reject the promise with their `err.code` but don't end the
+  /// iteration.
+  #[napi(ts_return_type = "Promise<Frame | null>")]
+  pub fn next(&self) -> AsyncTask<NextFrameTask> {
+    AsyncTask::new(NextFrameTask {
+      running: self.running.clone(),
+      receiver: self.receiver.clone(),
+      failure: None,
+    })
+  }
+
+  /// Stop the capture thread; later `next()` calls resolve to `null`
+  #[napi]
+  pub fn stop(&mut self) {
+    halt_frame_iterator(&self.running, &self.receiver);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+
+  /// Check if the iterator is still producing frames
+  #[napi]
+  pub fn is_running(&self) -> bool {
+    self.running.load(Ordering::SeqCst)
+  }
+}
+
+impl Drop for FrameIterator {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+/// Tells the capture thread of a `FrameIterator` to exit
+fn halt_frame_iterator(running: &AtomicBool, receiver: &Mutex<Receiver<FrameMessage>>) {
+  running.store(false, Ordering::SeqCst);
+  // The thread may be blocked handing over a frame, free the slot so it can exit
+  if let Ok(receiver) = receiver.try_lock() {
+    while receiver.try_recv().is_ok() {}
+  }
+}
+
+/// Adds `[Symbol.asyncIterator]()` to a `FrameIterator`, so it works with `for await`
+/// napi classes can't declare symbol-keyed methods, so the method is set on
+/// the instance. `next()` itself keeps resolving to `Frame | null`.
+pub fn into_async_iterable(
+  env: &Env,
+  iterator: FrameIterator,
+) -> Result<ClassInstance<'_, FrameIterator>> {
+  let (running, receiver) = (iterator.running.clone(), iterator.receiver.clone());
+  let instance = iterator.into_instance(env)?;
+  let symbol: Object = env.get_global()?.get_named_property_unchecked("Symbol")?;
+  let key: Unknown = symbol.get_named_property("asyncIterator")?;
+  let method =
+    env.create_function_from_closure::<(), _, _>("[Symbol.asyncIterator]", move |_| {
+      Ok(AsyncFrameIterator {
+        running: running.clone(),
+        receiver: receiver.clone(),
+      })
+    })?;
+  instance.as_object(env).set_property(key, method)?;
+  Ok(instance)
+}
+
+/// Step of a `for await` loop over a `FrameIterator`
+#[napi(object)]
+pub struct FrameIteratorResult {
+  pub value: Option<Frame>,
+  pub done: bool,
+}
+
+/// Async iterator returned by `FrameIterator[Symbol.asyncIterator]()`
+/// Shares the capture thread of its `FrameIterator`; leaving the loop early
+/// (`break`, `return` or a throw) stops that thread.
+#[napi]
+pub struct AsyncFrameIterator {
+  running: Arc<AtomicBool>,
+  receiver: Arc<Mutex<Receiver<FrameMessage>>>,
+}
+
+#[napi]
+impl AsyncFrameIterator {
+  /// Wait for the next frame; `done` is set once the capture thread has stopped
+  #[napi(ts_return_type = "Promise<FrameIteratorResult>")]
+  pub fn next(&self) -> AsyncTask<NextFrameResultTask> {
+    AsyncTask::new(NextFrameResultTask(NextFrameTask {
+      running: self.running.clone(),
+      receiver: self.receiver.clone(),
+      failure: None,
+    }))
+  }
+
+  /// Stop the capture thread, called by `for await` when the loop is left early
+  #[napi(js_name = "return")]
+  pub fn finish(&self) -> FrameIteratorResult {
+    halt_frame_iterator(&self.running, &self.receiver);
+    FrameIteratorResult {
+      value: None,
+      done: true,
+    }
+  }
+}
+
+/// Async task behind `FrameIterator.next()`
+pub struct NextFrameTask {
+  running: Arc<AtomicBool>,
+  receiver: Arc<Mutex<Receiver<FrameMessage>>>,
+  /// Coded capture error of a failed `compute`, thrown with its code by `reject`
+  failure: Option<Error<ErrorCode>>,
+}
+
+impl Task for NextFrameTask {
+  type Output = Option<RgbaFrame>;
+  type JsValue = Option<Frame>;
+
+  // Waits in short slices so a stalled device doesn't hold the pool thread
+  // past `stop()`, and releases the receiver between them for `halt_frame_iterator`
+  fn compute(&mut self) -> Result<Self::Output> {
+    loop {
+      let received = self
+        .receiver
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .recv_timeout(ITERATOR_POLL_INTERVAL);
+      match received {
+        Ok(Ok(frame)) => return Ok(Some(frame)),
+        Ok(Err(e)) => {
+          let plain = Error::from_reason(e.reason.clone());
+          self.failure = Some(e);
+          return Err(plain);
+        }
+        Err(RecvTimeoutError::Timeout) if self.running.load(Ordering::SeqCst) => {}
+        // Stopped, or the capture thread has exited
+        Err(_) => return Ok(None),
+      }
+    }
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    output.map(convert_to_napi_frame).transpose()
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_coded(env, err, self.failure.take())
+  }
+}
+
+/// Async task behind `AsyncFrameIterator.next()`, wrapping the frame in an iterator result
+pub struct NextFrameResultTask(NextFrameTask);
+
+impl Task for NextFrameResultTask {
+  type Output = Option<RgbaFrame>;
+  type JsValue = FrameIteratorResult;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    self.0.compute()
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let value = self.0.resolve(env, output)?;
+    Ok(FrameIteratorResult {
+      done: value.is_none(),
+      value,
+    })
+  }
+
+  fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+    reject_coded(env, err, self.0.failure.take())
+  }
+}
+
+/// Spawns a thread that decodes frames into a one-slot channel for `FrameIterator`
+/// The slot gives natural backpressure: the thread captures the next frame only
+/// after the previous one has been taken.
+pub fn spawn_frame_iterator(
+  camera: SharedCamera,
+  color_space: ColorSpace,
+  origin: ImageOrigin,
+) -> FrameIterator {
+  let running = Arc::new(AtomicBool::new(true));
+  let thread_running = running.clone();
+  let (sender, receiver) = mpsc::sync_channel::<FrameMessage>(1);
+
+  let thread = thread::spawn(move || {
+    while thread_running.load(Ordering::SeqCst) {
+      let message = camera
+        .lock()
+        .and_then(|mut cam| capture_frame(&mut cam, color_space))
+        .map(|mut frame| {
+          if origin == ImageOrigin::BottomLeft {
+            flip_vertical(&mut frame.data, frame.width, frame.height, 4);
+          }
+          frame
+        })
+        .map_err(anyhow_to_coded);
+
+      // Stop once the camera is gone or nobody is listening anymore
+      if camera.is_closed() || sender.send(message).is_err() {
+        break;
+      }
+    }
+    thread_running.store(false, Ordering::SeqCst);
+  });
+
+  FrameIterator {
+    running,
+    receiver: Arc::new(Mutex::new(receiver)),
+    thread: Some(thread),
+  }
+}
+
+// ============================================================================
+// Thread Configuration
+// ============================================================================
+
+/// Invalid stream setting, thrown before the capture thread is spawned
+fn invalid_config(message: impl ToString) -> Error {
+  Error::new(Status::InvalidArg, message.to_string())
+}
+
+/// Rejects thread settings that can't be applied, before a capture thread is spawned
+pub fn check_stream_config(config: &StreamConfig) -> Result<()> {
+  if config.thread_priority.is_some() && cfg!(not(any(unix, windows))) {
+    return Err(invalid_config(
+      "Thread priorities are not supported on this platform",
+    ));
+  }
+
+  let cores = config.cpu_affinity.as_deref().unwrap_or_default();
+  let [core] = cores else {
+    return match cores {
+      [] => Ok(()),
+      _ => Err(invalid_config(format!(
+        "Cannot pin the capture thread to {} cores: give exactly one core",
+        cores.len()
+      ))),
+    };
+  };
+  // macOS only takes affinity tags as scheduling hints, it can't pin a thread
+  let available = match core_affinity::get_core_ids() {
+    Some(ids) if !cfg!(target_os = "macos") => ids,
+    _ => {
+      return Err(invalid_config(
+        "CPU affinity is not supported on this platform",
+      ))
+    }
+  };
+  if !available.iter().any(|id| id.id == *core as usize) {
+    return Err(invalid_config(format!(
+      "CPU core {} doesn't exist on this machine",
+      core
+    )));
+  }
+  Ok(())
+}
+
+/// Applies priority and affinity settings to the calling thread
+/// `check_stream_config` has rejected what the platform can't do; the rest is
+/// best effort, so e.g. missing rights for `High` leave the default in place.
+fn apply_thread_config(config: &StreamConfig) {
+  #[cfg(any(unix, windows))]
+  if let Some(priority) = config.thread_priority {
+    let _ = thread_priority::set_current_thread_priority(os_thread_priority(priority));
+  }
+  if let Some(&[core]) = config.cpu_affinity.as_deref() {
+    core_affinity::set_for_current(core_affinity::CoreId { id: core as usize });
+  }
+}
+
+/// Maps a `ThreadPriority` onto the crate's 0-99 scale
+/// The values land on nice 9, 0 and -13 with Linux's default scheduler, and on
+/// BELOW_NORMAL, NORMAL and HIGHEST on Windows.
+#[cfg(any(unix, windows))]
+fn os_thread_priority(priority: ThreadPriority) -> thread_priority::ThreadPriority {
+  let value: u8 = match priority {
+    ThreadPriority::Low => 25,
+    ThreadPriority::Normal => 47,
+    ThreadPriority::High => 80,
+  };
+  thread_priority::ThreadPriority::Crossplatform(value.try_into().expect("priority is within 0-99"))
+}
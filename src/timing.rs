@@ -0,0 +1,238 @@
+//! Timing module for nokhwa-node
+//!
+//! This module provides the precise sleeping used to hit wall-clock capture times,
+//! the frame rate measurement behind `Camera::status`, the capture-rate benchmark
+//! and the exact frame rate reported by the driver.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+
+use crate::types::{FpsBenchmark, FrameInterval};
+
+/// How far in the past a scheduled capture time may be before it is rejected
+pub const SCHEDULE_TOLERANCE: Duration = Duration::from_millis(50);
+
+/// Remaining time that is spun instead of slept, to absorb OS scheduler jitter
+const SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// Current wall-clock time in Unix milliseconds, 0 if the clock is before the epoch
+pub fn unix_time_ms() -> f64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs_f64() * 1000.0)
+    .unwrap_or(0.0)
+}
+
+/// Runs `work` on a helper thread and waits at most `timeout` for its result
+/// On a timeout the thread keeps running; whatever it returns later is dropped.
+/// `Disconnected` means `work` panicked.
+pub fn run_with_timeout<T: Send + 'static>(
+  timeout: Duration,
+  work: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, RecvTimeoutError> {
+  let (sender, receiver) = mpsc::channel();
+  thread::spawn(move || {
+    let _ = sender.send(work());
+  });
+  receiver.recv_timeout(timeout)
+}
+
+/// Sleeps until `deadline` with sub-millisecond accuracy
+/// The bulk of the wait uses a regular sleep, the last few milliseconds are spun.
+pub fn sleep_until(deadline: Instant) {
+  loop {
+    let now = Instant::now();
+    if now >= deadline {
+      return;
+    }
+    let remaining = deadline - now;
+    if remaining > SPIN_THRESHOLD {
+      thread::sleep(remaining - SPIN_THRESHOLD);
+    } else {
+      thread::yield_now();
+    }
+  }
+}
+
+/// Converts a Unix timestamp in milliseconds into a monotonic deadline
+/// Fails if the timestamp lies further in the past than `SCHEDULE_TOLERANCE`.
+pub fn deadline_from_unix_ms(unix_ms: f64) -> anyhow::Result<Instant> {
+  if !unix_ms.is_finite() || unix_ms < 0.0 {
+    return Err(anyhow!("Invalid capture time: {}", unix_ms));
+  }
+
+  let now_ms = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_err(|e| anyhow!("System clock is before the Unix epoch: {}", e))?
+    .as_secs_f64()
+    * 1000.0;
+  let delta_ms = unix_ms - now_ms;
+
+  if delta_ms >= 0.0 {
+    return Ok(Instant::now() + Duration::from_secs_f64(delta_ms / 1000.0));
+  }
+  if -delta_ms > SCHEDULE_TOLERANCE.as_secs_f64() * 1000.0 {
+    return Err(anyhow!(
+      "Capture time is {:.1}ms in the past (tolerance {}ms)",
+      -delta_ms,
+      SCHEDULE_TOLERANCE.as_millis()
+    ));
+  }
+  Ok(Instant::now())
+}
+
+/// Gap between captures after which the measured rate starts over
+const FPS_IDLE_RESET: Duration = Duration::from_secs(2);
+
+/// Weight of the newest frame interval in the smoothed rate
+const FPS_SMOOTHING: f64 = 0.2;
+
+/// Smoothed rate at which frames are being captured
+#[derive(Default)]
+pub struct FpsMeter {
+  last_frame: Option<Instant>,
+  interval: Option<f64>,
+}
+
+impl FpsMeter {
+  /// Records that a frame was just captured
+  pub fn tick(&mut self) {
+    let now = Instant::now();
+    if let Some(last) = self.last_frame {
+      let elapsed = now - last;
+      if elapsed > FPS_IDLE_RESET {
+        self.interval = None;
+      } else {
+        let secs = elapsed.as_secs_f64();
+        self.interval = Some(match self.interval {
+          Some(interval) => interval + FPS_SMOOTHING * (secs - interval),
+          None => secs,
+        });
+      }
+    }
+    self.last_frame = Some(now);
+  }
+
+  /// Frames per second, or `None` until two frames have been captured close together
+  pub fn fps(&self) -> Option<f64> {
+    match (self.interval, self.last_frame) {
+      (Some(interval), Some(last)) if interval > 0.0 && last.elapsed() <= FPS_IDLE_RESET => {
+        Some(1.0 / interval)
+      }
+      _ => None,
+    }
+  }
+}
+
+/// Pulls frames as fast as the device delivers them for `duration`
+/// Frames aren't decoded, so this measures the device and transport, not the
+/// decoder. The rate is taken between the first and last frame, leaving out the
+/// latency before the first one; `dropped` counts the frames the advertised
+/// rate would have delivered in that time but didn't arrive.
+pub fn benchmark_fps(
+  camera: &mut nokhwa::Camera,
+  duration: Duration,
+) -> anyhow::Result<FpsBenchmark> {
+  let requested_fps = camera.frame_rate();
+  let deadline = Instant::now() + duration;
+  let mut frames = 0u32;
+  let mut first_frame = None;
+  let mut last_frame = Instant::now();
+
+  while Instant::now() < deadline {
+    camera
+      .frame_raw()
+      .map_err(|e| anyhow!("Capturing frame {}: {}", frames + 1, e))?;
+    last_frame = Instant::now();
+    first_frame.get_or_insert(last_frame);
+    frames += 1;
+  }
+
+  let span = first_frame.map_or(0.0, |first| (last_frame - first).as_secs_f64());
+  let measured_fps = if frames > 1 && span > 0.0 {
+    (frames - 1) as f64 / span
+  } else {
+    0.0
+  };
+  let expected = (requested_fps as f64 * span).floor() as u32 + 1;
+  Ok(FpsBenchmark {
+    requested_fps,
+    measured_fps,
+    frames,
+    dropped: expected.saturating_sub(frames),
+  })
+}
+
+/// V4L2_BUF_TYPE_VIDEO_CAPTURE
+#[cfg(target_os = "linux")]
+const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+
+/// VIDIOC_G_PARM, i.e. _IOWR('V', 21, struct v4l2_streamparm)
+#[cfg(target_os = "linux")]
+const VIDIOC_G_PARM: u32 = 0xc0cc_5615;
+
+/// `struct v4l2_streamparm` with the capture member of its 200-byte union
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct V4l2StreamParm {
+  buf_type: u32,
+  capability: u32,
+  capture_mode: u32,
+  /// Seconds per frame, i.e. the inverse of the frame rate
+  time_per_frame_numerator: u32,
+  time_per_frame_denominator: u32,
+  extended_mode: u32,
+  read_buffers: u32,
+  reserved: [u8; 176],
+}
+
+/// Reads the exact frame rate of a camera, falling back to its integer rate over 1
+/// Only Video4Linux reports the rational rate; nokhwa rounds it for the other
+/// backends, so those always take the fallback.
+pub fn frame_interval(camera: &nokhwa::Camera) -> FrameInterval {
+  #[cfg(target_os = "linux")]
+  if camera.backend() == nokhwa::utils::ApiBackend::Video4Linux {
+    if let Some(interval) = v4l2_frame_interval(camera.index()) {
+      return interval;
+    }
+  }
+  FrameInterval {
+    numerator: camera.frame_rate(),
+    denominator: 1,
+  }
+}
+
+/// Asks the driver for the active time per frame with VIDIOC_G_PARM
+/// This opens a second handle on the device node, which V4L2 allows while
+/// another handle is streaming. Returns `None` if the driver has no answer.
+#[cfg(target_os = "linux")]
+fn v4l2_frame_interval(index: &nokhwa::utils::CameraIndex) -> Option<FrameInterval> {
+  use std::os::fd::AsRawFd;
+
+  let path = match index {
+    nokhwa::utils::CameraIndex::Index(n) => format!("/dev/video{}", n),
+    nokhwa::utils::CameraIndex::String(s) if s.starts_with('/') => s.clone(),
+    nokhwa::utils::CameraIndex::String(s) => format!("/dev/video{}", s.parse::<u32>().ok()?),
+  };
+  let device = std::fs::OpenOptions::new()
+    .read(true)
+    .write(true)
+    .open(path)
+    .ok()?;
+
+  // SAFETY: V4l2StreamParm matches the kernel's struct v4l2_streamparm layout
+  // (204 bytes) and outlives the call, which only writes within it.
+  let mut parm: V4l2StreamParm = unsafe { std::mem::zeroed() };
+  parm.buf_type = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+  let ret = unsafe { libc::ioctl(device.as_raw_fd(), VIDIOC_G_PARM as _, &mut parm) };
+  if ret != 0 || parm.time_per_frame_numerator == 0 || parm.time_per_frame_denominator == 0 {
+    return None;
+  }
+  Some(FrameInterval {
+    numerator: parm.time_per_frame_denominator,
+    denominator: parm.time_per_frame_numerator,
+  })
+}
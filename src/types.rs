@@ -2,7 +2,11 @@
 //!
 //! This module contains all enum and struct type definitions used across the library.
 
+use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::stream::SlotView;
 
 // ============================================================================
 // Enums
@@ -16,11 +20,15 @@ pub enum ApiBackend {
   AVFoundation,
   OpenCv,
   Browser,
+  Video4Linux,
+  UniversalVideoClass,
+  GStreamer,
+  Network,
 }
 
 /// Frame format types supported by the camera
 #[napi(string_enum)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FrameFormat {
   MJPEG,
   YUYV,
@@ -31,7 +39,10 @@ pub enum FrameFormat {
 }
 
 /// Known camera control properties
+/// Mirrors every variant of nokhwa's `KnownCameraControl`; the conversions match
+/// exhaustively, so a new upstream variant fails to compile instead of collapsing.
 #[napi(string_enum)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KnownCameraControl {
   Brightness,
   Contrast,
@@ -48,11 +59,15 @@ pub enum KnownCameraControl {
   Exposure,
   Iris,
   Focus,
+  /// Vendor-specific control; its id travels separately as `otherId`
   Other,
 }
 
 /// Control value types for setting camera properties
+/// In control presets the value is stored bare, e.g. `128`, `0.5` or `true`.
 #[napi]
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
 pub enum ControlValueSetter {
   Integer(i64),
   Float(f64),
@@ -65,6 +80,121 @@ pub enum ControlValueSetter {
 pub enum RequestedFormatType {
   AbsoluteHighestResolution,
   AbsoluteHighestFrameRate,
+  /// No preference: take the first format the device lists, skipping the capability ranking
+  None,
+  /// The device format nearest to the config's width, height and frame rate
+  Closest,
+  /// A device format matching the config's width, height and frame rate, or an error
+  Exact,
+}
+
+/// YUV to RGB color matrix
+/// SD sources typically use BT.601, HD (720p and up) sources BT.709
+#[napi(string_enum)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+  #[default]
+  Bt601,
+  Bt709,
+}
+
+/// Where row 0 of a returned frame sits in the image
+/// Cameras deliver TopLeft; WebGL textures expect BottomLeft
+#[napi(string_enum)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ImageOrigin {
+  TopLeft,
+  BottomLeft,
+}
+
+/// OS scheduling priority for native capture threads
+#[napi(string_enum)]
+#[derive(Clone, Copy)]
+pub enum ThreadPriority {
+  Low,
+  Normal,
+  High,
+}
+
+/// Trade-off between latency and throughput for captures on a `Camera`
+#[napi(string_enum)]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum LatencyMode {
+  /// Return frames in the order the driver queued them (default)
+  #[default]
+  Balanced,
+  /// Skip frames that were already waiting in the driver queue and return the newest
+  LowestLatency,
+}
+
+/// Which camera `openBestCamera` picks when several are connected
+#[napi(string_enum)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CameraPreference {
+  /// Most pixels in the largest format, then the highest frame rate
+  HighestResolution,
+  /// Highest frame rate in any format, then the most pixels
+  HighestFrameRate,
+  /// USB cameras over built-in ones, then by resolution
+  ExternalFirst,
+}
+
+/// What a stream does with new frames while the JS callback is behind
+#[napi(string_enum)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum QueuePolicy {
+  /// Discard the oldest queued frame to make room (default, keeps latency low)
+  DropOldest,
+  /// Discard the frame that was just captured
+  DropNewest,
+  /// Stop capturing until the callback catches up
+  Block,
+}
+
+/// How interlaced frames are turned into progressive ones
+#[napi(string_enum)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DeinterlaceMode {
+  /// Line-double the top field; full height, half the vertical detail
+  Bob,
+  /// Average each line with the next; full height, softer motion
+  Blend,
+  /// Keep only the top field; the frame becomes half as tall
+  DiscardField,
+}
+
+/// One step of a camera's output pipeline, see `Camera::set_output_pipeline`
+#[napi(discriminant = "type")]
+#[derive(Clone)]
+pub enum FrameOp {
+  /// Scale to exactly `width`x`height` (triangle filter)
+  Resize {
+    width: u32,
+    height: u32,
+  },
+  /// Keep only the `width`x`height` region at (`x`, `y`)
+  Crop {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+  },
+  Flip {
+    horizontal: bool,
+    vertical: bool,
+  },
+  /// Rotate clockwise by 0, 90, 180 or 270 degrees
+  Rotate {
+    degrees: u32,
+  },
+  /// Convert to single-channel Rec.601 luma
+  Grayscale,
+  /// Encode as JPEG (must be the last step)
+  EncodeJpeg {
+    quality: u32,
+  },
+  /// Encode as PNG (must be the last step)
+  EncodePng,
 }
 
 // ============================================================================
@@ -87,23 +217,267 @@ pub struct CameraFormat {
   pub format: FrameFormat,
 }
 
+/// Frame rates a camera offers at one resolution, see `Camera::compatible_formats_grouped`
+#[napi(object)]
+pub struct ResolutionFrameRates {
+  pub resolution: Resolution,
+  /// Distinct frame rates, lowest first
+  pub frame_rates: Vec<u32>,
+}
+
+/// Resolutions a camera offers in one pixel format, see `Camera::compatible_formats_grouped`
+#[napi(object)]
+pub struct FormatGroup {
+  pub format: FrameFormat,
+  /// Distinct resolutions, highest pixel count first
+  pub resolutions: Vec<ResolutionFrameRates>,
+}
+
 /// Camera control descriptor
+/// The numeric fields are only set for integer and enum controls (enums have no
+/// range or step); read other kinds of values with `Camera::controls_snapshot`.
 #[napi(object)]
 pub struct CameraControl {
   pub name: String,
   pub control_type: String,
+  /// Vendor-specific control id, only set when the control is `Other`
+  pub other_id: Option<String>,
+  pub value: Option<i64>,
+  pub minimum: Option<i64>,
+  pub maximum: Option<i64>,
+  pub step: Option<i64>,
+  pub default: Option<i64>,
+  /// Whether the control currently has an effect (e.g. manual exposure while auto exposure is on)
+  pub active: bool,
+}
+
+/// Current value of a camera control
+#[napi(object)]
+pub struct ControlSnapshot {
+  pub name: String,
+  pub control: KnownCameraControl,
+  /// Vendor-specific control id, only set when `control` is `Other`
+  pub other_id: Option<String>,
+  pub value: ControlValueSetter,
+}
+
+/// One control to set in `Camera::set_camera_controls`
+#[napi(object)]
+pub struct ControlSetting {
+  pub control: KnownCameraControl,
+  pub value: ControlValueSetter,
+  /// Vendor-specific control id, required when `control` is `Other`
+  pub other_id: Option<String>,
+}
+
+/// Result of `Camera::import_controls`
+#[napi(object)]
+pub struct ControlsImport {
+  /// One entry per recognized control in the preset, as from `set_camera_controls`
+  pub applied: Vec<ControlSetResult>,
+  /// Preset entries that were skipped, e.g. controls this version doesn't know
+  pub warnings: Vec<String>,
+}
+
+/// Outcome of one entry of `Camera::set_camera_controls`
+#[napi(object)]
+pub struct ControlSetResult {
+  pub control: KnownCameraControl,
+  pub other_id: Option<String>,
+  pub ok: bool,
+  /// Why the control couldn't be set, null when `ok`
+  pub error: Option<String>,
 }
 
 /// Camera device information
 #[napi(object)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct CameraDevice {
   pub index: String,
   pub name: String,
 }
 
+/// Cameras plugged in and unplugged since the last event, see `watch_devices`
+#[napi(object)]
+pub struct DeviceChange {
+  pub added: Vec<CameraDevice>,
+  pub removed: Vec<CameraDevice>,
+}
+
+/// Camera device information with its current availability
+#[napi(object)]
+pub struct CameraDeviceStatus {
+  pub index: String,
+  pub name: String,
+  /// Whether the device could be opened, i.e. it isn't busy in another process
+  pub available: bool,
+}
+
+/// Camera device information with a small preview frame
+#[napi(object)]
+pub struct CameraThumbnail {
+  pub device: CameraDevice,
+  /// JPEG snapshot, null if the device was busy or failed to deliver a frame
+  pub thumbnail: Option<Buffer>,
+}
+
+/// Snapshot of a camera's identity, stream state and format
+#[napi(object)]
+pub struct CameraStatus {
+  pub index: String,
+  pub name: String,
+  pub backend: ApiBackend,
+  pub is_stream_open: bool,
+  /// Format the camera was configured with
+  pub current_format: CameraFormat,
+  /// Pixel format of the frames the device actually delivers
+  pub negotiated_pixel_format: FrameFormat,
+  /// Rate of recent captures through this camera object, null until two frames were captured
+  pub measured_fps: Option<f64>,
+}
+
+/// Exact frame rate as a fraction: `numerator / denominator` frames per second
+/// NTSC-style rates come out as e.g. 30000/1001 (29.97 fps).
+#[napi(object)]
+pub struct FrameInterval {
+  pub numerator: u32,
+  pub denominator: u32,
+}
+
+/// Result of `Camera::benchmark_fps`
+#[napi(object)]
+pub struct FpsBenchmark {
+  /// Frame rate the current format advertises
+  pub requested_fps: u32,
+  /// Rate frames actually arrived at
+  pub measured_fps: f64,
+  pub frames: u32,
+  /// Frames the advertised rate promised that never arrived
+  pub dropped: u32,
+}
+
+/// Result of `cameraSelfTest`
+#[napi(object)]
+pub struct SelfTestReport {
+  pub opened: bool,
+  pub frames_captured: u32,
+  /// Mean time to capture and decode one frame, null if no frame was captured
+  pub avg_capture_ms: Option<f64>,
+  pub backend: Option<ApiBackend>,
+  pub negotiated_format: Option<CameraFormat>,
+  /// Everything that went wrong or looked suspicious; empty when the camera is healthy
+  pub warnings: Vec<String>,
+}
+
+/// Retries for opening a camera that is busy, e.g. just released by another process
+#[napi(object)]
+#[derive(Clone)]
+pub struct RetryConfig {
+  /// Extra attempts after the first one
+  pub retries: u32,
+  /// Wait between attempts in milliseconds (default 200)
+  pub delay_ms: Option<u32>,
+}
+
 /// Requested format configuration
+/// Setting any of `width`, `height` or `frame_rate` asks for the device format
+/// closest to that target instead of ranking formats by `request_type`.
 #[napi(object)]
 pub struct RequestedFormatConfig {
   pub request_type: RequestedFormatType,
+  /// Pixel format; with a target only device formats of this source format are considered
   pub format: Option<FrameFormat>,
+  pub width: Option<u32>,
+  pub height: Option<u32>,
+  pub frame_rate: Option<u32>,
+}
+
+/// 8-bit RGB color
+#[napi(object)]
+pub struct RgbColor {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+}
+
+/// Color with the fraction of the frame it covers
+#[napi(object)]
+pub struct ColorWeight {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+  pub weight: f64,
+}
+
+/// Native thread settings for background streams
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct StreamConfig {
+  /// Scheduling priority of the capture thread (best effort, may need elevated rights for High)
+  pub thread_priority: Option<ThreadPriority>,
+  /// CPU core to pin the capture thread to, as a one-element list; not supported on macOS
+  pub cpu_affinity: Option<Vec<u32>>,
+  /// What to do with new frames when the queue for the callback is full (default DropOldest)
+  pub queue_policy: Option<QueuePolicy>,
+  /// Frames that may wait for the callback besides the one being delivered (default 2, minimum 1)
+  pub queue_depth: Option<u32>,
+  /// Deliver only every Nth captured frame; the others are discarded before decoding (default 1)
+  pub deliver_every_nth: Option<u32>,
+  /// Cap on the frames per second delivered by device-paced streams (`start_stream`,
+  /// `start_ring_stream`); frames in between are discarded before decoding. 0 or unset
+  /// is uncapped. Streams that take an `fps` argument pace themselves with that instead.
+  pub target_fps: Option<u32>,
+}
+
+/// Frame delivered by `Camera::start_ring_stream`
+/// `data` views the memory of ring slot `slot` and is detached once the callback
+/// returns: copy out whatever is needed while it runs.
+#[napi(object, object_from_js = false)]
+pub struct RingFrame {
+  pub slot: u32,
+  #[napi(ts_type = "Uint8Array")]
+  pub data: SlotView,
+  pub width: u32,
+  pub height: u32,
+  /// Measured capture rate, or the format's advertised rate for the first frames
+  pub fps: f64,
+  /// Wall-clock arrival time in Unix milliseconds, see `Frame::timestamp_ms`
+  pub timestamp_ms: f64,
+  /// Frames lost so far, counted as for `StreamFrame::dropped`
+  pub dropped: u32,
+  /// Frames this stream tried to deliver so far, see `StreamFrame::total`
+  pub total: u32,
+}
+
+/// Metadata embedded as EXIF tags in JPEG output; unset fields are left out
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct JpegMetadata {
+  /// Capture time in Unix milliseconds, written as DateTime in UTC
+  pub timestamp_ms: Option<f64>,
+  /// Written as the camera Model
+  pub camera_name: Option<String>,
+  /// EXIF orientation, 1 (upright) to 8
+  pub orientation: Option<u32>,
+}
+
+/// Mirroring and rotation applied to captured frames, see `Camera::set_transform`
+#[napi(object)]
+#[derive(Clone, Copy, Default)]
+pub struct FrameTransform {
+  pub flip_horizontal: bool,
+  pub flip_vertical: bool,
+  /// Clockwise rotation applied after the flips: 0, 90, 180 or 270 degrees
+  pub rotate: u32,
+}
+
+/// Most recent error reported by a background stream
+#[napi(object)]
+#[derive(Clone)]
+pub struct StreamErrorInfo {
+  /// Stable error code, same as `err.code` on thrown errors
+  pub code: String,
+  pub message: String,
+  /// When the error happened, in Unix milliseconds
+  pub timestamp_ms: f64,
 }
@@ -0,0 +1,130 @@
+//! Device watcher module for nokhwa-node
+//!
+//! This module polls the camera list on a background thread and reports the
+//! devices that were plugged in or unplugged since the last report.
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use napi::threadsafe_function::ThreadsafeFunctionCallMode;
+use napi_derive::napi;
+
+use crate::stream::StreamCallback;
+use crate::types::{CameraDevice, DeviceChange};
+
+/// Wait between re-reads while the device list is still changing
+const SETTLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Re-reads after which a list that keeps changing is reported anyway
+const MAX_SETTLE_READS: u32 = 8;
+
+/// Handle to a running device watcher, see `watch_devices`
+/// Dropping the handle stops the watcher as well
+#[napi]
+pub struct DeviceWatcher {
+  stop: Option<Sender<()>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+#[napi]
+impl DeviceWatcher {
+  /// Stop watching and wait for the polling thread to exit
+  #[napi]
+  pub fn stop(&mut self) {
+    // Dropping the sender wakes the thread from its wait right away
+    self.stop.take();
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+
+  /// Check if the watcher is still running
+  #[napi]
+  pub fn is_running(&self) -> bool {
+    self.thread.as_ref().is_some_and(|t| !t.is_finished())
+  }
+}
+
+impl Drop for DeviceWatcher {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+/// Reads the connected cameras, sorted so snapshots compare regardless of enumeration order
+pub fn query_devices() -> Result<Vec<CameraDevice>, nokhwa::NokhwaError> {
+  let mut devices: Vec<CameraDevice> = nokhwa::query(nokhwa::utils::ApiBackend::Auto)?
+    .into_iter()
+    .map(|cam| CameraDevice {
+      index: cam.index().as_string(),
+      name: cam.human_name(),
+    })
+    .collect();
+  devices.sort_by(|a, b| (&a.index, &a.name).cmp(&(&b.index, &b.name)));
+  Ok(devices)
+}
+
+/// Spawns the polling thread behind `watch_devices`, starting from the `initial` snapshot
+/// Once a poll sees a different list, the list is re-read until two reads agree,
+/// so a device that re-enumerates (vanishes and comes back within a moment)
+/// results in a single event, or none if it ends up unchanged. Failed queries
+/// are skipped.
+pub fn spawn_device_watcher(
+  initial: Vec<CameraDevice>,
+  interval: Duration,
+  callback: StreamCallback<DeviceChange>,
+) -> DeviceWatcher {
+  let (stop, stopped) = mpsc::channel::<()>();
+  // Waits for `timeout`, returning false once the watcher should stop
+  let wait = move |timeout: Duration| {
+    matches!(
+      stopped.recv_timeout(timeout),
+      Err(RecvTimeoutError::Timeout)
+    )
+  };
+
+  let thread = thread::spawn(move || {
+    let mut reported = initial;
+    while wait(interval) {
+      let Ok(mut current) = query_devices() else {
+        continue;
+      };
+      if current == reported {
+        continue;
+      }
+
+      let mut reads = 1;
+      while reads < MAX_SETTLE_READS {
+        if !wait(SETTLE_INTERVAL) {
+          return;
+        }
+        match query_devices() {
+          Ok(next) if next == current => break,
+          Ok(next) => current = next,
+          Err(_) => {}
+        }
+        reads += 1;
+      }
+
+      let change = DeviceChange {
+        added: difference(&current, &reported),
+        removed: difference(&reported, &current),
+      };
+      reported = current;
+      if !change.added.is_empty() || !change.removed.is_empty() {
+        callback.call(Ok(change), ThreadsafeFunctionCallMode::NonBlocking);
+      }
+    }
+  });
+
+  DeviceWatcher {
+    stop: Some(stop),
+    thread: Some(thread),
+  }
+}
+
+/// Devices in `a` that are not in `b`
+fn difference(a: &[CameraDevice], b: &[CameraDevice]) -> Vec<CameraDevice> {
+  a.iter().filter(|d| !b.contains(d)).cloned().collect()
+}
@@ -0,0 +1,369 @@
+//! YUV conversion module for nokhwa-node
+//!
+//! This module converts YUYV and NV12 frames to RGB with a selectable color
+//! matrix, since nokhwa's converters are hardwired to BT.601, extracts their
+//! luma as gray, converts between the two layouts directly in YUV space, and
+//! converts RGBA frames to planar I420 for video encoders.
+
+use anyhow::anyhow;
+
+use crate::types::ColorSpace;
+
+/// Limited-range YCbCr to RGB coefficients in 8.8 fixed point
+struct Matrix {
+  y: i32,
+  r_v: i32,
+  g_u: i32,
+  g_v: i32,
+  b_u: i32,
+}
+
+/// Same integer math as nokhwa's BT.601 converters, so results match exactly
+const BT601: Matrix = Matrix {
+  y: 298,
+  r_v: 409,
+  g_u: 100,
+  g_v: 208,
+  b_u: 516,
+};
+
+const BT709: Matrix = Matrix {
+  y: 298,
+  r_v: 459,
+  g_u: 55,
+  g_v: 136,
+  b_u: 541,
+};
+
+/// RGB to limited-range YCbCr coefficients in 8.8 fixed point, one row per output
+struct ForwardMatrix {
+  y: [i32; 3],
+  u: [i32; 3],
+  v: [i32; 3],
+}
+
+const BT601_FORWARD: ForwardMatrix = ForwardMatrix {
+  y: [66, 129, 25],
+  u: [-38, -74, 112],
+  v: [112, -94, -18],
+};
+
+const BT709_FORWARD: ForwardMatrix = ForwardMatrix {
+  y: [47, 157, 16],
+  u: [-26, -87, 113],
+  v: [112, -102, -10],
+};
+
+impl ColorSpace {
+  fn matrix(self) -> &'static Matrix {
+    match self {
+      ColorSpace::Bt601 => &BT601,
+      ColorSpace::Bt709 => &BT709,
+    }
+  }
+
+  fn forward_matrix(self) -> &'static ForwardMatrix {
+    match self {
+      ColorSpace::Bt601 => &BT601_FORWARD,
+      ColorSpace::Bt709 => &BT709_FORWARD,
+    }
+  }
+}
+
+#[inline]
+fn dot(coeffs: &[i32; 3], r: i32, g: i32, b: i32) -> i32 {
+  (coeffs[0] * r + coeffs[1] * g + coeffs[2] * b + 128) >> 8
+}
+
+#[inline]
+fn push_pixel(out: &mut Vec<u8>, m: &Matrix, y: u8, u: u8, v: u8, rgba: bool) {
+  let c = (y as i32 - 16) * m.y;
+  let d = u as i32 - 128;
+  let e = v as i32 - 128;
+  out.push(((c + m.r_v * e + 128) >> 8).clamp(0, 255) as u8);
+  out.push(((c - m.g_u * d - m.g_v * e + 128) >> 8).clamp(0, 255) as u8);
+  out.push(((c + m.b_u * d + 128) >> 8).clamp(0, 255) as u8);
+  if rgba {
+    out.push(255);
+  }
+}
+
+/// Expands a limited-range (16-235) luma sample to full-range gray
+/// Same scaling as the RGB conversions, so gray output matches their brightness.
+#[inline]
+fn full_range_luma(y: u8) -> u8 {
+  (((y as i32 - 16) * 298 + 128) >> 8).clamp(0, 255) as u8
+}
+
+/// Extracts the luma of a YUYV or NV12 frame as full-range gray, one byte per pixel
+/// Luma doesn't depend on the color matrix, so no color space is needed.
+pub fn luma_plane(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  interleaved: bool,
+) -> anyhow::Result<Vec<u8>> {
+  let pixels = width as usize * height as usize;
+  // YUYV interleaves luma with chroma, NV12 stores it as the first plane
+  let step = if interleaved { 2 } else { 1 };
+  let gray: Vec<u8> = data
+    .iter()
+    .step_by(step)
+    .take(pixels)
+    .map(|&y| full_range_luma(y))
+    .collect();
+  if gray.len() != pixels {
+    return Err(anyhow!(
+      "Invalid YUV buffer: {} bytes is too short for {}x{}",
+      data.len(),
+      width,
+      height
+    ));
+  }
+  Ok(gray)
+}
+
+/// Converts packed YUYV 4:2:2 to RGB (or RGBA)
+pub fn yuyv_to_rgb(data: &[u8], color_space: ColorSpace, rgba: bool) -> anyhow::Result<Vec<u8>> {
+  if !data.len().is_multiple_of(4) {
+    return Err(anyhow!(
+      "Invalid YUYV buffer: length {} is not a multiple of 4",
+      data.len()
+    ));
+  }
+
+  let m = color_space.matrix();
+  let pixel_size = if rgba { 4 } else { 3 };
+  let mut out = Vec::with_capacity(data.len() / 2 * pixel_size);
+  for px in data.chunks_exact(4) {
+    push_pixel(&mut out, m, px[0], px[1], px[3], rgba);
+    push_pixel(&mut out, m, px[2], px[1], px[3], rgba);
+  }
+  Ok(out)
+}
+
+/// Converts NV12 (Y plane followed by interleaved UV at quarter resolution) to RGB (or RGBA)
+pub fn nv12_to_rgb(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  color_space: ColorSpace,
+  rgba: bool,
+) -> anyhow::Result<Vec<u8>> {
+  let (width, height) = (width as usize, height as usize);
+  if !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+    return Err(anyhow!(
+      "Invalid NV12 resolution {}x{}: both dimensions must be even",
+      width,
+      height
+    ));
+  }
+  let luma_size = width * height;
+  if data.len() != luma_size * 3 / 2 {
+    return Err(anyhow!(
+      "Invalid NV12 buffer: expected {} bytes for {}x{}, got {}",
+      luma_size * 3 / 2,
+      width,
+      height,
+      data.len()
+    ));
+  }
+
+  let (luma, chroma) = data.split_at(luma_size);
+  let m = color_space.matrix();
+  let pixel_size = if rgba { 4 } else { 3 };
+  let mut out = Vec::with_capacity(luma_size * pixel_size);
+  for (row, line) in luma.chunks_exact(width).enumerate() {
+    let uv_line = &chroma[(row / 2) * width..(row / 2 + 1) * width];
+    for (x, y) in line.iter().enumerate() {
+      let uv = &uv_line[(x / 2) * 2..(x / 2) * 2 + 2];
+      push_pixel(&mut out, m, *y, uv[0], uv[1], rgba);
+    }
+  }
+  Ok(out)
+}
+
+/// Converts the `w`x`h` region at (`x`, `y`) of a YUYV frame to RGBA
+/// Only the pixels inside the region are read; the caller validates the bounds.
+pub fn yuyv_region_to_rgba(
+  data: &[u8],
+  width: u32,
+  x: u32,
+  y: u32,
+  w: u32,
+  h: u32,
+  color_space: ColorSpace,
+) -> Vec<u8> {
+  let m = color_space.matrix();
+  let stride = width as usize * 2;
+  let mut out = Vec::with_capacity(w as usize * h as usize * 4);
+  for row in y as usize..(y + h) as usize {
+    let line = &data[row * stride..(row + 1) * stride];
+    for col in x as usize..(x + w) as usize {
+      let pair = &line[(col / 2) * 4..(col / 2) * 4 + 4];
+      push_pixel(&mut out, m, pair[(col % 2) * 2], pair[1], pair[3], true);
+    }
+  }
+  out
+}
+
+/// Converts the `w`x`h` region at (`x`, `y`) of an NV12 frame to RGBA
+/// Only the pixels inside the region are read; the caller validates the bounds.
+#[allow(clippy::too_many_arguments)]
+pub fn nv12_region_to_rgba(
+  data: &[u8],
+  width: u32,
+  height: u32,
+  x: u32,
+  y: u32,
+  w: u32,
+  h: u32,
+  color_space: ColorSpace,
+) -> Vec<u8> {
+  let m = color_space.matrix();
+  let width = width as usize;
+  let (luma, chroma) = data.split_at(width * height as usize);
+  let mut out = Vec::with_capacity(w as usize * h as usize * 4);
+  for row in y as usize..(y + h) as usize {
+    let line = &luma[row * width..(row + 1) * width];
+    let uv_line = &chroma[(row / 2) * width..(row / 2 + 1) * width];
+    for col in x as usize..(x + w) as usize {
+      let uv = &uv_line[(col / 2) * 2..(col / 2) * 2 + 2];
+      push_pixel(&mut out, m, line[col], uv[0], uv[1], true);
+    }
+  }
+  out
+}
+
+/// Planar I420 (YUV 4:2:0) image: a full-size Y plane and quarter-size U and V planes
+pub struct I420Planes {
+  pub y: Vec<u8>,
+  pub u: Vec<u8>,
+  pub v: Vec<u8>,
+}
+
+/// Converts tightly packed RGBA to planar I420
+/// Chroma is the average of each 2x2 block, so both dimensions must be even.
+pub fn rgba_to_i420(
+  rgba: &[u8],
+  width: u32,
+  height: u32,
+  color_space: ColorSpace,
+) -> anyhow::Result<I420Planes> {
+  let (width, height) = (width as usize, height as usize);
+  if width == 0 || height == 0 || !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+    return Err(anyhow!(
+      "Invalid I420 resolution {}x{}: both dimensions must be even and non-zero",
+      width,
+      height
+    ));
+  }
+  if rgba.len() != width * height * 4 {
+    return Err(anyhow!(
+      "Invalid RGBA buffer: expected {} bytes for {}x{}, got {}",
+      width * height * 4,
+      width,
+      height,
+      rgba.len()
+    ));
+  }
+
+  let m = color_space.forward_matrix();
+  let (chroma_width, chroma_height) = (width / 2, height / 2);
+  let mut y_plane = Vec::with_capacity(width * height);
+  for px in rgba.chunks_exact(4) {
+    let (r, g, b) = (px[0] as i32, px[1] as i32, px[2] as i32);
+    y_plane.push((dot(&m.y, r, g, b) + 16).clamp(0, 255) as u8);
+  }
+
+  let mut u_plane = Vec::with_capacity(chroma_width * chroma_height);
+  let mut v_plane = Vec::with_capacity(chroma_width * chroma_height);
+  for cy in 0..chroma_height {
+    for cx in 0..chroma_width {
+      let (mut r, mut g, mut b) = (0, 0, 0);
+      for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        let i = ((cy * 2 + dy) * width + cx * 2 + dx) * 4;
+        r += rgba[i] as i32;
+        g += rgba[i + 1] as i32;
+        b += rgba[i + 2] as i32;
+      }
+      let (r, g, b) = ((r + 2) / 4, (g + 2) / 4, (b + 2) / 4);
+      u_plane.push((dot(&m.u, r, g, b) + 128).clamp(0, 255) as u8);
+      v_plane.push((dot(&m.v, r, g, b) + 128).clamp(0, 255) as u8);
+    }
+  }
+
+  Ok(I420Planes {
+    y: y_plane,
+    u: u_plane,
+    v: v_plane,
+  })
+}
+
+/// Checks the resolution and buffer size of a 4:2:2 or 4:2:0 frame
+/// `double_bpp` is twice the bytes per pixel: 4 for YUYV, 3 for NV12.
+fn check_yuv_frame(
+  data: &[u8],
+  width: usize,
+  height: usize,
+  double_bpp: usize,
+  name: &str,
+) -> anyhow::Result<()> {
+  if width == 0 || height == 0 || !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+    return Err(anyhow!(
+      "Invalid {} resolution {}x{}: both dimensions must be even and non-zero",
+      name,
+      width,
+      height
+    ));
+  }
+  let expected = width * height * double_bpp / 2;
+  if data.len() != expected {
+    return Err(anyhow!(
+      "Invalid {} buffer: expected {} bytes for {}x{}, got {}",
+      name,
+      expected,
+      width,
+      height,
+      data.len()
+    ));
+  }
+  Ok(())
+}
+
+/// Converts YUYV (4:2:2) to NV12 (4:2:0) without going through RGB
+/// Luma is copied as is; the chroma of each pair of rows is averaged, so both
+/// dimensions must be even.
+pub fn yuyv_to_nv12(data: &[u8], width: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+  let (width, height) = (width as usize, height as usize);
+  check_yuv_frame(data, width, height, 4, "YUYV")?;
+
+  let stride = width * 2;
+  let mut out = Vec::with_capacity(width * height * 3 / 2);
+  out.extend(data.iter().step_by(2));
+  for rows in data.chunks_exact(stride * 2) {
+    let (top, bottom) = rows.split_at(stride);
+    for (a, b) in top.chunks_exact(4).zip(bottom.chunks_exact(4)) {
+      out.push((a[1] as u16 + b[1] as u16).div_ceil(2) as u8);
+      out.push((a[3] as u16 + b[3] as u16).div_ceil(2) as u8);
+    }
+  }
+  Ok(out)
+}
+
+/// Converts NV12 (4:2:0) to YUYV (4:2:2) without going through RGB
+/// Each chroma row is repeated for the two luma rows it covers.
+pub fn nv12_to_yuyv(data: &[u8], width: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+  let (width, height) = (width as usize, height as usize);
+  check_yuv_frame(data, width, height, 3, "NV12")?;
+
+  let (luma, chroma) = data.split_at(width * height);
+  let mut out = Vec::with_capacity(width * height * 2);
+  for (row, line) in luma.chunks_exact(width).enumerate() {
+    let uv = &chroma[row / 2 * width..][..width];
+    for (y, c) in line.chunks_exact(2).zip(uv.chunks_exact(2)) {
+      out.extend_from_slice(&[y[0], c[0], y[1], c[1]]);
+    }
+  }
+  Ok(out)
+}